@@ -0,0 +1,110 @@
+//! benchmarks for the generator and both parts of every day.
+//!
+//! the inputs are in `benches/inputs/day<N>/`. `realistic.txt` is about the
+//! size of an actual puzzle input, `stress.txt` is a lot bigger to make
+//! regressions in the slow days (e.g. day 11's 10000 rounds or day 15's scan
+//! over 4000000 rows) easier to spot.
+
+use std::{
+    fs,
+    path::PathBuf,
+    time::Duration,
+};
+
+use aoc2022::{
+    day1::Day1,
+    day10::Day10,
+    day11::Day11,
+    day12::Day12,
+    day13::Day13,
+    day14::Day14,
+    day15::Day15,
+    day2::Day2,
+    day3::Day3,
+    day4::Day4,
+    day5::Day5,
+    day6::Day6,
+    day7::Day7,
+    day8::Day8,
+    day9::Day9,
+    solution::Solution,
+};
+use criterion::{
+    black_box,
+    criterion_group,
+    criterion_main,
+    BenchmarkId,
+    Criterion,
+};
+
+const SIZES: [&str; 2] = ["realistic", "stress"];
+
+fn load_input(day: u8, size: &str) -> String {
+    let path: PathBuf = [
+        env!("CARGO_MANIFEST_DIR"),
+        "benches",
+        "inputs",
+        &format!("day{}", day),
+        &format!("{}.txt", size),
+    ]
+    .iter()
+    .collect();
+
+    let mut input = fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("failed to read {}: {}", path.display(), e));
+
+    // same as cargo-aoc
+    input.truncate(input.trim_end_matches('\n').len());
+
+    input
+}
+
+/// benchmarks the generator and both parts of `S` for every input size.
+///
+/// `sample_size` can be lowered for days that take seconds per iteration.
+fn bench_day<S: Solution>(c: &mut Criterion, sample_size: usize) {
+    let mut group = c.benchmark_group(format!("day{}", S::DAY));
+    group.sample_size(sample_size);
+    if sample_size < 100 {
+        group.measurement_time(Duration::from_secs(20));
+    }
+
+    for size in SIZES {
+        let input = load_input(S::DAY, size);
+        let parsed = S::parse(&input)
+            .unwrap_or_else(|e| panic!("failed to parse day {} {} input: {}", S::DAY, size, e));
+
+        group.bench_with_input(BenchmarkId::new("parse", size), &input, |b, input| {
+            b.iter(|| S::parse(black_box(input)))
+        });
+        group.bench_with_input(BenchmarkId::new("part1", size), &parsed, |b, parsed| {
+            b.iter(|| S::part1(black_box(parsed)))
+        });
+        group.bench_with_input(BenchmarkId::new("part2", size), &parsed, |b, parsed| {
+            b.iter(|| S::part2(black_box(parsed)))
+        });
+    }
+
+    group.finish();
+}
+
+fn days(c: &mut Criterion) {
+    bench_day::<Day1>(c, 100);
+    bench_day::<Day2>(c, 100);
+    bench_day::<Day3>(c, 100);
+    bench_day::<Day4>(c, 100);
+    bench_day::<Day5>(c, 100);
+    bench_day::<Day6>(c, 100);
+    bench_day::<Day7>(c, 100);
+    bench_day::<Day8>(c, 100);
+    bench_day::<Day9>(c, 100);
+    bench_day::<Day10>(c, 100);
+    bench_day::<Day11>(c, 10);
+    bench_day::<Day12>(c, 100);
+    bench_day::<Day13>(c, 100);
+    bench_day::<Day14>(c, 10);
+    bench_day::<Day15>(c, 10);
+}
+
+criterion_group!(benches, days);
+criterion_main!(benches);
@@ -0,0 +1,146 @@
+"""tests for the python bindings, run with `pytest` after `maturin develop`."""
+
+import pytest
+
+import aoc2022
+from aoc2022 import day1, day10, day11, day12, day15
+
+CALORIES = "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000"
+
+MONKEYS = """\
+Monkey 0:
+  Starting items: 79, 98
+  Operation: new = old * 19
+  Test: divisible by 23
+    If true: throw to monkey 2
+    If false: throw to monkey 3
+
+Monkey 1:
+  Starting items: 54, 65, 75, 74
+  Operation: new = old + 6
+  Test: divisible by 19
+    If true: throw to monkey 2
+    If false: throw to monkey 0
+
+Monkey 2:
+  Starting items: 79, 60, 97
+  Operation: new = old * old
+  Test: divisible by 13
+    If true: throw to monkey 1
+    If false: throw to monkey 3
+
+Monkey 3:
+  Starting items: 74
+  Operation: new = old + 3
+  Test: divisible by 17
+    If true: throw to monkey 0
+    If false: throw to monkey 1"""
+
+HEIGHT_MAP = "Sabqponm\nabcryxxl\naccszExk\nacctuvwj\nabdefghi"
+
+SENSORS = """\
+Sensor at x=2, y=18: closest beacon is at x=-2, y=15
+Sensor at x=9, y=16: closest beacon is at x=10, y=16
+Sensor at x=13, y=2: closest beacon is at x=15, y=3
+Sensor at x=12, y=14: closest beacon is at x=10, y=16
+Sensor at x=10, y=20: closest beacon is at x=10, y=16
+Sensor at x=14, y=17: closest beacon is at x=10, y=16
+Sensor at x=8, y=7: closest beacon is at x=2, y=10
+Sensor at x=2, y=0: closest beacon is at x=2, y=10
+Sensor at x=0, y=11: closest beacon is at x=2, y=10
+Sensor at x=20, y=14: closest beacon is at x=25, y=17
+Sensor at x=17, y=20: closest beacon is at x=21, y=22
+Sensor at x=16, y=7: closest beacon is at x=15, y=3
+Sensor at x=14, y=3: closest beacon is at x=15, y=3
+Sensor at x=20, y=1: closest beacon is at x=15, y=3"""
+
+
+def test_days():
+    assert aoc2022.days() == list(range(1, 16))
+
+
+def test_solve():
+    assert aoc2022.solve(1, CALORIES + "\n", 1) == "24000"
+    assert aoc2022.solve(1, CALORIES, 2) == "45000"
+    with pytest.raises(aoc2022.AocError, match="day 30"):
+        aoc2022.solve(30, "", 1)
+
+
+def test_parse_and_parts():
+    calories = day1.parse(CALORIES)
+    assert isinstance(calories, day1.Input)
+    assert day1.part1(calories) == 24000
+    assert day1.part2(calories) == 45000
+
+
+def test_errors():
+    with pytest.raises(aoc2022.AocError, match="line 3, column 1"):
+        day1.parse("1000\n\n20x0")
+    with pytest.raises(ValueError):
+        day1.part2(day1.parse("1000"))
+
+
+def test_submodules_can_be_imported():
+    import aoc2022.day12
+
+    assert aoc2022.day12 is day12
+
+
+def test_cpu():
+    cpu = day10.Cpu()
+    assert (cpu.cycle, cpu.x) == (1, 1)
+
+    cpu.execute("addx 3")
+    cpu.execute("noop")
+    assert (cpu.cycle, cpu.x) == (4, 4)
+    assert cpu.screen.splitlines()[0].startswith("##..")
+
+    cpu.run("addx -5\nnoop")
+    assert (cpu.cycle, cpu.x) == (7, -1)
+    assert cpu.signal_strength == 0
+
+    with pytest.raises(aoc2022.AocError):
+        cpu.execute("jmp 3")
+
+
+def test_monkeys():
+    monkeys = day11.parse(MONKEYS)
+    assert len(monkeys) == 4
+    assert monkeys.items[1] == [54, 65, 75, 74]
+
+    monkeys.round()
+    assert monkeys.items == [[20, 23, 27, 26], [2080, 25, 167, 207, 401, 1046], [], []]
+
+    for _ in range(19):
+        monkeys.round(relief=True)
+    assert monkeys.inspect_counts == [101, 95, 7, 105]
+    assert monkeys.monkey_business() == 10605
+
+    assert day11.part1(day11.parse(MONKEYS)) == 10605
+
+
+def test_height_map():
+    height_map = day12.parse(HEIGHT_MAP)
+    assert (height_map.width, height_map.height) == (8, 5)
+    assert height_map.start == (0, 0)
+    assert height_map.best_signal == (5, 2)
+    assert height_map.elevation((5, 2)) == 25
+    assert sorted(height_map.neighbors((1, 1))) == [(0, 1), (1, 0), (1, 2), (2, 1)]
+
+    path = height_map.shortest_path_to_best_signal()
+    assert (path[0], path[-1], len(path) - 1) == ((0, 0), (5, 2), 31)
+    assert len(height_map.shortest_path_from_lowest_elevation()) - 1 == 29
+    assert day12.part1(height_map) == 31
+
+    with pytest.raises(IndexError):
+        height_map.neighbors((8, 0))
+
+
+def test_sensors():
+    sensors = day15.parse(SENSORS)
+    assert len(sensors) == 14
+    assert sensors.sensors[0] == ((2, 18), (-2, 15))
+
+    assert sensors.covered_positions_for_row(10) == [range(-2, 25)]
+    assert sensors.num_covered_positions_for_row(10) == 26
+    assert sensors.find_distress_signal(20) == (14, 11)
@@ -0,0 +1,261 @@
+//! known answers for regression checking.
+//!
+//! the answers are stored in a TOML file, with a table per day and input
+//! hash (see [`input::hash`](crate::input::hash)):
+//!
+//! ```toml
+//! [day1.3b2c...]
+//! part1 = "69528"
+//! part2 = "206152"
+//! ```
+
+use std::{
+    collections::{
+        btree_map::Entry,
+        BTreeMap,
+    },
+    fmt,
+    fs,
+    io,
+    path::Path,
+};
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use thiserror::Error;
+
+use crate::runner::Part;
+
+#[derive(Debug, Error)]
+pub enum AnswersError {
+    #[error("io error")]
+    Io(#[from] io::Error),
+    #[error("failed to parse answers")]
+    Parse(#[from] toml::de::Error),
+    #[error("failed to serialize answers")]
+    Serialize(#[from] toml::ser::Error),
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct PartAnswers {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    part1: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    part2: Option<String>,
+}
+
+impl PartAnswers {
+    fn get(&self, part: Part) -> Option<&String> {
+        match part {
+            Part::Part1 => self.part1.as_ref(),
+            Part::Part2 => self.part2.as_ref(),
+        }
+    }
+
+    fn get_mut(&mut self, part: Part) -> &mut Option<String> {
+        match part {
+            Part::Part1 => &mut self.part1,
+            Part::Part2 => &mut self.part2,
+        }
+    }
+}
+
+/// answers keyed by day, input hash and part.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Answers(BTreeMap<String, BTreeMap<String, PartAnswers>>);
+
+impl Answers {
+    /// loads answers from a file. a file that doesn't exist is treated as
+    /// empty.
+    pub fn load(path: &Path) -> Result<Self, AnswersError> {
+        match fs::read_to_string(path) {
+            Ok(s) => s.parse(),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), AnswersError> {
+        fs::write(path, toml::to_string(self)?)?;
+        Ok(())
+    }
+
+    pub fn get(&self, day: u8, input_hash: &str, part: Part) -> Option<&str> {
+        self.0
+            .get(&day_key(day))?
+            .get(input_hash)?
+            .get(part)
+            .map(String::as_str)
+    }
+
+    /// stores an answer and returns the answer it replaced.
+    pub fn insert(
+        &mut self,
+        day: u8,
+        input_hash: &str,
+        part: Part,
+        answer: String,
+    ) -> Option<String> {
+        self.0
+            .entry(day_key(day))
+            .or_default()
+            .entry(input_hash.to_owned())
+            .or_default()
+            .get_mut(part)
+            .replace(answer)
+    }
+
+    /// adds an empty table for a day, as a placeholder for its answers.
+    /// returns `false` if the day already has one.
+    pub fn add_day(&mut self, day: u8) -> bool {
+        match self.0.entry(day_key(day)) {
+            Entry::Occupied(_) => false,
+            Entry::Vacant(vacant) => {
+                vacant.insert(BTreeMap::new());
+                true
+            }
+        }
+    }
+
+    /// compares an answer with the known one.
+    pub fn check(&self, day: u8, input_hash: &str, part: Part, answer: &str) -> Verdict {
+        match self.get(day, input_hash, part) {
+            None => Verdict::Missing,
+            Some(expected) if expected == answer => Verdict::Pass,
+            Some(expected) => {
+                Verdict::Fail {
+                    expected: expected.to_owned(),
+                }
+            }
+        }
+    }
+}
+
+impl std::str::FromStr for Answers {
+    type Err = AnswersError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(toml::from_str(s)?)
+    }
+}
+
+fn day_key(day: u8) -> String {
+    format!("day{}", day)
+}
+
+/// result of comparing an answer with the answers file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: String },
+    Missing,
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Pass => write!(f, "PASS"),
+            Verdict::Fail { .. } => write!(f, "FAIL"),
+            Verdict::Missing => write!(f, "MISSING"),
+        }
+    }
+}
+
+/// returns a line by line diff of two answers, with `-` for expected and `+`
+/// for actual lines. lines that are the same are indented instead.
+///
+/// answers are mostly single numbers, but day 10's screen spans several lines.
+pub fn diff(expected: &str, actual: &str) -> String {
+    let expected = expected.lines().collect::<Vec<_>>();
+    let actual = actual.lines().collect::<Vec<_>>();
+    let mut diff = String::new();
+
+    for i in 0..expected.len().max(actual.len()) {
+        match (expected.get(i), actual.get(i)) {
+            (Some(expected), Some(actual)) if expected == actual => {
+                diff.push_str(&format!("  {}\n", expected));
+            }
+            (expected, actual) => {
+                if let Some(expected) = expected {
+                    diff.push_str(&format!("- {}\n", expected));
+                }
+                if let Some(actual) = actual {
+                    diff.push_str(&format!("+ {}\n", actual));
+                }
+            }
+        }
+    }
+
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HASH: &str = "ab12";
+
+    #[test]
+    fn check() {
+        let mut answers = Answers::default();
+        answers.insert(1, HASH, Part::Part1, "24000".to_owned());
+
+        assert_eq!(answers.check(1, HASH, Part::Part1, "24000"), Verdict::Pass);
+        assert_eq!(
+            answers.check(1, HASH, Part::Part1, "45000"),
+            Verdict::Fail {
+                expected: "24000".to_owned()
+            }
+        );
+        assert_eq!(
+            answers.check(1, HASH, Part::Part2, "45000"),
+            Verdict::Missing
+        );
+        assert_eq!(
+            answers.check(1, "cd34", Part::Part1, "24000"),
+            Verdict::Missing
+        );
+        assert_eq!(
+            answers.check(2, HASH, Part::Part1, "24000"),
+            Verdict::Missing
+        );
+    }
+
+    #[test]
+    fn toml_round_trip() {
+        let mut answers = Answers::default();
+        answers.insert(1, HASH, Part::Part1, "24000".to_owned());
+        answers.insert(10, HASH, Part::Part2, "\n##..\n..##\n".to_owned());
+
+        let toml = toml::to_string(&answers).unwrap();
+        assert!(toml.contains("[day1.ab12]\npart1 = \"24000\"\n"));
+        assert_eq!(toml.parse::<Answers>().unwrap(), answers);
+    }
+
+    #[test]
+    fn insert_replaces() {
+        let mut answers = Answers::default();
+        assert_eq!(answers.insert(1, HASH, Part::Part1, "1".to_owned()), None);
+        assert_eq!(
+            answers.insert(1, HASH, Part::Part1, "2".to_owned()),
+            Some("1".to_owned())
+        );
+        assert_eq!(answers.get(1, HASH, Part::Part1), Some("2"));
+    }
+
+    #[test]
+    fn add_day_placeholder() {
+        let mut answers = Answers::default();
+        assert!(answers.add_day(16));
+        assert!(!answers.add_day(16));
+        assert_eq!(toml::to_string(&answers).unwrap(), "[day16]\n");
+    }
+
+    #[test]
+    fn diff_lines() {
+        assert_eq!(diff("24000", "45000"), "- 24000\n+ 45000\n");
+        assert_eq!(diff("#.\n.#", "#.\n##\n.."), "  #.\n- .#\n+ ##\n+ ..\n");
+    }
+}
@@ -0,0 +1,539 @@
+//! checkpoints of the long-running simulations, so that they can be stopped
+//! after some steps, inspected and resumed later.
+//!
+//! a checkpoint holds everything needed to continue: the state of the
+//! simulation, the instructions it follows and the number of steps taken so
+//! far. days 9, 10, 11 and 14 are [`Simulation`]s, day 5 is stepped here.
+//! what a step is depends on the day:
+//!
+//! | day | state                  | step                 |
+//! |-----|------------------------|----------------------|
+//! | 5   | [`Stacks`]             | one move of crates   |
+//! | 9   | [`RopeSimulation`]     | one step of the head |
+//! | 10  | [`CpuSimulation`]      | one instruction      |
+//! | 11  | [`MonkeySimulation`]   | one round            |
+//! | 14  | [`Sandbox`]            | one grain of sand    |
+//!
+//! checkpoints are saved as JSON (`.json`), which is easy to inspect, or in a
+//! compact binary format (`.bin`).
+
+use std::{
+    fs,
+    io,
+    path::Path,
+};
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use thiserror::Error;
+
+#[cfg(feature = "day10")]
+use crate::day10::{
+    self,
+    CpuSimulation,
+};
+#[cfg(feature = "day11")]
+use crate::day11::{
+    self,
+    MonkeySimulation,
+};
+#[cfg(feature = "day14")]
+use crate::day14::{
+    self,
+    Sandbox,
+};
+#[cfg(feature = "day5")]
+use crate::day5::{
+    self,
+    Move,
+    Stacks,
+};
+#[cfg(feature = "day9")]
+use crate::day9::{
+    self,
+    RopeSimulation,
+};
+#[cfg(any(
+    feature = "day9",
+    feature = "day10",
+    feature = "day11",
+    feature = "day14"
+))]
+use crate::simulation::Simulation;
+use crate::{
+    error,
+    runner::Part,
+};
+
+/// the days that can be checkpointed. only days that are enabled with their
+/// feature are included.
+pub const DAYS: &[u8] = &[
+    #[cfg(feature = "day5")]
+    5,
+    #[cfg(feature = "day9")]
+    9,
+    #[cfg(feature = "day10")]
+    10,
+    #[cfg(feature = "day11")]
+    11,
+    #[cfg(feature = "day14")]
+    14,
+];
+
+#[derive(Debug, Error)]
+pub enum CheckpointError {
+    #[error("io error")]
+    Io(#[from] io::Error),
+    #[error("failed to encode or decode JSON")]
+    Json(#[from] serde_json::Error),
+    #[error("failed to encode or decode binary checkpoint")]
+    Binary(#[from] postcard::Error),
+    #[error("unknown checkpoint format: {0}")]
+    UnknownFormat(String),
+    #[error("day {0} has no simulation to checkpoint")]
+    UnknownDay(u8),
+    #[error("failed to parse input for day {day}")]
+    Parse {
+        day: u8,
+        #[source]
+        source: error::Error,
+    },
+    #[error("step {step} of day {day} failed")]
+    Step {
+        day: u8,
+        step: usize,
+        #[source]
+        source: error::Error,
+    },
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CheckpointFormat {
+    Json,
+    Binary,
+}
+
+impl CheckpointFormat {
+    /// derives the format from the extension of `path`.
+    pub fn from_path(path: &Path) -> Result<Self, CheckpointError> {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or_default();
+        match extension {
+            "json" => Ok(Self::Json),
+            "bin" => Ok(Self::Binary),
+            _ => Err(CheckpointError::UnknownFormat(path.display().to_string())),
+        }
+    }
+}
+
+/// the state of a simulation, together with the instructions it follows.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum State {
+    #[cfg(feature = "day5")]
+    Day5 { stacks: Stacks, moves: Vec<Move> },
+    #[cfg(feature = "day9")]
+    Day9(RopeSimulation),
+    #[cfg(feature = "day10")]
+    Day10(Box<CpuSimulation>),
+    #[cfg(feature = "day11")]
+    Day11(MonkeySimulation),
+    #[cfg(feature = "day14")]
+    Day14(Sandbox),
+}
+
+/// a simulation after some number of steps.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Checkpoint {
+    part: Part,
+    steps: usize,
+    state: State,
+}
+
+impl Checkpoint {
+    /// parses `input` and sets up the simulation of `part` of `day`.
+    pub fn start(day: u8, part: Part, input: &str) -> Result<Self, CheckpointError> {
+        let parse_error = |source| CheckpointError::Parse { day, source };
+
+        let state = match day {
+            #[cfg(feature = "day5")]
+            5 => {
+                let input = day5::day5_input(input).map_err(parse_error)?;
+                State::Day5 {
+                    stacks: input.stacks().clone(),
+                    moves: input.moves().to_vec(),
+                }
+            }
+            #[cfg(feature = "day9")]
+            9 => {
+                let length = match part {
+                    Part::Part1 => 2,
+                    Part::Part2 => 10,
+                };
+                let movements = day9::day9_input(input).map_err(parse_error)?;
+                State::Day9(RopeSimulation::new(length, movements))
+            }
+            #[cfg(feature = "day10")]
+            10 => {
+                let program = day10::day10_input(input).map_err(parse_error)?;
+                State::Day10(Box::new(CpuSimulation::new(program)))
+            }
+            #[cfg(feature = "day11")]
+            11 => {
+                let monkeys = day11::day11_input(input).map_err(parse_error)?;
+                State::Day11(MonkeySimulation::new(monkeys, part == Part::Part1))
+            }
+            #[cfg(feature = "day14")]
+            14 => {
+                let rock_paths = day14::day14_input(input).map_err(parse_error)?;
+                State::Day14(Sandbox::from_rock_paths(&rock_paths, part == Part::Part2))
+            }
+            _ => return Err(CheckpointError::UnknownDay(day)),
+        };
+
+        Ok(Self {
+            part,
+            steps: 0,
+            state,
+        })
+    }
+
+    pub fn day(&self) -> u8 {
+        match self.state {
+            #[cfg(feature = "day5")]
+            State::Day5 { .. } => 5,
+            #[cfg(feature = "day9")]
+            State::Day9(_) => 9,
+            #[cfg(feature = "day10")]
+            State::Day10(_) => 10,
+            #[cfg(feature = "day11")]
+            State::Day11(_) => 11,
+            #[cfg(feature = "day14")]
+            State::Day14(_) => 14,
+        }
+    }
+
+    pub fn part(&self) -> Part {
+        self.part
+    }
+
+    /// the number of steps taken so far.
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    pub fn state(&self) -> &State {
+        &self.state
+    }
+
+    pub fn is_finished(&self) -> bool {
+        match &self.state {
+            #[cfg(feature = "day5")]
+            State::Day5 { moves, .. } => self.steps >= moves.len(),
+            #[cfg(feature = "day9")]
+            State::Day9(simulation) => simulation.is_finished(),
+            #[cfg(feature = "day10")]
+            State::Day10(simulation) => simulation.is_finished(),
+            #[cfg(feature = "day11")]
+            State::Day11(simulation) => simulation.is_finished(),
+            #[cfg(feature = "day14")]
+            State::Day14(sandbox) => sandbox.is_finished(),
+        }
+    }
+
+    /// takes the next step. returns `false` without taking a step if the
+    /// simulation is finished.
+    pub fn step(&mut self) -> Result<bool, CheckpointError> {
+        if self.is_finished() {
+            return Ok(false);
+        }
+
+        let stepped = match &mut self.state {
+            #[cfg(feature = "day5")]
+            State::Day5 { stacks, moves } => {
+                let step = self.steps;
+                let result = match self.part {
+                    Part::Part1 => moves[step].execute_9000(stacks),
+                    Part::Part2 => moves[step].execute_9001(stacks),
+                };
+                result.map_err(|source| {
+                    CheckpointError::Step {
+                        day: 5,
+                        step,
+                        source,
+                    }
+                })?;
+                true
+            }
+            #[cfg(feature = "day9")]
+            State::Day9(simulation) => {
+                simulation.try_step().map_err(|source| {
+                    CheckpointError::Step {
+                        day: 9,
+                        step: self.steps,
+                        source,
+                    }
+                })?
+            }
+            #[cfg(feature = "day10")]
+            State::Day10(simulation) => simulation.step(),
+            #[cfg(feature = "day11")]
+            State::Day11(simulation) => simulation.step(),
+            #[cfg(feature = "day14")]
+            State::Day14(sandbox) => sandbox.step(),
+        };
+
+        if stepped {
+            self.steps += 1;
+        }
+        Ok(stepped)
+    }
+
+    /// takes up to `max_steps` steps, or runs until the simulation is finished
+    /// if it's `None`. returns the number of steps taken.
+    pub fn run(&mut self, max_steps: Option<usize>) -> Result<usize, CheckpointError> {
+        let mut steps = 0;
+        while max_steps.is_none_or(|max_steps| steps < max_steps) && self.step()? {
+            steps += 1;
+        }
+        Ok(steps)
+    }
+
+    /// returns the answer of the part, once the simulation is finished.
+    pub fn answer(&self) -> Option<String> {
+        if !self.is_finished() {
+            return None;
+        }
+
+        let answer = match &self.state {
+            #[cfg(feature = "day5")]
+            State::Day5 { stacks, .. } => stacks.top_crates(),
+            #[cfg(feature = "day9")]
+            State::Day9(simulation) => simulation.rope().num_tail_positions().to_string(),
+            #[cfg(feature = "day10")]
+            State::Day10(simulation) => {
+                let cpu = simulation.cpu();
+                match self.part {
+                    Part::Part1 => cpu.signal_strength().to_string(),
+                    // starts on a new line, like the answer of the solver.
+                    Part::Part2 => format!("\n{}", cpu.frame_buffer()),
+                }
+            }
+            #[cfg(feature = "day11")]
+            State::Day11(simulation) => simulation.monkeys().monkey_business().to_string(),
+            // every step is a grain that came to rest.
+            #[cfg(feature = "day14")]
+            State::Day14(sandbox) => sandbox.steps().to_string(),
+        };
+
+        Some(answer)
+    }
+
+    pub fn to_bytes(&self, format: CheckpointFormat) -> Result<Vec<u8>, CheckpointError> {
+        let bytes = match format {
+            CheckpointFormat::Json => serde_json::to_vec_pretty(self)?,
+            CheckpointFormat::Binary => postcard::to_allocvec(self)?,
+        };
+        Ok(bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8], format: CheckpointFormat) -> Result<Self, CheckpointError> {
+        let checkpoint = match format {
+            CheckpointFormat::Json => serde_json::from_slice(bytes)?,
+            CheckpointFormat::Binary => postcard::from_bytes(bytes)?,
+        };
+        Ok(checkpoint)
+    }
+
+    /// writes the checkpoint to a file. the format is derived from the file
+    /// extension.
+    pub fn save(&self, path: &Path) -> Result<(), CheckpointError> {
+        let format = CheckpointFormat::from_path(path)?;
+        fs::write(path, self.to_bytes(format)?)?;
+        Ok(())
+    }
+
+    /// reads a checkpoint from a file. the format is derived from the file
+    /// extension.
+    pub fn load(path: &Path) -> Result<Self, CheckpointError> {
+        let format = CheckpointFormat::from_path(path)?;
+        Self::from_bytes(&fs::read(path)?, format)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(all(feature = "generate", feature = "day11"))]
+    use std::path::PathBuf;
+
+    use super::*;
+    #[cfg(feature = "generate")]
+    use crate::{
+        generate,
+        solution,
+    };
+
+    #[cfg(all(feature = "generate", feature = "day11"))]
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "aoc2022-checkpoint-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// stops every part after a few steps, resumes it from `format` and checks
+    /// that it ends with the answer of the solver.
+    #[cfg(feature = "generate")]
+    fn check_resume(format: CheckpointFormat) {
+        for &day in DAYS {
+            let input = generate::generate(day, 1, None).unwrap();
+            let solver = solution::solver(day).unwrap();
+
+            for part in Part::ALL {
+                let day_run = solver.run(&input, &[part]).unwrap();
+
+                let mut checkpoint = Checkpoint::start(day, part, &input).unwrap();
+                assert_eq!(checkpoint.run(Some(5)).unwrap(), 5);
+                assert_eq!(checkpoint.answer(), None);
+
+                let bytes = checkpoint.to_bytes(format).unwrap();
+                let mut resumed = Checkpoint::from_bytes(&bytes, format).unwrap();
+                assert_eq!((resumed.day(), resumed.part()), (day, part));
+                assert_eq!(resumed.steps(), 5);
+
+                resumed.run(None).unwrap();
+                assert!(!resumed.step().unwrap());
+                assert_eq!(
+                    resumed.answer().as_ref(),
+                    Some(&day_run.parts[0].answer),
+                    "day {} - {}",
+                    day,
+                    part
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "generate")]
+    fn resume_json() {
+        check_resume(CheckpointFormat::Json);
+    }
+
+    #[test]
+    #[cfg(feature = "generate")]
+    fn resume_binary() {
+        check_resume(CheckpointFormat::Binary);
+    }
+
+    #[test]
+    #[cfg(all(feature = "generate", feature = "day11"))]
+    fn save_and_load() {
+        let dir = temp_dir("save");
+        let input = generate::generate(11, 1, None).unwrap();
+        let mut checkpoint = Checkpoint::start(11, Part::Part2, &input).unwrap();
+        checkpoint.run(Some(100)).unwrap();
+
+        for name in ["monkeys.json", "monkeys.bin"] {
+            checkpoint.save(&dir.join(name)).unwrap();
+            let loaded = Checkpoint::load(&dir.join(name)).unwrap();
+            assert_eq!(loaded.steps(), 100);
+        }
+
+        // the binary format is the compact one
+        let json = fs::metadata(dir.join("monkeys.json")).unwrap().len();
+        let binary = fs::metadata(dir.join("monkeys.bin")).unwrap().len();
+        assert!(binary < json);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "day11")]
+    const MONKEYS: &str = "Monkey 0:
+  Starting items: 79, 98
+  Operation: new = old * 19
+  Test: divisible by 23
+    If true: throw to monkey 1
+    If false: throw to monkey 1
+
+Monkey 1:
+  Starting items: 54
+  Operation: new = old + old
+  Test: divisible by 19
+    If true: throw to monkey 0
+    If false: throw to monkey 0";
+
+    #[test]
+    #[cfg(feature = "day11")]
+    fn readable_json() {
+        let checkpoint = Checkpoint::start(11, Part::Part1, MONKEYS).unwrap();
+        let json = String::from_utf8(checkpoint.to_bytes(CheckpointFormat::Json).unwrap()).unwrap();
+
+        assert!(json.contains(r#""items": ["#));
+        assert!(json.contains(r#""79","#));
+        assert!(json.contains(r#""Constant": "19""#));
+        assert!(json.contains(r#""divisible_by": "23""#));
+    }
+
+    #[test]
+    #[cfg(feature = "day11")]
+    fn invalid_monkeys() {
+        let checkpoint = Checkpoint::start(11, Part::Part1, MONKEYS).unwrap();
+        let json = String::from_utf8(checkpoint.to_bytes(CheckpointFormat::Json).unwrap()).unwrap();
+        let json = json.replace(r#""true_monkey": 1"#, r#""true_monkey": 2"#);
+
+        assert!(matches!(
+            Checkpoint::from_bytes(json.as_bytes(), CheckpointFormat::Json),
+            Err(CheckpointError::Json(_))
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "day5")]
+    fn failing_step() {
+        let input = "[A] [B]
+ 1   2
+
+move 2 from 1 to 2";
+        let mut checkpoint = Checkpoint::start(5, Part::Part1, input).unwrap();
+        assert!(matches!(
+            checkpoint.run(None),
+            Err(CheckpointError::Step {
+                day: 5,
+                step: 0,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn unknown_day() {
+        assert!(matches!(
+            Checkpoint::start(1, Part::Part1, "1"),
+            Err(CheckpointError::UnknownDay(1))
+        ));
+    }
+
+    #[test]
+    fn format_from_path() {
+        assert_eq!(
+            CheckpointFormat::from_path(Path::new("a/b.json")).unwrap(),
+            CheckpointFormat::Json
+        );
+        assert_eq!(
+            CheckpointFormat::from_path(Path::new("b.bin")).unwrap(),
+            CheckpointFormat::Binary
+        );
+        assert!(matches!(
+            CheckpointFormat::from_path(Path::new("b")),
+            Err(CheckpointError::UnknownFormat(_))
+        ));
+    }
+}
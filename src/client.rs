@@ -0,0 +1,739 @@
+//! downloads puzzle inputs and submits answers.
+//!
+//! the base URL is configurable, so that everything can be tested against a
+//! local server instead of adventofcode.com. inputs are cached in the input
+//! directory, and every submission is stored in a history file. the history
+//! is used to not submit the same wrong answer twice, and to wait out rate
+//! limits without asking the server.
+
+use std::{
+    fmt,
+    fs,
+    io,
+    path::{
+        Path,
+        PathBuf,
+    },
+    thread,
+    time::{
+        Duration,
+        SystemTime,
+        UNIX_EPOCH,
+    },
+};
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use thiserror::Error;
+
+use crate::runner::Part;
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+const YEAR: u16 = 2022;
+
+/// identifies the client to the server, as requested by the AoC automation
+/// guidelines.
+const USER_AGENT: &str = concat!(
+    env!("CARGO_PKG_NAME"),
+    "/",
+    env!("CARGO_PKG_VERSION"),
+    " (",
+    env!("CARGO_PKG_AUTHORS"),
+    ")"
+);
+
+lazy_static! {
+    static ref ARTICLE_REGEX: Regex = r"(?s)<article[^>]*>(.*?)</article>".parse().unwrap();
+    static ref TAG_REGEX: Regex = r"<[^>]*>".parse().unwrap();
+    static ref TIME_LEFT_REGEX: Regex =
+        r"You have (?:(\d+)m )?(\d+)s left to wait".parse().unwrap();
+    static ref PENALTY_REGEX: Regex = r"(?i)wait (one|\d+) minutes? before trying again"
+        .parse()
+        .unwrap();
+}
+
+#[derive(Debug, Error)]
+pub enum ClientError {
+    #[error("io error")]
+    Io(#[from] io::Error),
+    #[error("http request failed")]
+    Http(#[from] Box<ureq::Error>),
+    #[error("server responded with status {status}: {message}")]
+    Status { status: u16, message: String },
+    #[error("failed to parse submission history")]
+    ParseHistory(#[from] toml::de::Error),
+    #[error("failed to serialize submission history")]
+    SerializeHistory(#[from] toml::ser::Error),
+    #[error("unexpected response: {0}")]
+    UnexpectedResponse(String),
+}
+
+/// what the server said about a submitted answer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SubmitOutcome {
+    Correct,
+    TooHigh,
+    TooLow,
+    Incorrect,
+    /// an answer was submitted too recently. the answer wasn't checked.
+    TooSoon,
+    /// the part is either solved already or not unlocked yet.
+    WrongLevel,
+}
+
+impl fmt::Display for SubmitOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Self::Correct => "correct",
+            Self::TooHigh => "too high",
+            Self::TooLow => "too low",
+            Self::Incorrect => "incorrect",
+            Self::TooSoon => "too soon",
+            Self::WrongLevel => "wrong level",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl SubmitOutcome {
+    /// returns whether the server actually checked the answer.
+    pub fn is_verdict(&self) -> bool {
+        matches!(
+            self,
+            Self::Correct | Self::TooHigh | Self::TooLow | Self::Incorrect
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Submission {
+    pub day: u8,
+    pub part: u8,
+    pub answer: String,
+    /// unix time in seconds.
+    pub time: u64,
+    pub outcome: SubmitOutcome,
+    /// how long to wait before the next submission.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wait_seconds: Option<u64>,
+    /// the message of the server.
+    pub message: String,
+}
+
+/// all submissions so far, oldest first.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubmissionHistory {
+    #[serde(rename = "submission", default)]
+    pub submissions: Vec<Submission>,
+}
+
+impl SubmissionHistory {
+    /// loads the history from a file. a file that doesn't exist is treated as
+    /// empty.
+    pub fn load(path: &Path) -> Result<Self, ClientError> {
+        match fs::read_to_string(path) {
+            Ok(s) => Ok(toml::from_str(&s)?),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), ClientError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, toml::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// returns the unix time until which the server won't accept answers.
+    pub fn wait_until(&self) -> Option<u64> {
+        self.submissions
+            .iter()
+            .filter_map(|submission| Some(submission.time + submission.wait_seconds?))
+            .max()
+    }
+
+    /// returns the submission that solved a part.
+    pub fn solved(&self, day: u8, part: Part) -> Option<&Submission> {
+        self.submissions.iter().find(|submission| {
+            submission.day == day
+                && submission.part == part.number()
+                && submission.outcome == SubmitOutcome::Correct
+        })
+    }
+
+    /// returns an earlier submission of the same answer that was checked by
+    /// the server.
+    pub fn find(&self, day: u8, part: Part, answer: &str) -> Option<&Submission> {
+        self.submissions.iter().find(|submission| {
+            submission.day == day
+                && submission.part == part.number()
+                && submission.answer == answer
+                && submission.outcome.is_verdict()
+        })
+    }
+}
+
+/// result of [`Client::submit`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SubmitResult {
+    /// the answer was sent to the server.
+    Submitted(Submission),
+    /// the part is already solved, or the same answer was submitted before.
+    /// nothing was sent.
+    Known(Submission),
+    /// the server won't accept answers for this much longer. nothing was
+    /// sent.
+    Wait(Duration),
+}
+
+pub struct Client {
+    agent: ureq::Agent,
+    base_url: String,
+    session: String,
+    input_dir: PathBuf,
+    history_path: PathBuf,
+}
+
+impl Client {
+    /// `input_dir` is where inputs are cached as `day<N>.txt`, and
+    /// `history_path` is the file the submissions are stored in.
+    pub fn new(
+        base_url: &str,
+        session: &str,
+        input_dir: impl Into<PathBuf>,
+        history_path: impl Into<PathBuf>,
+    ) -> Self {
+        Self {
+            agent: ureq::AgentBuilder::new()
+                .user_agent(USER_AGENT)
+                .timeout(Duration::from_secs(30))
+                .build(),
+            base_url: base_url.trim_end_matches('/').to_owned(),
+            session: session.to_owned(),
+            input_dir: input_dir.into(),
+            history_path: history_path.into(),
+        }
+    }
+
+    pub fn input_path(&self, day: u8) -> PathBuf {
+        self.input_dir.join(format!("day{}.txt", day))
+    }
+
+    /// returns the input of a day. it's only downloaded if it isn't cached yet
+    /// or `refresh` is set.
+    pub fn fetch_input(&self, day: u8, refresh: bool) -> Result<String, ClientError> {
+        let path = self.input_path(day);
+
+        if !refresh {
+            match fs::read_to_string(&path) {
+                Ok(input) => return Ok(input),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        let url = format!("{}/{}/day/{}/input", self.base_url, YEAR, day);
+        let request = self.agent.get(&url).set("Cookie", &self.cookie());
+        let input = response_body(request.call())?;
+
+        fs::create_dir_all(&self.input_dir)?;
+        fs::write(&path, &input)?;
+
+        Ok(input)
+    }
+
+    /// submits an answer, unless the history shows that it's pointless.
+    ///
+    /// with `wait` set, rate limits are waited out instead of returned as
+    /// [`SubmitResult::Wait`].
+    pub fn submit(
+        &self,
+        day: u8,
+        part: Part,
+        answer: &str,
+        wait: bool,
+    ) -> Result<SubmitResult, ClientError> {
+        loop {
+            let result = self.submit_once(day, part, answer)?;
+
+            let wait_time = match &result {
+                SubmitResult::Wait(wait_time) => *wait_time,
+                SubmitResult::Submitted(Submission {
+                    outcome: SubmitOutcome::TooSoon,
+                    wait_seconds: Some(wait_seconds),
+                    ..
+                }) => Duration::from_secs(*wait_seconds),
+                _ => return Ok(result),
+            };
+
+            if !wait {
+                return Ok(result);
+            }
+            thread::sleep(wait_time);
+        }
+    }
+
+    fn submit_once(&self, day: u8, part: Part, answer: &str) -> Result<SubmitResult, ClientError> {
+        let mut history = SubmissionHistory::load(&self.history_path)?;
+
+        if let Some(submission) = history
+            .solved(day, part)
+            .or_else(|| history.find(day, part, answer))
+        {
+            return Ok(SubmitResult::Known(submission.clone()));
+        }
+
+        let now = unix_time();
+        if let Some(wait_until) = history.wait_until() {
+            if wait_until > now {
+                return Ok(SubmitResult::Wait(Duration::from_secs(wait_until - now)));
+            }
+        }
+
+        let url = format!("{}/{}/day/{}/answer", self.base_url, YEAR, day);
+        let level = part.number().to_string();
+        let request = self.agent.post(&url).set("Cookie", &self.cookie());
+        let body = response_body(request.send_form(&[("level", &level), ("answer", answer)]))?;
+
+        let message = article_text(&body);
+        let (outcome, wait_seconds) = parse_submit_response(&message)?;
+        let submission = Submission {
+            day,
+            part: part.number(),
+            answer: answer.to_owned(),
+            time: now,
+            outcome,
+            wait_seconds,
+            message,
+        };
+
+        history.submissions.push(submission.clone());
+        history.save(&self.history_path)?;
+
+        Ok(SubmitResult::Submitted(submission))
+    }
+
+    fn cookie(&self) -> String {
+        format!("session={}", self.session)
+    }
+}
+
+fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+fn response_body(response: Result<ureq::Response, ureq::Error>) -> Result<String, ClientError> {
+    match response {
+        Ok(response) => Ok(response.into_string()?),
+        Err(ureq::Error::Status(status, response)) => {
+            let message = response.into_string().unwrap_or_default();
+            Err(ClientError::Status {
+                status,
+                message: message.trim().to_owned(),
+            })
+        }
+        Err(e) => Err(Box::new(e).into()),
+    }
+}
+
+/// returns the text of the `<article>` on a page, which is where AoC puts the
+/// response to a submission. returns the whole page without tags if there is
+/// no article.
+fn article_text(html: &str) -> String {
+    let article = ARTICLE_REGEX
+        .captures(html)
+        .map(|captures| captures.get(1).unwrap().as_str())
+        .unwrap_or(html);
+    let text = TAG_REGEX.replace_all(article, "");
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn parse_submit_response(message: &str) -> Result<(SubmitOutcome, Option<u64>), ClientError> {
+    let penalty = || {
+        PENALTY_REGEX.captures(message).map(|captures| {
+            match captures.get(1).unwrap().as_str() {
+                "one" => 60,
+                minutes => minutes.parse::<u64>().unwrap_or(1) * 60,
+            }
+        })
+    };
+
+    if message.contains("That's the right answer") {
+        Ok((SubmitOutcome::Correct, None))
+    }
+    else if message.contains("That's not the right answer") {
+        let outcome = if message.contains("your answer is too high") {
+            SubmitOutcome::TooHigh
+        }
+        else if message.contains("your answer is too low") {
+            SubmitOutcome::TooLow
+        }
+        else {
+            SubmitOutcome::Incorrect
+        };
+        Ok((outcome, penalty()))
+    }
+    else if message.contains("You gave an answer too recently") {
+        let wait_seconds = TIME_LEFT_REGEX.captures(message).map(|captures| {
+            let minutes = captures
+                .get(1)
+                .map_or(0, |m| m.as_str().parse::<u64>().unwrap_or(0));
+            let seconds = captures
+                .get(2)
+                .unwrap()
+                .as_str()
+                .parse::<u64>()
+                .unwrap_or(0);
+            minutes * 60 + seconds
+        });
+        Ok((SubmitOutcome::TooSoon, wait_seconds))
+    }
+    else if message.contains("You don't seem to be solving the right level") {
+        Ok((SubmitOutcome::WrongLevel, None))
+    }
+    else {
+        Err(ClientError::UnexpectedResponse(message.to_owned()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{
+            BufRead,
+            BufReader,
+            Read,
+            Write,
+        },
+        net::{
+            TcpListener,
+            TcpStream,
+        },
+        sync::{
+            Arc,
+            Mutex,
+        },
+    };
+
+    use super::*;
+
+    const SESSION: &str = "53616c7465645f5f";
+
+    #[derive(Debug, Default)]
+    struct StandInState {
+        /// number of requests so far.
+        num_requests: usize,
+        /// the next submission is answered with "too recently" and this many
+        /// seconds.
+        rate_limit: Option<u64>,
+    }
+
+    /// a tiny HTTP server that responds like adventofcode.com. the input of
+    /// day N is `"input N\n"` and the answer to every part is `"42"`. only
+    /// days 1 to 3 are unlocked.
+    struct StandIn {
+        base_url: String,
+        state: Arc<Mutex<StandInState>>,
+    }
+
+    impl StandIn {
+        fn start() -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let base_url = format!("http://{}", listener.local_addr().unwrap());
+            let state = Arc::new(Mutex::new(StandInState::default()));
+
+            let server_state = state.clone();
+            thread::spawn(move || {
+                for stream in listener.incoming() {
+                    handle_request(stream.unwrap(), &server_state);
+                }
+            });
+
+            Self { base_url, state }
+        }
+
+        fn num_requests(&self) -> usize {
+            self.state.lock().unwrap().num_requests
+        }
+    }
+
+    fn handle_request(mut stream: TcpStream, state: &Mutex<StandInState>) {
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).unwrap();
+        let mut request_line = request_line.split_whitespace();
+        let method = request_line.next().unwrap().to_owned();
+        let path = request_line.next().unwrap().to_owned();
+
+        let mut content_length = 0;
+        let mut cookie = String::new();
+        loop {
+            let mut header = String::new();
+            reader.read_line(&mut header).unwrap();
+            let header = header.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            let (name, value) = header.split_once(": ").unwrap();
+            match name.to_lowercase().as_str() {
+                "content-length" => content_length = value.parse().unwrap(),
+                "cookie" => cookie = value.to_owned(),
+                _ => {}
+            }
+        }
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body).unwrap();
+        let body = String::from_utf8(body).unwrap();
+
+        let mut state = state.lock().unwrap();
+        state.num_requests += 1;
+
+        let day = path
+            .strip_prefix("/2022/day/")
+            .and_then(|rest| rest.split('/').next())
+            .and_then(|day| day.parse::<u8>().ok());
+
+        let (status, response) = if cookie != format!("session={}", SESSION) {
+            (
+                400,
+                "Puzzle inputs differ by user.  Please log in to get your puzzle input.\n"
+                    .to_owned(),
+            )
+        }
+        else {
+            match (method.as_str(), day) {
+                (_, Some(day)) if day > 3 => (404, "404 Not Found\n".to_owned()),
+                ("GET", Some(day)) if path.ends_with("/input") => (200, format!("input {}\n", day)),
+                ("POST", Some(_)) if path.ends_with("/answer") => {
+                    let message = if let Some(seconds) = state.rate_limit.take() {
+                        format!(
+                            "You gave an answer too recently; you have to wait after submitting \
+                             an answer before trying again.  You have {}s left to wait.",
+                            seconds
+                        )
+                    }
+                    else if body.contains("level=2") {
+                        "You don't seem to be solving the right level.  Did you already \
+                         complete it?"
+                            .to_owned()
+                    }
+                    else if body.contains("answer=42") {
+                        "That's the right answer!  You are <em>one gold star</em> closer to \
+                         collecting enough star fruit."
+                            .to_owned()
+                    }
+                    else {
+                        "That's not the right answer; your answer is too low.  If you're \
+                         stuck, make sure you're using the full input data.  Please wait one \
+                         minute before trying again."
+                            .to_owned()
+                    };
+                    (
+                        200,
+                        format!(
+                            "<html><body><main>\n<article><p>{}</p></article>\n</main></body></\
+                             html>",
+                            message
+                        ),
+                    )
+                }
+                _ => (404, "404 Not Found\n".to_owned()),
+            }
+        };
+
+        write!(
+            stream,
+            "HTTP/1.1 {} Stand-In\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            response.len(),
+            response
+        )
+        .unwrap();
+    }
+
+    /// returns a client for `stand_in` that caches in a new temporary
+    /// directory.
+    fn new_client(stand_in: &StandIn, session: &str, name: &str) -> (Client, PathBuf) {
+        let dir =
+            std::env::temp_dir().join(format!("aoc2022-client-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let client = Client::new(
+            &stand_in.base_url,
+            session,
+            dir.join("input"),
+            dir.join("submissions.toml"),
+        );
+        (client, dir)
+    }
+
+    #[test]
+    fn fetch_and_cache_input() {
+        let stand_in = StandIn::start();
+        let (client, dir) = new_client(&stand_in, SESSION, "fetch");
+
+        assert_eq!(client.fetch_input(2, false).unwrap(), "input 2\n");
+        assert_eq!(
+            fs::read_to_string(dir.join("input/day2.txt")).unwrap(),
+            "input 2\n"
+        );
+        assert_eq!(stand_in.num_requests(), 1);
+
+        // cached
+        assert_eq!(client.fetch_input(2, false).unwrap(), "input 2\n");
+        assert_eq!(stand_in.num_requests(), 1);
+
+        // refreshed
+        fs::write(dir.join("input/day2.txt"), "stale").unwrap();
+        assert_eq!(client.fetch_input(2, true).unwrap(), "input 2\n");
+        assert_eq!(stand_in.num_requests(), 2);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn fetch_errors() {
+        let stand_in = StandIn::start();
+
+        let (client, dir) = new_client(&stand_in, "wrong", "fetch-errors");
+        let error = client.fetch_input(1, false).unwrap_err();
+        assert!(matches!(error, ClientError::Status { status: 400, .. }));
+        assert!(!dir.join("input/day1.txt").exists());
+
+        let (client, _) = new_client(&stand_in, SESSION, "fetch-errors");
+        let error = client.fetch_input(24, false).unwrap_err();
+        assert!(matches!(error, ClientError::Status { status: 404, .. }));
+    }
+
+    #[test]
+    fn submit_and_remember() {
+        let stand_in = StandIn::start();
+        let (client, dir) = new_client(&stand_in, SESSION, "submit");
+
+        // wrong answers come with a minute of waiting
+        let SubmitResult::Submitted(submission) =
+            client.submit(1, Part::Part1, "7", false).unwrap()
+        else {
+            panic!("answer wasn't submitted");
+        };
+        assert_eq!(submission.outcome, SubmitOutcome::TooLow);
+        assert_eq!(submission.wait_seconds, Some(60));
+
+        // so the next answer isn't even sent
+        let result = client.submit(1, Part::Part1, "42", false).unwrap();
+        assert!(matches!(result, SubmitResult::Wait(_)));
+        // and neither is the same wrong answer
+        let result = client.submit(1, Part::Part1, "7", false).unwrap();
+        assert!(matches!(result, SubmitResult::Known(_)));
+        assert_eq!(stand_in.num_requests(), 1);
+
+        // pretend the minute has passed
+        let history_path = dir.join("submissions.toml");
+        let mut history = SubmissionHistory::load(&history_path).unwrap();
+        history.submissions[0].time -= 60;
+        history.save(&history_path).unwrap();
+
+        let result = client.submit(1, Part::Part1, "42", false).unwrap();
+        assert!(matches!(
+            result,
+            SubmitResult::Submitted(Submission {
+                outcome: SubmitOutcome::Correct,
+                ..
+            })
+        ));
+        let result = client.submit(1, Part::Part1, "43", false).unwrap();
+        assert!(matches!(
+            result,
+            SubmitResult::Known(Submission {
+                outcome: SubmitOutcome::Correct,
+                ..
+            })
+        ));
+        assert_eq!(stand_in.num_requests(), 2);
+
+        let history = SubmissionHistory::load(&history_path).unwrap();
+        assert_eq!(history.submissions.len(), 2);
+        assert_eq!(history.submissions[1].answer, "42");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn submit_waits_for_rate_limit() {
+        let stand_in = StandIn::start();
+        let (client, dir) = new_client(&stand_in, SESSION, "wait");
+
+        stand_in.state.lock().unwrap().rate_limit = Some(1);
+        let result = client.submit(2, Part::Part1, "42", false).unwrap();
+        assert!(matches!(
+            result,
+            SubmitResult::Submitted(Submission {
+                outcome: SubmitOutcome::TooSoon,
+                wait_seconds: Some(1),
+                ..
+            })
+        ));
+
+        stand_in.state.lock().unwrap().rate_limit = Some(1);
+        let result = client.submit(3, Part::Part1, "42", true).unwrap();
+        assert!(matches!(
+            result,
+            SubmitResult::Submitted(Submission {
+                outcome: SubmitOutcome::Correct,
+                ..
+            })
+        ));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn parse_responses() {
+        assert_eq!(
+            parse_submit_response(
+                "You gave an answer too recently; you have to wait after submitting an answer \
+                 before trying again. You have 4m 12s left to wait. [Return to Day 1]"
+            )
+            .unwrap(),
+            (SubmitOutcome::TooSoon, Some(252))
+        );
+        assert_eq!(
+            parse_submit_response(
+                "That's not the right answer. If you're stuck, make sure you're using the full \
+                 input data; please wait 5 minutes before trying again."
+            )
+            .unwrap(),
+            (SubmitOutcome::Incorrect, Some(300))
+        );
+        assert_eq!(
+            parse_submit_response(
+                "That's not the right answer; your answer is too high. Please wait one minute \
+                 before trying again."
+            )
+            .unwrap(),
+            (SubmitOutcome::TooHigh, Some(60))
+        );
+        assert!(parse_submit_response("Something else").is_err());
+    }
+
+    #[test]
+    fn article_text_strips_tags() {
+        assert_eq!(
+            article_text(
+                "<main><article><p>That's <em>the</em>\n right answer!</p></article></main>"
+            ),
+            "That's the right answer!"
+        );
+    }
+}
@@ -1,34 +1,291 @@
-#[aoc_generator(day1)]
-fn day1_input(input: &str) -> Vec<Vec<u32>> {
+use crate::{
+    error::Error,
+    input::{
+        self,
+        Line,
+    },
+    num::{
+        self,
+        Number,
+    },
+    solution::Solution,
+    stream::Incremental,
+};
+
+/// parses the calories carried by every elf.
+pub fn parse_calories<T: Number>(input: &str) -> Result<Vec<Vec<T>>, Error> {
     let mut calories = Vec::new();
     let mut buf = Vec::new();
 
-    for line in input.lines() {
-        if line.is_empty() {
-            calories.push(buf.drain(..).collect());
+    for line in input::lines(input) {
+        if line.text.is_empty() {
+            calories.push(std::mem::take(&mut buf));
         }
         else {
-            let current = line.parse().unwrap();
+            let current = line.parse(line.text, "a number of calories")?;
             buf.push(current);
         }
     }
 
+    if !buf.is_empty() {
+        calories.push(buf);
+    }
+
+    Ok(calories)
+}
+
+/// returns the total calories carried by every elf.
+pub fn totals<T: Number>(calories: &[Vec<T>]) -> Result<Vec<T>, Error> {
     calories
+        .iter()
+        .enumerate()
+        .map(|(i, c)| num::checked_sum(c, format_args!("the calories of elf {}", i + 1)))
+        .collect()
+}
+
+/// returns the most calories carried by a single elf.
+pub fn max_total<T: Number>(calories: &[Vec<T>]) -> Result<T, Error> {
+    totals(calories)?
+        .into_iter()
+        .max()
+        .ok_or_else(|| Error::NoSolution("no elves".to_owned()))
+}
+
+/// returns the calories carried by the three elves carrying the most.
+pub fn top_three_total<T: Number>(calories: &[Vec<T>]) -> Result<T, Error> {
+    let mut calories = totals(calories)?;
+
+    if calories.len() < 3 {
+        return Err(Error::NoSolution(format!(
+            "need at least 3 elves, but got {}",
+            calories.len()
+        )));
+    }
+
+    calories.sort_by(|a, b| a.cmp(b).reverse());
+
+    num::checked_sum(&calories[0..3], "the calories of the top three elves")
+}
+
+/// counts calories while the input arrives, keeping only the current elf and
+/// the three elves carrying the most.
+#[derive(Clone, Debug)]
+pub struct CalorieCounter<T = u64> {
+    /// the number of elves whose calories ended with a blank line.
+    elves: usize,
+    /// the calories of the current elf, if any were listed yet.
+    current: Option<T>,
+    /// the most calories carried by single elves, in descending order.
+    top: Vec<T>,
+}
+
+impl<T> Default for CalorieCounter<T> {
+    fn default() -> Self {
+        Self {
+            elves: 0,
+            current: None,
+            top: Vec::with_capacity(4),
+        }
+    }
+}
+
+impl<T: Number> CalorieCounter<T> {
+    /// the totals of the three elves carrying the most, including the current
+    /// elf.
+    fn top_three(&self) -> Vec<T> {
+        let mut top = self.top.clone();
+        if let Some(current) = &self.current {
+            insert_top_three(&mut top, current.clone());
+        }
+        top
+    }
+
+    /// the number of elves, including the current elf.
+    fn elves(&self) -> usize {
+        self.elves + usize::from(self.current.is_some())
+    }
+}
+
+impl<T: Number> Incremental for CalorieCounter<T> {
+    const DAY: u8 = 1;
+
+    type Part1 = T;
+    type Part2 = T;
+
+    fn push_line(&mut self, line: Line<'_>) -> Result<(), Error> {
+        if line.text.is_empty() {
+            let total = self.current.take().unwrap_or_else(T::zero);
+            insert_top_three(&mut self.top, total);
+            self.elves += 1;
+        }
+        else {
+            let calories: T = line.parse(line.text, "a number of calories")?;
+            let total = self.current.take().unwrap_or_else(T::zero);
+            self.current = Some(total.checked_add(&calories).ok_or_else(|| {
+                num::overflow::<T>(format_args!("the calories of elf {}", self.elves + 1))
+            })?);
+        }
+        Ok(())
+    }
+
+    fn part1(&self) -> Result<T, Error> {
+        self.top_three()
+            .into_iter()
+            .next()
+            .ok_or_else(|| Error::NoSolution("no elves".to_owned()))
+    }
+
+    fn part2(&self) -> Result<T, Error> {
+        if self.elves() < 3 {
+            return Err(Error::NoSolution(format!(
+                "need at least 3 elves, but got {}",
+                self.elves()
+            )));
+        }
+
+        num::checked_sum(&self.top_three(), "the calories of the top three elves")
+    }
+}
+
+/// inserts `total` into the descending list `top`, keeping the largest three.
+fn insert_top_three<T: Number>(top: &mut Vec<T>, total: T) {
+    let index = top.partition_point(|other| *other >= total);
+    if index < 3 {
+        top.insert(index, total);
+        top.truncate(3);
+    }
+}
+
+#[aoc_generator(day1)]
+pub fn day1_input(input: &str) -> Result<Vec<Vec<u64>>, Error> {
+    parse_calories(input)
 }
 
 #[aoc(day1, part1)]
-fn day1_part1(calories: &[Vec<u32>]) -> u32 {
-    calories.iter().map(|c| c.iter().sum()).max().unwrap()
+pub fn day1_part1(calories: &[Vec<u64>]) -> Result<u64, Error> {
+    max_total(calories)
 }
 
 #[aoc(day1, part2)]
-fn day1_part2(calories: &[Vec<u32>]) -> u32 {
-    let mut calories = calories
-        .iter()
-        .map(|c| c.iter().sum())
-        .collect::<Vec<u32>>();
+pub fn day1_part2(calories: &[Vec<u64>]) -> Result<u64, Error> {
+    top_three_total(calories)
+}
 
-    calories.sort_by(|a, b| a.cmp(b).reverse());
+pub struct Day1;
+
+impl Solution for Day1 {
+    const DAY: u8 = 1;
+
+    type Input = Vec<Vec<u64>>;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        day1_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Part1, Error> {
+        day1_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Part2, Error> {
+        day1_part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "1000
+2000
+3000
+
+4000
 
-    calories[0..3].iter().sum()
+5000
+6000
+
+7000
+8000
+9000
+
+10000";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(day1_part1(&day1_input(EXAMPLE).unwrap()).unwrap(), 24000);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(day1_part2(&day1_input(EXAMPLE).unwrap()).unwrap(), 45000);
+    }
+
+    #[test]
+    fn last_elf_without_trailing_blank_line() {
+        // the inputs end without a blank line, and the last elf carries the
+        // most calories here.
+        let calories = day1_input("1000\n\n2000\n3000").unwrap();
+        assert_eq!(calories, [vec![1000], vec![2000, 3000]]);
+        assert_eq!(day1_part1(&calories).unwrap(), 5000);
+    }
+
+    #[test]
+    fn invalid_calories() {
+        let error = day1_input("1000\n\n20x0").unwrap_err();
+        assert!(matches!(
+            error,
+            Error::Parse {
+                line: 3,
+                column: 1,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn overflow() {
+        let input = "4000000000\n\n3000000000\n2000000000";
+        let calories = parse_calories::<u32>(input).unwrap();
+        assert!(matches!(max_total(&calories), Err(Error::Overflow(_))));
+        assert_eq!(
+            max_total(&parse_calories::<i64>(input).unwrap()).unwrap(),
+            5000000000
+        );
+
+        let input = "18446744073709551615\n1\n\n1\n\n1";
+        assert!(matches!(
+            top_three_total(&parse_calories::<u64>(input).unwrap()),
+            Err(Error::Overflow(_))
+        ));
+        assert_eq!(
+            top_three_total(&parse_calories::<i128>(input).unwrap()).unwrap(),
+            18446744073709551618
+        );
+    }
+
+    #[test]
+    fn stream_example() {
+        let mut counter = CalorieCounter::<u64>::default();
+        let mut answers = Vec::new();
+        for line in input::lines(EXAMPLE) {
+            counter.push_line(line).unwrap();
+            answers.push(counter.part1().unwrap());
+        }
+
+        assert_eq!(answers[..4], [1000, 3000, 6000, 6000]);
+        assert_eq!(counter.part1().unwrap(), 24000);
+        assert_eq!(counter.part2().unwrap(), 45000);
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn bigint() {
+        use num_bigint::BigInt;
+
+        let input = "340282366920938463463374607431768211455\n1\n\n2\n\n3";
+        let calories = parse_calories::<BigInt>(input).unwrap();
+        assert_eq!(max_total(&calories).unwrap(), BigInt::from(u128::MAX) + 1);
+    }
 }
@@ -1,7 +1,42 @@
-use std::str::FromStr;
+use std::{
+    fmt,
+    str::FromStr,
+};
 
-use thiserror::Error;
+use serde::{
+    de,
+    Deserialize,
+    Deserializer,
+    Serialize,
+    Serializer,
+};
+use tracing::trace;
 
+use crate::{
+    error::Error,
+    geometry::{
+        BoundingBox,
+        Point2,
+    },
+    input::{
+        self,
+        Line,
+    },
+    render::{
+        Animation,
+        Cell,
+        Frame,
+        FrameSink,
+        Render,
+        RenderError,
+        Rgb,
+    },
+    simulation::Simulation,
+    solution::Solution,
+    stream::Incremental,
+};
+
+#[derive(Clone, Debug)]
 pub struct FrameBuffer {
     data: [bool; Self::NUM_PIXELS],
 }
@@ -19,35 +54,114 @@ impl FrameBuffer {
     const NUM_ROWS: usize = 6;
     const NUM_PIXELS: usize = Self::NUM_PIXELS_PER_ROW * Self::NUM_ROWS;
 
+    /// returns the rows of the screen, with `#` for lit pixels and `.` for
+    /// dark ones.
+    fn rows(&self) -> impl Iterator<Item = String> + '_ {
+        self.data.chunks(Self::NUM_PIXELS_PER_ROW).map(|row| {
+            row.iter()
+                .map(|lit| {
+                    if *lit {
+                        '#'
+                    }
+                    else {
+                        '.'
+                    }
+                })
+                .collect()
+        })
+    }
+
     pub fn send_data(&mut self, cycle: u64, sprite: i64) {
         let line = (cycle as i64 - 1) / Self::NUM_PIXELS_PER_ROW as i64;
         let col = (cycle as i64 - 1) % Self::NUM_PIXELS_PER_ROW as i64;
 
-        if line >= 0 && line < 6 && col >= 0 && col < 40 {
-            if sprite - 1 <= col && sprite + 1 >= col {
-                self.data[line as usize * Self::NUM_PIXELS_PER_ROW + col as usize] = true;
+        if (0..Self::NUM_ROWS as i64).contains(&line)
+            && (0..Self::NUM_PIXELS_PER_ROW as i64).contains(&col)
+            && (sprite - 1..=sprite + 1).contains(&col)
+        {
+            self.data[line as usize * Self::NUM_PIXELS_PER_ROW + col as usize] = true;
+        }
+    }
+}
+
+/// the screen is stored as its rows, which is easier to read than 240
+/// booleans.
+impl Serialize for FrameBuffer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.rows())
+    }
+}
+
+impl<'de> Deserialize<'de> for FrameBuffer {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let rows = Vec::<String>::deserialize(deserializer)?;
+        if rows.len() != Self::NUM_ROWS {
+            return Err(de::Error::invalid_length(rows.len(), &"6 rows of pixels"));
+        }
+
+        let mut frame_buffer = Self::default();
+        for (line, row) in rows.iter().enumerate() {
+            if row.len() != Self::NUM_PIXELS_PER_ROW {
+                return Err(de::Error::invalid_length(row.len(), &"40 pixels"));
+            }
+            for (col, pixel) in row.chars().enumerate() {
+                frame_buffer.data[line * Self::NUM_PIXELS_PER_ROW + col] = match pixel {
+                    '#' => true,
+                    '.' => false,
+                    _ => {
+                        return Err(de::Error::invalid_value(
+                            de::Unexpected::Char(pixel),
+                            &"`#` or `.`",
+                        ))
+                    }
+                };
             }
         }
+
+        Ok(frame_buffer)
+    }
+}
+
+const DARK: Cell = Cell::new('.', Rgb(8, 24, 8));
+const LIT: Cell = Cell::new('#', Rgb(64, 255, 96));
+
+impl Render for FrameBuffer {
+    fn bounds(&self) -> BoundingBox<i32> {
+        BoundingBox {
+            min: Point2::zero(),
+            max: Point2::new(
+                Self::NUM_PIXELS_PER_ROW as i32 - 1,
+                Self::NUM_ROWS as i32 - 1,
+            ),
+        }
     }
 
-    pub fn print(&self) {
+    fn render_area(&self, area: BoundingBox<i32>) -> Frame {
+        let mut frame = Frame::for_area(area, DARK);
+
         for line in 0..Self::NUM_ROWS {
             for col in 0..Self::NUM_PIXELS_PER_ROW {
                 if self.data[line * Self::NUM_PIXELS_PER_ROW + col] {
-                    print!("#");
-                }
-                else {
-                    print!(".");
+                    frame.set_point(area, Point2::new(col as i32, line as i32), LIT);
                 }
             }
-            println!("");
         }
+
+        frame
+    }
+}
+
+impl fmt::Display for FrameBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.render())
     }
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Cpu {
     x_register: i64,
-    signal: Vec<i64>,
+    /// the sum of the signal strengths sampled so far.
+    signal_strength: i64,
     cycle: u64,
     frame_buffer: FrameBuffer,
 }
@@ -56,7 +170,7 @@ impl Default for Cpu {
     fn default() -> Self {
         Self {
             x_register: 1,
-            signal: vec![],
+            signal_strength: 0,
             cycle: 1,
             frame_buffer: Default::default(),
         }
@@ -70,28 +184,114 @@ impl Cpu {
         for cycle in self.cycle..cycle_after_execution {
             if cycle == 20 || (cycle > 20 && (cycle - 20) % 40 == 0) {
                 let signal_strength = cycle as i64 * self.x_register;
-                //println!("cycle={}, x={}, signal={}", cycle, self.x_register,
-                // signal_strength);
-                self.signal.push(signal_strength);
+                trace!(cycle, x = self.x_register, signal_strength, "signal sample");
+                self.signal_strength += signal_strength;
             }
 
             self.frame_buffer.send_data(cycle, self.x_register);
         }
 
-        //println!("cycle={}, x={}, instruction={:?}", cycle_after_execution,
-        // self.x_register, instruction);
         instruction.execute(&mut self.x_register);
         self.cycle = cycle_after_execution;
     }
 
-    pub fn run_program(&mut self, program: &[Instruction]) {
-        for instruction in program {
-            self.run_instruction(*instruction);
+    /// the cycle of the next instruction.
+    pub fn cycle(&self) -> u64 {
+        self.cycle
+    }
+
+    pub fn x_register(&self) -> i64 {
+        self.x_register
+    }
+
+    /// returns the sum of the signal strengths sampled so far.
+    pub fn signal_strength(&self) -> i64 {
+        self.signal_strength
+    }
+
+    pub fn frame_buffer(&self) -> &FrameBuffer {
+        &self.frame_buffer
+    }
+}
+
+/// a cpu running `program`. a step runs one instruction.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CpuSimulation {
+    cpu: Cpu,
+    program: Vec<Instruction>,
+    steps: usize,
+}
+
+impl CpuSimulation {
+    pub fn new(program: Vec<Instruction>) -> Self {
+        Self {
+            cpu: Cpu::default(),
+            program,
+            steps: 0,
         }
     }
+
+    pub fn cpu(&self) -> &Cpu {
+        &self.cpu
+    }
+}
+
+impl Simulation for CpuSimulation {
+    fn step(&mut self) -> bool {
+        match self.program.get(self.steps) {
+            Some(instruction) => {
+                self.cpu.run_instruction(*instruction);
+                self.steps += 1;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn steps(&self) -> usize {
+        self.steps
+    }
+
+    fn is_finished(&self) -> bool {
+        self.steps >= self.program.len()
+    }
+}
+
+/// shows the screen.
+impl Render for CpuSimulation {
+    fn bounds(&self) -> BoundingBox<i32> {
+        self.cpu.frame_buffer.bounds()
+    }
+
+    fn render_area(&self, area: BoundingBox<i32>) -> Frame {
+        self.cpu.frame_buffer.render_area(area)
+    }
 }
 
-#[derive(Copy, Clone, Debug)]
+/// runs `program` and pushes a frame of the screen after every instruction.
+pub fn animate_screen(
+    program: &[Instruction],
+    sink: &mut dyn FrameSink,
+) -> Result<(), RenderError> {
+    let mut simulation = CpuSimulation::new(program.to_vec());
+    let mut animation = Animation::new(sink, simulation.bounds());
+    simulation.run_with(&mut animation);
+    animation.finish()
+}
+
+/// returns the cpu after running `program`.
+fn run_program(program: &[Instruction]) -> Cpu {
+    let mut simulation = CpuSimulation::new(program.to_vec());
+    simulation.run();
+    simulation.cpu
+}
+
+/// returns the screen after running `program`.
+pub fn run_screen(program: &[Instruction]) -> FrameBuffer {
+    run_program(program).frame_buffer
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
 pub enum Instruction {
     Add(i64),
     Noop,
@@ -113,46 +313,304 @@ impl Instruction {
     }
 }
 
-#[derive(Debug, Error)]
-#[error("failed to parse instruction: {0}")]
-pub struct InstructionParseError(String);
-
 impl FromStr for Instruction {
-    type Err = InstructionParseError;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let err = || InstructionParseError(s.to_owned());
-
         if s == "noop" {
             Ok(Self::Noop)
         }
         else {
-            let operand: i64 = s
+            let operand = s
                 .strip_prefix("addx ")
-                .ok_or_else(err)?
+                .ok_or_else(|| Error::parse(1, s, s, "expected an instruction"))?;
+            let operand = operand
                 .parse()
-                .map_err(|_| err())?;
+                .map_err(|_| Error::parse(1, s, operand, "expected a number"))?;
             Ok(Self::Add(operand))
         }
     }
 }
 
 #[aoc_generator(day10)]
-fn day10_input(input: &str) -> Vec<Instruction> {
-    input.lines().map(|line| line.parse().unwrap()).collect()
+pub fn day10_input(input: &str) -> Result<Vec<Instruction>, Error> {
+    input::lines(input)
+        .map(|line| line.text.parse().map_err(|e: Error| e.at_line(line.number)))
+        .collect()
 }
 
 #[aoc(day10, part1)]
-fn day10_part1(program: &[Instruction]) -> i64 {
-    let mut cpu = Cpu::default();
-    cpu.run_program(program);
-    cpu.signal.iter().sum()
+pub fn day10_part1(program: &[Instruction]) -> Result<i64, Error> {
+    Ok(run_program(program).signal_strength())
 }
 
 #[aoc(day10, part2)]
-fn day10_part2(program: &[Instruction]) -> &'static str {
-    let mut cpu = Cpu::default();
-    cpu.run_program(program);
-    cpu.frame_buffer.print();
-    "read from framebuffer"
+pub fn day10_part2(program: &[Instruction]) -> Result<String, Error> {
+    // the answer has to be read from the screen, so we start it on a new line.
+    Ok(format!("\n{}", run_screen(program).render()))
+}
+
+/// runs the program while it arrives. the screen has a fixed size, so the cpu
+/// only keeps a constant amount of state.
+impl Incremental for Cpu {
+    const DAY: u8 = 10;
+
+    type Part1 = i64;
+    type Part2 = String;
+
+    fn push_line(&mut self, line: Line<'_>) -> Result<(), Error> {
+        let instruction = line
+            .text
+            .parse()
+            .map_err(|e: Error| e.at_line(line.number))?;
+        self.run_instruction(instruction);
+        Ok(())
+    }
+
+    fn part1(&self) -> Result<i64, Error> {
+        Ok(self.signal_strength())
+    }
+
+    fn part2(&self) -> Result<String, Error> {
+        Ok(format!("\n{}", self.frame_buffer().render()))
+    }
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u8 = 10;
+
+    type Input = Vec<Instruction>;
+    type Part1 = i64;
+    type Part2 = String;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        day10_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Part1, Error> {
+        day10_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Part2, Error> {
+        day10_part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "addx 15
+addx -11
+addx 6
+addx -3
+addx 5
+addx -1
+addx -8
+addx 13
+addx 4
+noop
+addx -1
+addx 5
+addx -1
+addx 5
+addx -1
+addx 5
+addx -1
+addx 5
+addx -1
+addx -35
+addx 1
+addx 24
+addx -19
+addx 1
+addx 16
+addx -11
+noop
+noop
+addx 21
+addx -15
+noop
+noop
+addx -3
+addx 9
+addx 1
+addx -3
+addx 8
+addx 1
+addx 5
+noop
+noop
+noop
+noop
+noop
+addx -36
+noop
+addx 1
+addx 7
+noop
+noop
+noop
+addx 2
+addx 6
+noop
+noop
+noop
+noop
+noop
+addx 1
+noop
+noop
+addx 7
+addx 1
+noop
+addx -13
+addx 13
+addx 7
+noop
+addx 1
+addx -33
+noop
+noop
+noop
+addx 2
+noop
+noop
+noop
+addx 8
+noop
+addx -1
+addx 2
+addx 1
+noop
+addx 17
+addx -9
+addx 1
+addx 1
+addx -3
+addx 11
+noop
+noop
+addx 1
+noop
+addx 1
+noop
+noop
+addx -13
+addx -19
+addx 1
+addx 3
+addx 26
+addx -30
+addx 12
+addx -1
+addx 3
+addx 1
+noop
+noop
+noop
+addx -9
+addx 18
+addx 1
+addx 2
+noop
+noop
+addx 9
+noop
+noop
+noop
+addx -1
+addx 2
+addx -37
+addx 1
+addx 3
+noop
+addx 15
+addx -21
+addx 22
+addx -6
+addx 1
+noop
+addx 2
+addx 1
+noop
+addx -10
+noop
+noop
+addx 20
+addx 1
+addx 2
+addx 2
+addx -6
+addx -11
+noop
+noop
+noop";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(day10_part1(&day10_input(EXAMPLE).unwrap()).unwrap(), 13140);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(
+            day10_part2(&day10_input(EXAMPLE).unwrap()).unwrap(),
+            "
+##..##..##..##..##..##..##..##..##..##..
+###...###...###...###...###...###...###.
+####....####....####....####....####....
+#####.....#####.....#####.....#####.....
+######......######......######......####
+#######.......#######.......#######.....
+"
+        );
+    }
+
+    #[test]
+    fn stream_example() {
+        let mut cpu = Cpu::default();
+        for line in input::lines(EXAMPLE) {
+            cpu.push_line(line).unwrap();
+            if cpu.cycle() == 21 {
+                assert_eq!(Incremental::part1(&cpu).unwrap(), 420);
+            }
+        }
+        assert_eq!(Incremental::part1(&cpu).unwrap(), 13140);
+        assert_eq!(
+            Incremental::part2(&cpu).unwrap(),
+            day10_part2(&day10_input(EXAMPLE).unwrap()).unwrap()
+        );
+    }
+
+    #[test]
+    fn animate_example() {
+        let program = day10_input(EXAMPLE).unwrap();
+        let mut frames = vec![];
+        animate_screen(&program, &mut frames).unwrap();
+
+        assert_eq!(frames.len(), program.len() + 1);
+        assert_eq!(
+            frames[0].to_ascii(),
+            format!("{}\n", ".".repeat(40)).repeat(6)
+        );
+        assert_eq!(frames.last(), Some(&run_screen(&program).render()));
+    }
+
+    #[test]
+    fn serialize_screen() {
+        let screen = run_screen(&day10_input(EXAMPLE).unwrap());
+        let json = serde_json::to_string(&screen).unwrap();
+        assert!(json.starts_with("[\"##..##..##..##..##..##..##..##..##..##..\",\"###..."));
+
+        let deserialized: FrameBuffer = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.to_string(), screen.to_string());
+
+        let error = serde_json::from_str::<FrameBuffer>(r##"["#."]"##).unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("invalid length 1, expected 6 rows"));
+    }
 }
@@ -2,9 +2,33 @@ use lazy_static::lazy_static;
 use num_bigint::BigInt;
 use num_traits::Zero;
 use regex::Regex;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use tracing::{
+    debug,
+    debug_span,
+};
+
+use crate::{
+    error::Error,
+    input::{
+        self,
+        Line,
+    },
+    simulation::Simulation,
+    solution::Solution,
+};
 
 pub type MonkeyId = u8;
 
+/// the number of rounds of part 1, where worry levels are reduced after every
+/// inspection.
+pub const ROUNDS_WITH_RELIEF: usize = 20;
+/// the number of rounds of part 2.
+pub const ROUNDS_WITHOUT_RELIEF: usize = 10000;
+
 lazy_static! {
     static ref MONKEY_REGEX: Regex = r"^Monkey (\d+):$".parse().unwrap();
     static ref OPERATION_REGEX: Regex = r"^  Operation: new = ([\w\d]+) ([\*\+]) ([\w\d]+)$"
@@ -12,7 +36,9 @@ lazy_static! {
         .unwrap();
 }
 
-#[derive(Clone, Debug)]
+/// serialized as just the monkeys, the rest is derived from them.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(try_from = "Vec<Monkey>", into = "Vec<Monkey>")]
 pub struct Monkeys {
     monkeys: Vec<Monkey>,
     dispatch: Vec<Vec<BigInt>>,
@@ -66,6 +92,10 @@ impl Monkeys {
         }
     }
 
+    pub fn monkeys(&self) -> &[Monkey] {
+        &self.monkeys
+    }
+
     pub fn monkey_business(&self) -> usize {
         let mut inspect_counts = self
             .monkeys
@@ -78,15 +108,118 @@ impl Monkeys {
     }
 }
 
-#[derive(Clone, Debug)]
+/// checks that the monkeys only throw to each other, and that there are
+/// enough of them for the monkey business.
+impl TryFrom<Vec<Monkey>> for Monkeys {
+    type Error = Error;
+
+    fn try_from(monkeys: Vec<Monkey>) -> Result<Self, Self::Error> {
+        for (i, monkey) in monkeys.iter().enumerate() {
+            if monkey.test.divisible_by.is_zero() {
+                return Err(Error::InvalidInput(format!(
+                    "monkey {} tests divisibility by 0",
+                    i
+                )));
+            }
+            for target in [monkey.test.true_monkey, monkey.test.false_monkey] {
+                if usize::from(target) >= monkeys.len() {
+                    return Err(Error::InvalidInput(format!(
+                        "monkey {} throws to monkey {}, which doesn't exist",
+                        i, target
+                    )));
+                }
+            }
+        }
+
+        if monkeys.len() < 2 {
+            return Err(Error::InvalidInput(format!(
+                "need at least 2 monkeys, but got {}",
+                monkeys.len()
+            )));
+        }
+
+        Ok(Self::new(monkeys))
+    }
+}
+
+impl From<Monkeys> for Vec<Monkey> {
+    fn from(monkeys: Monkeys) -> Self {
+        monkeys.monkeys
+    }
+}
+
+/// the monkeys playing keep away for the rounds of a part. a step is one
+/// round.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MonkeySimulation {
+    monkeys: Monkeys,
+    /// whether worry levels are reduced after every inspection, as in part 1.
+    relief: bool,
+    rounds: usize,
+}
+
+impl MonkeySimulation {
+    pub fn new(monkeys: Monkeys, relief: bool) -> Self {
+        Self {
+            monkeys,
+            relief,
+            rounds: 0,
+        }
+    }
+
+    pub fn monkeys(&self) -> &Monkeys {
+        &self.monkeys
+    }
+}
+
+impl Simulation for MonkeySimulation {
+    fn step(&mut self) -> bool {
+        if self.is_finished() {
+            return false;
+        }
+
+        self.monkeys.round(self.relief);
+        self.rounds += 1;
+        true
+    }
+
+    fn steps(&self) -> usize {
+        self.rounds
+    }
+
+    fn is_finished(&self) -> bool {
+        let rounds = if self.relief {
+            ROUNDS_WITH_RELIEF
+        }
+        else {
+            ROUNDS_WITHOUT_RELIEF
+        };
+        self.rounds >= rounds
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Monkey {
+    #[serde(with = "decimal::vec")]
     items: Vec<BigInt>,
     operation: WorryLevelOperation,
     test: Test,
     inspect_count: usize,
 }
 
-#[derive(Clone, Debug)]
+impl Monkey {
+    /// the worry levels of the items the monkey holds.
+    pub fn items(&self) -> &[BigInt] {
+        &self.items
+    }
+
+    /// how often the monkey inspected an item.
+    pub fn inspect_count(&self) -> usize {
+        self.inspect_count
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct WorryLevelOperation {
     left: Operand,
     right: Operand,
@@ -101,7 +234,7 @@ impl WorryLevelOperation {
     }
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
 pub enum Operation {
     Add,
     Mul,
@@ -116,10 +249,10 @@ impl Operation {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum Operand {
     Old,
-    Constant(BigInt),
+    Constant(#[serde(with = "decimal")] BigInt),
 }
 
 impl Operand {
@@ -131,8 +264,9 @@ impl Operand {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Test {
+    #[serde(with = "decimal")]
     divisible_by: BigInt,
     true_monkey: MonkeyId,
     false_monkey: MonkeyId,
@@ -149,69 +283,115 @@ impl Test {
     }
 }
 
+/// serializes worry levels as decimal strings, which stay readable no matter
+/// how large they get.
+mod decimal {
+    use num_bigint::BigInt;
+    use serde::{
+        de,
+        Deserialize,
+        Deserializer,
+        Serializer,
+    };
+
+    pub fn serialize<S: Serializer>(value: &BigInt, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(value)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<BigInt, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+
+    pub mod vec {
+        use num_bigint::BigInt;
+        use serde::{
+            de,
+            Deserialize,
+            Deserializer,
+            Serializer,
+        };
+
+        pub fn serialize<S: Serializer>(
+            values: &[BigInt],
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq(values.iter().map(BigInt::to_string))
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Vec<BigInt>, D::Error> {
+            Vec::<String>::deserialize(deserializer)?
+                .iter()
+                .map(|value| value.parse().map_err(de::Error::custom))
+                .collect()
+        }
+    }
+}
+
 #[aoc_generator(day11)]
-fn day11_input(input: &str) -> Monkeys {
-    fn parse_operand(s: &str) -> Operand {
+pub fn day11_input(input: &str) -> Result<Monkeys, Error> {
+    fn parse_operand(line: &Line, s: &str) -> Result<Operand, Error> {
         if s == "old" {
-            Operand::Old
+            Ok(Operand::Old)
         }
         else {
-            Operand::Constant(s.parse().unwrap())
+            Ok(Operand::Constant(line.parse(s, "`old` or a number")?))
         }
     }
-    let mut lines = input.lines();
+
+    let mut lines = input::lines(input);
     let mut monkeys = vec![];
 
     while let Some(line) = lines.next() {
-        assert!(MONKEY_REGEX.is_match(line));
+        if !MONKEY_REGEX.is_match(line.text) {
+            return Err(line.error(line.text, "expected a monkey"));
+        }
 
-        let items = lines
-            .next()
-            .expect("line with starting items")
-            .strip_prefix("  Starting items: ")
-            .expect("starting items prefix")
+        let line = lines.next_line("starting items")?;
+        let items = line
+            .strip_prefix("  Starting items: ")?
             .split(", ")
-            .map(|s| {
-                s.parse::<BigInt>()
-                    .expect("starting item worry level as number")
-            })
-            .collect();
-
-        let captures = OPERATION_REGEX.captures(lines.next().unwrap()).unwrap();
-        let left = parse_operand(captures.get(1).unwrap().as_str());
+            .map(|s| line.parse::<BigInt>(s, "a worry level"))
+            .collect::<Result<_, _>>()?;
+
+        let line = lines.next_line("an operation")?;
+        let captures = OPERATION_REGEX
+            .captures(line.text)
+            .ok_or_else(|| line.error(line.text, "expected an operation"))?;
+        let left = parse_operand(&line, captures.get(1).unwrap().as_str())?;
         let operation = match captures.get(2).unwrap().as_str() {
             "+" => Operation::Add,
             "*" => Operation::Mul,
-            op_str => panic!("unknown operation: {}", op_str),
+            _ => unreachable!("operation is matched by regex"),
         };
-        let right = parse_operand(captures.get(3).unwrap().as_str());
+        let right = parse_operand(&line, captures.get(3).unwrap().as_str())?;
         let operation = WorryLevelOperation {
             left,
             right,
             operation,
         };
 
-        let divisible_by = lines
-            .next()
-            .unwrap()
-            .strip_prefix("  Test: divisible by ")
-            .unwrap()
-            .parse()
-            .unwrap();
-        let true_monkey = lines
-            .next()
-            .unwrap()
-            .strip_prefix("    If true: throw to monkey ")
-            .unwrap()
-            .parse()
-            .unwrap();
-        let false_monkey = lines
-            .next()
-            .unwrap()
-            .strip_prefix("    If false: throw to monkey ")
-            .unwrap()
-            .parse()
-            .unwrap();
+        let line = lines.next_line("a test")?;
+        let divisor = line.strip_prefix("  Test: divisible by ")?;
+        let divisible_by: BigInt = line.parse(divisor, "a number")?;
+        if divisible_by.is_zero() {
+            return Err(line.error(divisor, "can't test divisibility by 0"));
+        }
+
+        let line = lines.next_line("the monkey to throw to if true")?;
+        let true_monkey = line.parse(
+            line.strip_prefix("    If true: throw to monkey ")?,
+            "a monkey",
+        )?;
+
+        let line = lines.next_line("the monkey to throw to if false")?;
+        let false_monkey = line.parse(
+            line.strip_prefix("    If false: throw to monkey ")?,
+            "a monkey",
+        )?;
 
         let test = Test {
             divisible_by,
@@ -219,8 +399,11 @@ fn day11_input(input: &str) -> Monkeys {
             false_monkey,
         };
 
-        let spacing = lines.next();
-        assert!(spacing == None || spacing.unwrap() == "");
+        if let Some(line) = lines.next() {
+            if !line.text.is_empty() {
+                return Err(line.error(line.text, "expected an empty line"));
+            }
+        }
 
         let monkey = Monkey {
             items,
@@ -231,42 +414,114 @@ fn day11_input(input: &str) -> Monkeys {
         monkeys.push(monkey)
     }
 
-    Monkeys::new(monkeys)
+    monkeys.try_into()
 }
 
-fn print_monkey_business(monkeys: &Monkeys) {
+fn log_inspect_counts(monkeys: &Monkeys) {
     for (i, monkey) in monkeys.monkeys.iter().enumerate() {
-        println!(
-            "monkey {} inspected items {} times.",
-            i, monkey.inspect_count
+        debug!(
+            monkey = i,
+            inspect_count = monkey.inspect_count,
+            "inspected items"
         );
     }
 }
 
 #[aoc(day11, part1)]
-fn day11_part1(monkeys: &Monkeys) -> usize {
-    let mut monkeys = monkeys.clone();
-
-    for _ in 0..20 {
-        monkeys.round(true);
-    }
+pub fn day11_part1(monkeys: &Monkeys) -> Result<usize, Error> {
+    let mut simulation = MonkeySimulation::new(monkeys.clone(), true);
+    simulation.run();
 
-    print_monkey_business(&monkeys);
+    log_inspect_counts(simulation.monkeys());
 
-    monkeys.monkey_business()
+    Ok(simulation.monkeys().monkey_business())
 }
 
 #[aoc(day11, part2)]
-fn day11_part2(monkeys: &Monkeys) -> usize {
-    let mut monkeys = monkeys.clone();
-
-    for round in 1..=10000 {
-        monkeys.round(false);
-        if round == 1 || round == 20 || round % 1000 == 0 {
-            println!("== after round {} ==", round);
-            print_monkey_business(&monkeys);
+pub fn day11_part2(monkeys: &Monkeys) -> Result<usize, Error> {
+    let mut simulation = MonkeySimulation::new(monkeys.clone(), false);
+    simulation.run_with(&mut |simulation: &MonkeySimulation| {
+        let round = simulation.steps();
+        if round == 1 || round == 20 || round.is_multiple_of(1000) {
+            let _round = debug_span!("round", round).entered();
+            log_inspect_counts(simulation.monkeys());
         }
+    });
+
+    Ok(simulation.monkeys().monkey_business())
+}
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    const DAY: u8 = 11;
+
+    type Input = Monkeys;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        day11_input(input)
     }
 
-    monkeys.monkey_business()
+    fn part1(input: &Self::Input) -> Result<Self::Part1, Error> {
+        day11_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Part2, Error> {
+        day11_part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "Monkey 0:
+  Starting items: 79, 98
+  Operation: new = old * 19
+  Test: divisible by 23
+    If true: throw to monkey 2
+    If false: throw to monkey 3
+
+Monkey 1:
+  Starting items: 54, 65, 75, 74
+  Operation: new = old + 6
+  Test: divisible by 19
+    If true: throw to monkey 2
+    If false: throw to monkey 0
+
+Monkey 2:
+  Starting items: 79, 60, 97
+  Operation: new = old * old
+  Test: divisible by 13
+    If true: throw to monkey 1
+    If false: throw to monkey 3
+
+Monkey 3:
+  Starting items: 74
+  Operation: new = old + 3
+  Test: divisible by 17
+    If true: throw to monkey 0
+    If false: throw to monkey 1";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(day11_part1(&day11_input(EXAMPLE).unwrap()).unwrap(), 10605);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(
+            day11_part2(&day11_input(EXAMPLE).unwrap()).unwrap(),
+            2713310158
+        );
+    }
+
+    #[test]
+    fn missing_test_line() {
+        let input = EXAMPLE.lines().take(10).collect::<Vec<_>>().join("\n");
+        let error = day11_input(&input).unwrap_err();
+        assert!(matches!(error, Error::UnexpectedEnd { line: 11, .. }));
+    }
 }
@@ -1,48 +1,45 @@
-use nalgebra::Vector2;
-
+use crate::{
+    error::Error,
+    geometry::Point2,
+    grid::Grid,
+    solution::Solution,
+};
+
+#[derive(Clone, Debug)]
 pub struct HeightMap {
-    elevation_data: Vec<u8>,
-    width: i32,
-    height: i32,
-    start_position: Vector2<i32>,
-    best_signal: Vector2<i32>,
+    elevations: Grid<u8>,
+    start_position: Point2<usize>,
+    best_signal: Point2<usize>,
 }
 
 impl HeightMap {
-    pub fn get_height(&self, position: Vector2<i32>) -> i32 {
-        assert!(position.x >= 0 && position.x < self.width);
-        assert!(position.y >= 0 && position.y < self.height);
-        self.elevation_data[(position.y * self.width + position.x) as usize] as i32
+    pub fn elevations(&self) -> &Grid<u8> {
+        &self.elevations
+    }
+
+    pub fn start_position(&self) -> Point2<usize> {
+        self.start_position
+    }
+
+    pub fn best_signal(&self) -> Point2<usize> {
+        self.best_signal
+    }
+
+    pub fn get_height(&self, position: Point2<usize>) -> i32 {
+        self.elevations[position] as i32
     }
 
-    pub fn neighbors(&self, position: Vector2<i32>) -> Vec<Vector2<i32>> {
-        let mut neighbors = vec![];
+    /// returns the positions from which `position` can be reached, i.e. the
+    /// neighbors that are at most one lower.
+    pub fn neighbors(&self, position: Point2<usize>) -> impl Iterator<Item = Point2<usize>> + '_ {
         let height = self.get_height(position);
 
-        let mut consider_neighbor = |dx, dy| {
-            let neighbor = position + Vector2::new(dx, dy);
-            if height - self.get_height(neighbor) <= 1 {
-                neighbors.push(neighbor)
-            }
-        };
-
-        if position.x > 0 {
-            consider_neighbor(-1, 0);
-        }
-        if position.x < self.width - 1 {
-            consider_neighbor(1, 0);
-        }
-        if position.y > 0 {
-            consider_neighbor(0, -1);
-        }
-        if position.y < self.height - 1 {
-            consider_neighbor(0, 1);
-        }
-
-        neighbors
+        self.elevations
+            .neighbors4(position)
+            .filter(move |neighbor| height - self.get_height(*neighbor) <= 1)
     }
 
-    pub fn shortest_path_to_best_signal(&self) -> Vec<Vector2<i32>> {
+    pub fn shortest_path_to_best_signal(&self) -> Option<Vec<Point2<usize>>> {
         // note: we search from destination to start, so that we can use the same
         // neighbor function for part b.
 
@@ -50,78 +47,109 @@ impl HeightMap {
             &self.best_signal,
             |position| self.neighbors(*position),
             |position| position == &self.start_position,
-        )
-        .expect("path");
+        )?;
 
         path.reverse();
 
-        path
+        Some(path)
     }
 
-    pub fn shortest_path_from_lowest_elevation(&self) -> Vec<Vector2<i32>> {
+    pub fn shortest_path_from_lowest_elevation(&self) -> Option<Vec<Point2<usize>>> {
         let mut path = pathfinding::prelude::bfs(
             &self.best_signal,
             |position| self.neighbors(*position),
             |position| self.get_height(*position) == 0,
-        )
-        .expect("path");
+        )?;
 
         path.reverse();
 
-        path
+        Some(path)
     }
 }
 
-pub struct Path(Vec<Vector2<u32>>);
-
 #[aoc_generator(day12)]
-fn day12_input(input: &str) -> HeightMap {
-    let mut width = 0;
-    let mut height = 0;
-    let mut start_position = Vector2::zeros();
-    let mut best_signal = Vector2::zeros();
-    let mut elevation_data = vec![];
-
-    for (y, line) in input.lines().enumerate() {
-        height += 1;
-        if width == 0 {
-            width = line.len() as i32;
-        }
-
-        for (x, mut c) in line.chars().enumerate() {
-            if c == 'S' {
-                start_position = Vector2::new(x as i32, y as i32);
-                c = 'a';
-            }
-            else if c == 'E' {
-                best_signal = Vector2::new(x as i32, y as i32);
-                c = 'z';
-            }
-            let elevation: u8 = (u32::from(c) - u32::from('a')).try_into().unwrap();
-            elevation_data.push(elevation);
-        }
-    }
-
-    HeightMap {
-        elevation_data,
-        width,
-        height,
-        start_position,
-        best_signal,
-    }
+pub fn day12_input(input: &str) -> Result<HeightMap, Error> {
+    let (elevations, markers) =
+        Grid::parse_with_markers(input, &['S', 'E'], "an elevation", |c| {
+            let c = match c {
+                'S' => 'a',
+                'E' => 'z',
+                c => c,
+            };
+            c.is_ascii_lowercase().then(|| c as u8 - b'a')
+        })?;
+
+    Ok(HeightMap {
+        elevations,
+        start_position: markers.single('S', "start position")?,
+        best_signal: markers.single('E', "best signal position")?,
+    })
 }
 
 #[aoc(day12, part1)]
-fn day12_part1(height_map: &HeightMap) -> usize {
-    let path = height_map.shortest_path_to_best_signal();
+pub fn day12_part1(height_map: &HeightMap) -> Result<usize, Error> {
+    let path = height_map
+        .shortest_path_to_best_signal()
+        .ok_or_else(|| Error::NoSolution("no path to best signal".to_owned()))?;
 
     // note: the path contains the start and end position, so the number of steps is
     // exactly one less than the number of nodes visited.
-    path.len() - 1
+    Ok(path.len() - 1)
 }
 
 #[aoc(day12, part2)]
-fn day12_part2(height_map: &HeightMap) -> usize {
-    let path = height_map.shortest_path_from_lowest_elevation();
-    path.len() - 1
+pub fn day12_part2(height_map: &HeightMap) -> Result<usize, Error> {
+    let path = height_map
+        .shortest_path_from_lowest_elevation()
+        .ok_or_else(|| Error::NoSolution("no path from lowest elevation".to_owned()))?;
+    Ok(path.len() - 1)
+}
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    const DAY: u8 = 12;
+
+    type Input = HeightMap;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        day12_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Part1, Error> {
+        day12_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Part2, Error> {
+        day12_part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "Sabqponm
+abcryxxl
+accszExk
+acctuvwj
+abdefghi";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(day12_part1(&day12_input(EXAMPLE).unwrap()).unwrap(), 31);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(day12_part2(&day12_input(EXAMPLE).unwrap()).unwrap(), 29);
+    }
+
+    #[test]
+    fn two_start_positions() {
+        let error = day12_input("SbS\nabE").unwrap_err();
+        assert!(matches!(error, Error::InvalidInput(_)));
+    }
 }
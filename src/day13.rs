@@ -2,38 +2,62 @@ use std::{
     cmp::Ordering,
     iter::Peekable,
     str::{
-        Chars,
+        CharIndices,
         FromStr,
     },
 };
 
-use thiserror::Error;
-
-#[derive(Debug, Error)]
-pub enum ParseError {
-    #[error("unexpected end of input")]
-    UnexpectedEnd,
-    #[error("unexpected character: {0}")]
-    UnexpectedCharacter(char),
-}
+use crate::{
+    error::Error,
+    input,
+    num::{
+        self,
+        Number,
+    },
+    solution::Solution,
+};
 
 pub struct Parser<'a> {
-    chars: Peekable<Chars<'a>>,
+    s: &'a str,
+    chars: Peekable<CharIndices<'a>>,
 }
 
 impl<'a> Parser<'a> {
     pub fn new(s: &'a str) -> Self {
         Self {
-            chars: s.chars().peekable(),
+            s,
+            chars: s.char_indices().peekable(),
         }
     }
 
-    pub fn parse_number(&mut self) -> Result<i32, ParseError> {
-        let mut token = String::new();
+    fn unexpected_character(&self, i: usize, c: char) -> Error {
+        Error::parse(
+            1,
+            self.s,
+            &self.s[i..i + c.len_utf8()],
+            "unexpected character",
+        )
+    }
+
+    fn unexpected_end(&self) -> Error {
+        Error::parse(
+            1,
+            self.s,
+            &self.s[self.s.len()..],
+            "unexpected end of packet",
+        )
+    }
+
+    pub fn parse_number<T: Number>(&mut self) -> Result<T, Error> {
+        let start = match self.chars.peek() {
+            Some((i, _)) => *i,
+            None => return Err(self.unexpected_end()),
+        };
+        let mut end = start;
 
-        while let Some(c) = self.chars.peek() {
-            if c.is_numeric() {
-                token.push(*c);
+        while let Some((i, c)) = self.chars.peek() {
+            if c.is_ascii_digit() {
+                end = *i + 1;
                 self.chars.next();
             }
             else {
@@ -41,28 +65,35 @@ impl<'a> Parser<'a> {
             }
         }
 
+        let token = &self.s[start..end];
+
         if token.is_empty() {
-            Err(ParseError::UnexpectedEnd)
+            Err(self.unexpected_end())
         }
         else {
-            // token consists of numeric chars, so should parse correctly
-            let number = token.parse().unwrap();
-            Ok(number)
+            token.parse().map_err(|_| {
+                Error::parse(
+                    1,
+                    self.s,
+                    token,
+                    format!("number out of range of {}", num::type_name::<T>()),
+                )
+            })
         }
     }
 
-    pub fn parse_list(&mut self) -> Result<Vec<Value>, ParseError> {
+    pub fn parse_list<T: Number>(&mut self) -> Result<Vec<Value<T>>, Error> {
         match self.chars.next() {
-            Some('[') => {}
-            Some(c) => return Err(ParseError::UnexpectedCharacter(c)),
+            Some((_, '[')) => {}
+            Some((i, c)) => return Err(self.unexpected_character(i, c)),
             None => {
-                return Err(ParseError::UnexpectedEnd);
+                return Err(self.unexpected_end());
             }
         }
 
         let mut values = vec![];
 
-        while let Some(c) = self.chars.peek() {
+        while let Some((_, c)) = self.chars.peek() {
             match c {
                 ']' => {
                     self.chars.next();
@@ -72,10 +103,10 @@ impl<'a> Parser<'a> {
                     values.push(self.parse_value()?);
 
                     match self.chars.next() {
-                        Some(']') => break,
-                        Some(',') => {}
-                        Some(c) => return Err(ParseError::UnexpectedCharacter(c)),
-                        None => return Err(ParseError::UnexpectedEnd),
+                        Some((_, ']')) => break,
+                        Some((_, ',')) => {}
+                        Some((i, c)) => return Err(self.unexpected_character(i, c)),
+                        None => return Err(self.unexpected_end()),
                     }
                 }
             }
@@ -84,66 +115,76 @@ impl<'a> Parser<'a> {
         Ok(values)
     }
 
-    pub fn parse_value(&mut self) -> Result<Value, ParseError> {
-        let value = match self.chars.peek() {
-            Some('[') => Value::List(self.parse_list()?),
-            Some(c) => {
-                if c.is_numeric() {
+    pub fn parse_value<T: Number>(&mut self) -> Result<Value<T>, Error> {
+        let value = match self.chars.peek().copied() {
+            Some((_, '[')) => Value::List(self.parse_list()?),
+            Some((i, c)) => {
+                if c.is_ascii_digit() {
                     Value::Number(self.parse_number()?)
                 }
                 else {
-                    return Err(ParseError::UnexpectedCharacter(*c));
+                    return Err(self.unexpected_character(i, c));
                 }
             }
-            None => return Err(ParseError::UnexpectedEnd),
+            None => return Err(self.unexpected_end()),
         };
 
         Ok(value)
     }
+
+    /// checks that the whole input was parsed.
+    pub fn finish(&mut self) -> Result<(), Error> {
+        match self.chars.next() {
+            Some((i, c)) => Err(self.unexpected_character(i, c)),
+            None => Ok(()),
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
-pub struct PacketPair([Packet; 2]);
+pub struct PacketPair<T = u64>([Packet<T>; 2]);
 
-impl PacketPair {
-    pub fn compare(&self) -> CompareResult {
+impl<T: Number> PacketPair<T> {
+    pub fn compare(&self) -> Option<CompareResult> {
         self.0[0].compare(&self.0[1])
     }
 
-    pub fn is_in_right_order(&self) -> bool {
-        match self.compare() {
-            CompareResult::RightOrder => true,
-            CompareResult::WrongOrder => false,
+    pub fn is_in_right_order(&self) -> Option<bool> {
+        match self.compare()? {
+            CompareResult::RightOrder => Some(true),
+            CompareResult::WrongOrder => Some(false),
         }
     }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
-pub struct Packet(Vec<Value>);
+pub struct Packet<T = u64>(Vec<Value<T>>);
 
-impl Packet {
-    pub fn compare(&self, other: &Self) -> CompareResult {
-        compare_lists(&self.0, &other.0).unwrap()
+impl<T: Number> Packet<T> {
+    /// compares two packets. returns `None` if they are equal.
+    pub fn compare(&self, other: &Self) -> Option<CompareResult> {
+        compare_lists(&self.0, &other.0)
     }
 }
 
-impl FromStr for Packet {
-    type Err = ParseError;
+impl<T: Number> FromStr for Packet<T> {
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut parser = Parser::new(s);
         let values = parser.parse_list()?;
+        parser.finish()?;
         Ok(Packet(values))
     }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
-pub enum Value {
-    Number(i32),
-    List(Vec<Value>),
+pub enum Value<T = u64> {
+    Number(T),
+    List(Vec<Value<T>>),
 }
 
-impl Value {
+impl<T: Number> Value<T> {
     pub fn compare(&self, other: &Self) -> Option<CompareResult> {
         match (self, other) {
             (Value::Number(left), Value::Number(right)) => {
@@ -155,20 +196,20 @@ impl Value {
             }
             (Value::List(left), Value::List(right)) => compare_lists(left, right),
             (Value::Number(left), Value::List(right)) => {
-                let left = [Value::Number(*left)];
+                let left = [Value::Number(left.clone())];
                 compare_lists(&left, right)
             }
             (Value::List(left), Value::Number(right)) => {
-                let right = [Value::Number(*right)];
+                let right = [Value::Number(right.clone())];
                 compare_lists(left, &right)
             }
         }
     }
 }
 
-pub fn compare_lists(left: &[Value], right: &[Value]) -> Option<CompareResult> {
-    let mut left_iter = left.into_iter();
-    let mut right_iter = right.into_iter();
+pub fn compare_lists<T: Number>(left: &[Value<T>], right: &[Value<T>]) -> Option<CompareResult> {
+    let mut left_iter = left.iter();
+    let mut right_iter = right.iter();
 
     loop {
         match (left_iter.next(), right_iter.next()) {
@@ -192,41 +233,68 @@ pub enum CompareResult {
     WrongOrder,
 }
 
-#[aoc_generator(day13)]
-fn day13_input(input: &str) -> Vec<PacketPair> {
-    let mut lines = input.lines();
+/// parses the pairs of packets with numbers of type `T`.
+pub fn parse_packet_pairs<T: Number>(input: &str) -> Result<Vec<PacketPair<T>>, Error> {
+    let mut lines = input::lines(input);
     let mut packet_pairs = vec![];
 
+    let parse_packet = |line: input::Line| -> Result<Packet<T>, Error> {
+        line.text.parse().map_err(|e: Error| e.at_line(line.number))
+    };
+
     while let Some(first_line) = lines.next() {
-        let second_line = lines.next().unwrap();
+        let second_line = lines.next_line("second packet of pair")?;
 
-        let first_packet = first_line.parse().unwrap();
-        let second_packet = second_line.parse().unwrap();
+        let first_packet = parse_packet(first_line)?;
+        let second_packet = parse_packet(second_line)?;
 
         let packet_pair = PacketPair([first_packet, second_packet]);
         packet_pairs.push(packet_pair);
 
         // separator
-        lines.next();
+        if let Some(line) = lines.next() {
+            if !line.text.is_empty() {
+                return Err(line.error(line.text, "expected an empty line"));
+            }
+        }
     }
 
-    packet_pairs
+    Ok(packet_pairs)
+}
+
+#[aoc_generator(day13)]
+pub fn day13_input(input: &str) -> Result<Vec<PacketPair>, Error> {
+    parse_packet_pairs(input)
 }
 
 #[aoc(day13, part1)]
-fn day13_part1(packet_pairs: &[PacketPair]) -> usize {
+pub fn day13_part1(packet_pairs: &[PacketPair]) -> Result<usize, Error> {
+    sum_of_ordered_pairs(packet_pairs)
+}
+
+#[aoc(day13, part2)]
+pub fn day13_part2(packet_pairs: &[PacketPair]) -> Result<usize, Error> {
+    decoder_key(packet_pairs)
+}
+
+/// returns the sum of the indices of the pairs that are in the right order.
+pub fn sum_of_ordered_pairs<T: Number>(packet_pairs: &[PacketPair<T>]) -> Result<usize, Error> {
     let mut sum = 0;
-    for (i, pair) in packet_pairs.into_iter().enumerate() {
-        if pair.is_in_right_order() {
+    for (i, pair) in packet_pairs.iter().enumerate() {
+        let is_in_right_order = pair
+            .is_in_right_order()
+            .ok_or_else(|| Error::InvalidInput(format!("packets of pair {} are equal", i + 1)))?;
+        if is_in_right_order {
             sum += i + 1;
         }
     }
 
-    sum
+    Ok(sum)
 }
 
-#[aoc(day13, part2)]
-fn day13_part2(packet_pairs: &[PacketPair]) -> usize {
+/// sorts all packets together with the divider packets, and returns the
+/// product of the indices of the divider packets.
+pub fn decoder_key<T: Number>(packet_pairs: &[PacketPair<T>]) -> Result<usize, Error> {
     let mut packets = vec![];
 
     for pair in packet_pairs {
@@ -234,15 +302,16 @@ fn day13_part2(packet_pairs: &[PacketPair]) -> usize {
         packets.push(pair.0[1].clone());
     }
 
-    let divider_packet_2: Packet = "[[2]]".parse().unwrap();
-    let divider_packet_6: Packet = "[[6]]".parse().unwrap();
+    let divider_packet_2: Packet<T> = "[[2]]".parse()?;
+    let divider_packet_6: Packet<T> = "[[6]]".parse()?;
     packets.push(divider_packet_2.clone());
     packets.push(divider_packet_6.clone());
 
     packets.sort_by(|left, right| {
         match left.compare(right) {
-            CompareResult::RightOrder => Ordering::Less,
-            CompareResult::WrongOrder => Ordering::Greater,
+            Some(CompareResult::RightOrder) => Ordering::Less,
+            Some(CompareResult::WrongOrder) => Ordering::Greater,
+            None => Ordering::Equal,
         }
     });
 
@@ -257,5 +326,118 @@ fn day13_part2(packet_pairs: &[PacketPair]) -> usize {
         }
     }
 
-    divider_packet_indices[0].unwrap() * divider_packet_indices[1].unwrap()
+    // both divider packets were added above, so they are always found.
+    Ok(divider_packet_indices[0].unwrap() * divider_packet_indices[1].unwrap())
+}
+
+pub struct Day13;
+
+impl Solution for Day13 {
+    const DAY: u8 = 13;
+
+    type Input = Vec<PacketPair>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        day13_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Part1, Error> {
+        day13_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Part2, Error> {
+        day13_part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "[1,1,3,1,1]
+[1,1,5,1,1]
+
+[[1],[2,3,4]]
+[[1],4]
+
+[9]
+[[8,7,6]]
+
+[[4,4],4,4]
+[[4,4],4,4,4]
+
+[7,7,7,7]
+[7,7,7]
+
+[]
+[3]
+
+[[[]]]
+[[]]
+
+[1,[2,[3,[4,[5,6,7]]]],8,9]
+[1,[2,[3,[4,[5,6,0]]]],8,9]";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(day13_part1(&day13_input(EXAMPLE).unwrap()).unwrap(), 13);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(day13_part2(&day13_input(EXAMPLE).unwrap()).unwrap(), 140);
+    }
+
+    #[test]
+    fn unexpected_character() {
+        let error = day13_input("[1,2]\n[1,x]").unwrap_err();
+        assert!(matches!(
+            error,
+            Error::Parse {
+                line: 2,
+                column: 4,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn unexpected_end() {
+        let error = day13_input("[1,2]\n[1,[2]").unwrap_err();
+        assert!(matches!(
+            error,
+            Error::Parse {
+                line: 2,
+                column: 7,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn large_numbers() {
+        let input = "[[18446744073709551616]]\n[[18446744073709551617],1]";
+        assert!(matches!(
+            day13_input(input),
+            Err(Error::Parse {
+                line: 1,
+                column: 3,
+                ..
+            })
+        ));
+
+        let packet_pairs = parse_packet_pairs::<u128>(input).unwrap();
+        assert_eq!(sum_of_ordered_pairs(&packet_pairs).unwrap(), 1);
+        assert_eq!(decoder_key(&packet_pairs).unwrap(), 2);
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn bigint() {
+        let input = format!("[{0}0]\n[{0}1,[]]", u128::MAX);
+        let packet_pairs = parse_packet_pairs::<num_bigint::BigUint>(&input).unwrap();
+        assert_eq!(sum_of_ordered_pairs(&packet_pairs).unwrap(), 1);
+    }
 }
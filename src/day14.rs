@@ -1,9 +1,38 @@
-use std::collections::BTreeMap;
+use std::str::FromStr;
 
 use itertools::Itertools;
-use nalgebra::Vector2;
-
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+use num_traits::Signed;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use tracing::trace;
+
+use crate::{
+    error::Error,
+    geometry::{
+        BoundingBox,
+        Coordinate,
+        Direction,
+        Point2,
+    },
+    grid::Grid,
+    input,
+    num,
+    render::{
+        Animation,
+        Cell,
+        Frame,
+        FrameSink,
+        Render,
+        RenderError,
+        Rgb,
+    },
+    simulation::Simulation,
+    solution::Solution,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Tile {
     Empty,
     Rock,
@@ -17,124 +46,101 @@ pub enum SimulationOutcome {
     SourceBlocked,
 }
 
-#[derive(Clone, Debug)]
-pub struct Sandbox {
-    tiles: BTreeMap<(i32, i32), Tile>,
-    source: Vector2<i32>,
-    min: Vector2<i32>,
-    max: Vector2<i32>,
+/// a cave with positions of type `T`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Sandbox<T = i32> {
+    /// the part of the cave that sand can reach, with `origin` in its top left
+    /// corner.
+    tiles: Grid<Tile>,
+    origin: Point2<T>,
+    source: Point2<T>,
+    /// the area spanned by the source and the rock. sand below it falls into
+    /// the void, or onto the floor 2 units below.
+    rock_bounds: BoundingBox<T>,
     floor: bool,
+    /// the number of grains that came to rest.
+    num_sand: usize,
+    /// whether a grain didn't come to rest anymore.
+    finished: bool,
 }
 
-impl Sandbox {
-    pub fn from_rock_paths(paths: &RockPaths, floor: bool) -> Self {
-        let mut tiles = BTreeMap::new();
-        let source = Vector2::new(500, 0);
-        let mut min: Vector2<i32> = source;
-        let mut max: Vector2<i32> = source;
+impl<T: Coordinate + Signed> Sandbox<T> {
+    pub fn from_rock_paths(paths: &RockPaths<T>, floor: bool) -> Self {
+        let mut rock = vec![];
 
-        let mut add_tile = |x, y| {
-            // insert tile
-            tiles.insert((x, y), Tile::Rock);
-
-            // track bounding box of sandbox
-            if x < min.x {
-                min.x = x;
-            }
-            if y < min.y {
-                min.y = y;
-            }
-            if x > max.x {
-                max.x = x;
-            }
-            if y > max.y {
-                max.y = y;
-            }
-        };
+        let mut add_tile = |position| rock.push(position);
 
         for path in &paths.0 {
-            for (a, b) in path.into_iter().tuple_windows() {
-                if a.x < b.x {
-                    // horizontal
-                    for x in a.x..=b.x {
-                        add_tile(x, a.y);
-                    }
-                }
-                else if a.x > b.x {
-                    // horizontal
-                    for x in b.x..=a.x {
-                        add_tile(x, a.y);
-                    }
-                }
-                else if a.y < b.y {
-                    // vertical
-                    for y in a.y..=b.y {
-                        add_tile(a.x, y);
-                    }
-                }
-                else if a.y > b.y {
-                    // vertical
-                    for y in b.y..=a.y {
-                        add_tile(a.x, y);
-                    }
-                }
-                else {
-                    panic!(
-                        "start and end position of rock are the same: {:?}, {:?}",
-                        a, b
-                    );
+            for (a, b) in path.iter().tuple_windows() {
+                // rock paths are validated by the generator, so this is a unit
+                // step along one axis.
+                let step = (*b - *a).signum();
+                let mut position = *a;
+                add_tile(position);
+                while position != *b {
+                    position += step;
+                    add_tile(position);
                 }
             }
         }
 
-        Sandbox {
-            tiles,
+        let (source, rock_bounds, cave) =
+            paths.cave().expect("the cave is checked by the generator");
+        let size = |size: T| {
+            size.to_usize()
+                .expect("the cave is checked by the generator")
+        };
+
+        let mut sandbox = Sandbox {
+            tiles: Grid::new(size(cave.width()), size(cave.height()), Tile::Empty)
+                .expect("the cave is checked by the generator"),
+            origin: cave.min,
             source,
-            min,
-            max,
+            rock_bounds,
             floor,
+            num_sand: 0,
+            finished: false,
+        };
+        for position in rock {
+            sandbox.set_tile(position, Tile::Rock);
         }
-    }
 
-    pub fn get_tile(&self, position: Vector2<i32>) -> Tile {
-        self.tiles
-            .get(&(position.x, position.y))
-            .copied()
-            .unwrap_or(Tile::Empty)
+        sandbox
     }
 
-    pub fn set_tile(&mut self, position: Vector2<i32>, tile: Tile) {
-        self.tiles.insert((position.x, position.y), tile);
+    /// converts a position in the cave to a position in `tiles`.
+    fn grid_position(&self, position: Point2<T>) -> Option<Point2<usize>> {
+        Point2::new(
+            position.x.checked_sub(&self.origin.x)?,
+            position.y.checked_sub(&self.origin.y)?,
+        )
+        .cast()
     }
 
-    pub fn print(&self) {
-        for y in self.min.y - 2..=self.max.y + 2 {
-            print!("{:>3} ", y);
+    /// returns the tile at `position`. everything outside of the area that
+    /// sand can reach is empty.
+    pub fn get_tile(&self, position: Point2<T>) -> Tile {
+        self.grid_position(position)
+            .and_then(|position| self.tiles.get(position))
+            .copied()
+            .unwrap_or(Tile::Empty)
+    }
 
-            for x in self.min.x - 2..=self.max.x + 2 {
-                match self.get_tile(Vector2::new(x, y)) {
-                    Tile::Empty => {
-                        if x == self.source.x && y == self.source.y {
-                            print!("+")
-                        }
-                        else if self.floor && y == self.max.y + 2 {
-                            print!("#")
-                        }
-                        else {
-                            print!(".")
-                        }
-                    }
-                    Tile::Rock => print!("#"),
-                    Tile::Sand => print!("o"),
-                }
-            }
-            println!("");
+    /// sets the tile at `position`.
+    ///
+    /// panics if `position` is outside of the area that sand can reach.
+    pub fn set_tile(&mut self, position: Point2<T>, tile: Tile) {
+        let cell = self
+            .grid_position(position)
+            .and_then(|grid_position| self.tiles.get_mut(grid_position));
+        match cell {
+            Some(cell) => *cell = tile,
+            None => panic!("{:?} is out of reach of the sand", position),
         }
-        println!("");
     }
 
     /// returns whether the sand fell into the void
-    pub fn simulate_sand_particle(&mut self) -> SimulationOutcome {
+    fn simulate_sand_particle(&mut self) -> SimulationOutcome {
         if self.get_tile(self.source) == Tile::Sand {
             return SimulationOutcome::SourceBlocked;
         }
@@ -142,9 +148,9 @@ impl Sandbox {
         let mut sand_position = self.source;
 
         loop {
-            let down = sand_position + Vector2::new(0, 1);
-            let down_left = sand_position + Vector2::new(-1, 1);
-            let down_right = sand_position + Vector2::new(1, 1);
+            let down = sand_position.step(Direction::Down);
+            let down_left = down.step(Direction::Left);
+            let down_right = down.step(Direction::Right);
 
             if self.get_tile(down) == Tile::Empty {
                 sand_position = down;
@@ -163,7 +169,7 @@ impl Sandbox {
 
             if self.floor {
                 // part 2
-                if sand_position.y == self.max.y + 1 {
+                if sand_position.y == self.rock_bounds.max.y + T::one() {
                     // sand can't move anymore
                     self.set_tile(sand_position, Tile::Sand);
                     return SimulationOutcome::SandRests;
@@ -171,65 +177,366 @@ impl Sandbox {
             }
             else {
                 // part 1
-                if sand_position.y > self.max.y {
+                if sand_position.y > self.rock_bounds.max.y {
                     return SimulationOutcome::SandFellIntoVoid;
                 }
             }
         }
     }
+
+    /// drops sand until a grain doesn't come to rest anymore, and returns the
+    /// number of grains that came to rest.
+    pub fn fill(&mut self) -> usize {
+        self.run_with(&mut |sandbox: &Self| {
+            trace!(num_sand = sandbox.num_sand, "sand came to rest");
+        });
+
+        self.num_sand
+    }
 }
 
-pub struct RockPaths(Vec<Vec<Vector2<i32>>>);
+/// a step is a grain of sand that comes to rest.
+impl<T: Coordinate + Signed> Simulation for Sandbox<T> {
+    fn step(&mut self) -> bool {
+        if self.finished {
+            return false;
+        }
 
-#[aoc_generator(day14)]
-fn day14_input(input: &str) -> RockPaths {
+        if self.simulate_sand_particle() == SimulationOutcome::SandRests {
+            self.num_sand += 1;
+            true
+        }
+        else {
+            self.finished = true;
+            false
+        }
+    }
+
+    fn steps(&self) -> usize {
+        self.num_sand
+    }
+
+    fn is_finished(&self) -> bool {
+        self.finished
+    }
+}
+
+const EMPTY: Cell = Cell::new('.', Rgb(16, 16, 24));
+const ROCK: Cell = Cell::new('#', Rgb(120, 120, 130));
+const SAND: Cell = Cell::new('o', Rgb(230, 190, 90));
+const SOURCE: Cell = Cell::new('+', Rgb(255, 64, 64));
+
+impl Render for Sandbox {
+    fn bounds(&self) -> BoundingBox<i32> {
+        let mut area = self.rock_bounds;
+        for (position, tile) in self.tiles.iter() {
+            if *tile == Tile::Sand {
+                let position = position.cast::<i32>().expect("the cave fits into i32");
+                area.include(self.origin + position);
+            }
+        }
+        if self.floor {
+            area.include(Point2::new(area.min.x, self.rock_bounds.max.y + 2));
+        }
+        area.expand(1)
+    }
+
+    fn render_area(&self, area: BoundingBox<i32>) -> Frame {
+        let mut frame = Frame::for_area(area, EMPTY);
+
+        for y in area.min.y..=area.max.y {
+            for x in area.min.x..=area.max.x {
+                let position = Point2::new(x, y);
+                let cell = match self.get_tile(position) {
+                    Tile::Empty => {
+                        if position == self.source {
+                            SOURCE
+                        }
+                        else if self.floor && y == self.rock_bounds.max.y + 2 {
+                            ROCK
+                        }
+                        else {
+                            continue;
+                        }
+                    }
+                    Tile::Rock => ROCK,
+                    Tile::Sand => SAND,
+                };
+                frame.set_point(area, position, cell);
+            }
+        }
+
+        frame
+    }
+}
+
+/// pours sand into the cave and pushes a frame after every grain that comes
+/// to rest.
+///
+/// every frame shows the area of the filled cave.
+pub fn animate_sand(
+    rock_paths: &RockPaths,
+    floor: bool,
+    sink: &mut dyn FrameSink,
+) -> Result<(), RenderError> {
+    let mut sandbox = Sandbox::from_rock_paths(rock_paths, floor);
+
+    let mut filled = sandbox.clone();
+    filled.fill();
+    let area = filled.bounds();
+
+    let mut animation = Animation::new(sink, area);
+    sandbox.run_with(&mut animation);
+    animation.finish()
+}
+
+/// the paths of rock with positions of type `T`. the generator checks that
+/// every position that sand can reach fits into `T`, and that the cave isn't
+/// too large to simulate.
+#[derive(Clone, Debug)]
+pub struct RockPaths<T = i32>(Vec<Vec<Point2<T>>>);
+
+impl<T: Coordinate + Signed> RockPaths<T> {
+    /// returns the source of the sand, the area spanned by it and the rock,
+    /// and the area that sand can reach. returns `None` if any of them, or a
+    /// position right next to them, doesn't fit into `T`.
+    fn cave(&self) -> Option<(Point2<T>, BoundingBox<T>, BoundingBox<T>)> {
+        let source = Point2::new(T::from(500)?, T::zero());
+
+        let mut rock_bounds = BoundingBox::from_point(source);
+        for position in self.0.iter().flatten() {
+            rock_bounds.include(*position);
+        }
+
+        // sand moves at most one step sideways per step down, and never gets
+        // deeper than the floor.
+        let two = T::one() + T::one();
+        let floor_y = rock_bounds.max.y.checked_add(&two)?;
+        let mut cave = rock_bounds;
+        cave.include(Point2::new(source.x.checked_sub(&floor_y)?, floor_y));
+        cave.include(Point2::new(source.x.checked_add(&floor_y)?, floor_y));
+
+        // sand looks at the positions right next to the cave, and positions in
+        // the cave are stored relative to its top left corner.
+        let one = T::one();
+        let min_x = cave.min.x.checked_sub(&one)?;
+        let max_x = cave.max.x.checked_add(&one)?;
+        let max_y = cave.max.y.checked_add(&one)?;
+        max_x.checked_sub(&min_x)?;
+        max_y.checked_sub(&cave.min.y)?;
+
+        Some((source, rock_bounds, cave))
+    }
+}
+
+/// parses the rock paths with positions of type `T`, and checks that the cave
+/// fits into `T` and into a [`Grid`].
+pub fn parse_rock_paths<T: Coordinate + Signed + FromStr>(
+    input: &str,
+) -> Result<RockPaths<T>, Error> {
     let mut rock_paths = vec![];
 
-    for line in input.lines() {
-        let mut path = vec![];
+    for line in input::lines(input) {
+        let mut path: Vec<Point2<T>> = vec![];
+
+        for point_str in line.text.split(" -> ") {
+            let (x, y) = point_str
+                .split_once(',')
+                .ok_or_else(|| line.error(point_str, "expected a point like 498,4"))?;
+            let point = Point2::new(
+                line.parse(x, "an x coordinate")?,
+                line.parse(y, "a y coordinate")?,
+            );
+
+            if let Some(previous) = path.last() {
+                if *previous == point {
+                    return Err(line.error(point_str, "rock can't end where it starts"));
+                }
+                if previous.x != point.x && previous.y != point.y {
+                    return Err(line.error(point_str, "rock must be horizontal or vertical"));
+                }
+            }
 
-        for point in line.split(" -> ") {
-            let (x, y) = point.split_once(",").unwrap();
-            let point: Vector2<i32> = Vector2::new(x.parse().unwrap(), y.parse().unwrap());
             path.push(point);
         }
 
         rock_paths.push(path);
     }
 
-    RockPaths(rock_paths)
+    let rock_paths = RockPaths(rock_paths);
+    let (_, _, cave) = rock_paths
+        .cave()
+        .ok_or_else(|| num::overflow::<T>("the cave"))?;
+
+    // the cave is simulated on a dense grid, so it also has to fit into memory.
+    let (width, height) = (cave.width(), cave.height());
+    let num_cells = width
+        .to_usize()
+        .zip(height.to_usize())
+        .and_then(|(width, height)| Grid::<Tile>::num_cells(width, height));
+    if num_cells.is_none() {
+        return Err(Error::InvalidInput(format!(
+            "the cave is too large to simulate: {:?}x{:?}",
+            width, height
+        )));
+    }
+
+    Ok(rock_paths)
+}
+
+/// fills the cave and logs how it looks afterwards.
+fn fill_cave(rock_paths: &RockPaths, floor: bool) -> usize {
+    let mut sandbox = Sandbox::from_rock_paths(rock_paths, floor);
+    let num_sand = sandbox.fill();
+
+    trace!(
+        "cave after {} units of sand came to rest:\n{}",
+        num_sand,
+        sandbox.render()
+    );
+
+    num_sand
+}
+
+#[aoc_generator(day14)]
+pub fn day14_input(input: &str) -> Result<RockPaths, Error> {
+    parse_rock_paths(input)
 }
 
 #[aoc(day14, part1)]
-fn day14_part1(rock_paths: &RockPaths) -> usize {
-    let mut sandbox = Sandbox::from_rock_paths(rock_paths, false);
-    let mut num_sand = 0;
+pub fn day14_part1(rock_paths: &RockPaths) -> Result<usize, Error> {
+    Ok(fill_cave(rock_paths, false))
+}
+
+#[aoc(day14, part2)]
+pub fn day14_part2(rock_paths: &RockPaths) -> Result<usize, Error> {
+    Ok(fill_cave(rock_paths, true))
+}
+
+pub struct Day14;
 
-    //sandbox.print();
+impl Solution for Day14 {
+    const DAY: u8 = 14;
 
-    while sandbox.simulate_sand_particle() != SimulationOutcome::SandFellIntoVoid {
-        num_sand += 1;
+    type Input = RockPaths;
+    type Part1 = usize;
+    type Part2 = usize;
 
-        //println!("sand particles: {}", num_sand);
-        //sandbox.print();
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        day14_input(input)
     }
 
-    num_sand
+    fn part1(input: &Self::Input) -> Result<Self::Part1, Error> {
+        day14_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Part2, Error> {
+        day14_part2(input)
+    }
 }
 
-#[aoc(day14, part2)]
-fn day14_part2(rock_paths: &RockPaths) -> usize {
-    let mut sandbox = Sandbox::from_rock_paths(rock_paths, true);
-    let mut num_sand = 0;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    //sandbox.print();
+    const EXAMPLE: &str = "498,4 -> 498,6 -> 496,6
+503,4 -> 502,4 -> 502,9 -> 494,9";
 
-    while sandbox.simulate_sand_particle() != SimulationOutcome::SourceBlocked {
-        num_sand += 1;
+    #[test]
+    fn part1_example() {
+        assert_eq!(day14_part1(&day14_input(EXAMPLE).unwrap()).unwrap(), 24);
     }
 
-    //println!("sand particles: {}", num_sand);
-    //sandbox.print();
+    #[test]
+    fn part2_example() {
+        assert_eq!(day14_part2(&day14_input(EXAMPLE).unwrap()).unwrap(), 93);
+    }
 
-    num_sand
+    #[test]
+    fn render_example() {
+        let mut sandbox = Sandbox::from_rock_paths(&day14_input(EXAMPLE).unwrap(), false);
+        sandbox.fill();
+        assert_eq!(
+            sandbox.render().to_ascii(),
+            "............
+.......+....
+............
+.......o....
+......ooo...
+.....#ooo##.
+....o#ooo#..
+...###ooo#..
+.....oooo#..
+..o.ooooo#..
+.#########..
+............
+"
+        );
+    }
+
+    #[test]
+    fn animate_example() {
+        let mut frames = vec![];
+        animate_sand(&day14_input(EXAMPLE).unwrap(), true, &mut frames).unwrap();
+
+        // the empty cave and one frame per grain
+        assert_eq!(frames.len(), 94);
+        assert!(frames[1..]
+            .iter()
+            .all(|frame| frame.width() == frames[0].width()));
+    }
+
+    #[test]
+    fn coordinate_types() {
+        let rock_paths = parse_rock_paths::<i16>(EXAMPLE).unwrap();
+        assert_eq!(Sandbox::from_rock_paths(&rock_paths, false).fill(), 24);
+        assert_eq!(Sandbox::from_rock_paths(&rock_paths, true).fill(), 93);
+
+        let rock_paths = parse_rock_paths::<i64>(EXAMPLE).unwrap();
+        assert_eq!(Sandbox::from_rock_paths(&rock_paths, true).fill(), 93);
+    }
+
+    #[test]
+    fn overflow() {
+        // the source at x = 500 doesn't fit
+        assert!(matches!(
+            parse_rock_paths::<i8>("1,4 -> 1,6"),
+            Err(Error::Overflow(_))
+        ));
+        // neither does the floor below the rock
+        assert!(matches!(
+            parse_rock_paths::<i16>("498,32765 -> 502,32765"),
+            Err(Error::Overflow(_))
+        ));
+        // nor the width of the area the sand spreads over
+        assert!(matches!(
+            parse_rock_paths::<i16>("498,20000 -> 502,20000"),
+            Err(Error::Overflow(_))
+        ));
+        assert!(parse_rock_paths::<i32>("498,20000 -> 502,20000").is_ok());
+    }
+
+    #[test]
+    fn cave_too_large() {
+        // the positions fit into `i32`, but the cave spans two billion columns
+        let error = day14_input("2000000000,5 -> 2000000000,6").unwrap_err();
+        assert!(matches!(error, Error::InvalidInput(_)));
+        assert!(matches!(
+            parse_rock_paths::<i32>("498,32765 -> 502,32765"),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn diagonal_rock() {
+        let error = day14_input("498,4 -> 498,6 -> 496,8").unwrap_err();
+        assert!(matches!(
+            error,
+            Error::Parse {
+                line: 1,
+                column: 19,
+                ..
+            }
+        ));
+    }
 }
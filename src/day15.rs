@@ -1,45 +1,74 @@
 use std::collections::HashSet;
 
 use lazy_static::lazy_static;
-use nalgebra::Vector2;
 use rangemap::RangeInclusiveSet;
 use regex::Regex;
+use tracing::trace;
+
+use crate::{
+    error::Error,
+    geometry::Point2,
+    input,
+    num,
+    solution::Solution,
+};
 
 lazy_static! {
     static ref SENSOR_SIGNAL_REGEX: Regex =
-        r"Sensor at x=(-?\d+), y=(-?\d+): closest beacon is at x=(-?\d+), y=(-?\d+)"
+        r"^Sensor at x=(-?\d+), y=(-?\d+): closest beacon is at x=(-?\d+), y=(-?\d+)$"
             .parse()
             .unwrap();
 }
 
-pub fn manhattan_distance(a: Vector2<i64>, b: Vector2<i64>) -> i64 {
-    (a.x - b.x).abs() + (a.y - b.y).abs()
-}
-
 pub struct Sensor {
-    position: Vector2<i64>,
-    closest_beacon: Vector2<i64>,
+    position: Point2<i64>,
+    closest_beacon: Point2<i64>,
 }
 
 impl Sensor {
+    pub fn position(&self) -> Point2<i64> {
+        self.position
+    }
+
+    pub fn closest_beacon(&self) -> Point2<i64> {
+        self.closest_beacon
+    }
+
     pub fn beacon_distance(&self) -> i64 {
-        manhattan_distance(self.position, self.closest_beacon)
+        self.position.manhattan_distance(self.closest_beacon)
     }
 }
 
 #[aoc_generator(day15)]
-fn day15_input(input: &str) -> Vec<Sensor> {
+pub fn day15_input(input: &str) -> Result<Vec<Sensor>, Error> {
     let mut sensor_signals = vec![];
-    for line in input.lines() {
-        let captures = SENSOR_SIGNAL_REGEX.captures(line).unwrap();
+    for line in input::lines(input) {
+        let captures = SENSOR_SIGNAL_REGEX
+            .captures(line.text)
+            .ok_or_else(|| line.error(line.text, "expected a sensor report"))?;
+        let coordinate = |i| line.parse(captures.get(i).unwrap().as_str(), "a coordinate");
 
-        let x = captures.get(1).unwrap().as_str().parse().unwrap();
-        let y = captures.get(2).unwrap().as_str().parse().unwrap();
-        let position = Vector2::new(x, y);
+        let position: Point2<i64> = Point2::new(coordinate(1)?, coordinate(2)?);
+        let closest_beacon: Point2<i64> = Point2::new(coordinate(3)?, coordinate(4)?);
 
-        let x = captures.get(3).unwrap().as_str().parse().unwrap();
-        let y = captures.get(4).unwrap().as_str().parse().unwrap();
-        let closest_beacon = Vector2::new(x, y);
+        // the rows covered by a sensor are computed in `i64`, so the area it
+        // covers has to fit.
+        let beacon_distance = position
+            .x
+            .abs_diff(closest_beacon.x)
+            .checked_add(position.y.abs_diff(closest_beacon.y))
+            .and_then(|distance| i64::try_from(distance).ok());
+        let fits = beacon_distance.is_some_and(|distance| {
+            [position.x, position.y]
+                .into_iter()
+                .all(|c| c.checked_sub(distance).is_some() && c.checked_add(distance).is_some())
+        });
+        if !fits {
+            return Err(num::overflow::<i64>(format!(
+                "the area covered by the sensor in line {}",
+                line.number
+            )));
+        }
 
         sensor_signals.push(Sensor {
             position,
@@ -47,12 +76,12 @@ fn day15_input(input: &str) -> Vec<Sensor> {
         })
     }
 
-    sensor_signals
+    Ok(sensor_signals)
 }
 
 pub struct Sensors<'a> {
     sensors: &'a [Sensor],
-    beacon_positions: HashSet<Vector2<i64>>,
+    beacon_positions: HashSet<Point2<i64>>,
 }
 
 impl<'a> Sensors<'a> {
@@ -76,65 +105,193 @@ impl<'a> Sensors<'a> {
         for sensor in self.sensors {
             // given the beacon distance we can compute the x range where beacons can't be.
             let beacon_distance = sensor.beacon_distance();
-            let y_distance = (sensor.position.y - y).abs();
-            let x_distance = beacon_distance - y_distance;
-
-            // there can't be another beacon at the same distance, so x_distance must be >=
-            // 0
-            if x_distance >= 0 {
-                //println!("sensor.position = {:?}", sensor.position);
-                //println!("sensor.closest_beacon = {:?}", sensor.closest_beacon);
-                //println!("beacon_distance = {}", beacon_distance);
-                //println!("y_distance = {}", y_distance);
-                //println!("x_distance = {}", x_distance);
+            let y_distance = sensor.position.y.abs_diff(y);
+
+            // there can't be another beacon at the same distance, so y_distance must be <=
+            // beacon_distance
+            if y_distance <= beacon_distance.unsigned_abs() {
+                // both fit, since the generator checked the area of the sensor
+                let y_distance = y_distance as i64;
+                let x_distance = beacon_distance - y_distance;
+                trace!(
+                    y,
+                    sensor = %sensor.position,
+                    closest_beacon = %sensor.closest_beacon,
+                    beacon_distance,
+                    y_distance,
+                    x_distance,
+                    "sensor covers row"
+                );
 
                 let lower = sensor.position.x - x_distance;
                 let higher = sensor.position.x + x_distance;
                 covered_positions.insert(lower..=higher);
-
-                //println!("");
             }
         }
 
         covered_positions
     }
 
-    pub fn num_covered_positions_for_row(&self, y: i64) -> i64 {
+    /// returns an error if there are more covered positions than fit into an
+    /// `i64`.
+    pub fn num_covered_positions_for_row(&self, y: i64) -> Result<i64, Error> {
         let covered_positions = self.covered_positions_for_row(y);
-        let mut n = 0;
+        let mut n: i64 = 0;
 
         for range in covered_positions.iter() {
-            n += range.end() - range.start();
+            n = range
+                .end()
+                .checked_sub(*range.start())
+                .and_then(|length| length.checked_add(1))
+                .and_then(|length| n.checked_add(length))
+                .ok_or_else(|| {
+                    num::overflow::<i64>(format!("the covered positions in row {}", y))
+                })?;
         }
 
-        n
+        // positions with a known beacon are covered, but can obviously contain a
+        // beacon.
+        for beacon in &self.beacon_positions {
+            if beacon.y == y && covered_positions.contains(&beacon.x) {
+                n -= 1;
+            }
+        }
+
+        Ok(n)
     }
 
-    pub fn find_distress_signal(&self, max_xy: i64) -> Vector2<i64> {
+    pub fn find_distress_signal(&self, max_xy: i64) -> Result<Point2<i64>, Error> {
         for y in 0..=max_xy {
             let covered_positions = self.covered_positions_for_row(y);
-            for gap in covered_positions.gaps(&(0..=max_xy)) {
-                assert!(gap.start() == gap.end());
-                return Vector2::new(*gap.start(), y);
+            if let Some(gap) = covered_positions.gaps(&(0..=max_xy)).next() {
+                if gap.start() != gap.end() {
+                    return Err(Error::NoSolution(format!(
+                        "distress signal could be anywhere from x={} to x={} in row {}",
+                        gap.start(),
+                        gap.end(),
+                        y
+                    )));
+                }
+                return Ok(Point2::new(*gap.start(), y));
             }
         }
 
-        panic!("no distress signal found")
+        Err(Error::NoSolution("no distress signal found".to_owned()))
     }
 }
 
-#[aoc(day15, part1)]
-fn day15_part1(sensors: &[Sensor]) -> i64 {
+/// row in which covered positions are counted for part 1
+const PART1_ROW: i64 = 2000000;
+
+/// maximum x and y coordinate of the distress signal for part 2
+const SEARCH_AREA: i64 = 4000000;
+
+fn num_positions_without_beacon(sensors: &[Sensor], y: i64) -> Result<i64, Error> {
     let sensors = Sensors::new(sensors);
-    //sensors.num_covered_positions_for_row(20)
-    sensors.num_covered_positions_for_row(2000000)
+    sensors.num_covered_positions_for_row(y)
 }
 
-#[aoc(day15, part2)]
-fn day15_part2(sensors: &[Sensor]) -> i64 {
+fn tuning_frequency(sensors: &[Sensor], max_xy: i64) -> Result<i64, Error> {
     let sensors = Sensors::new(sensors);
-    //let distress_signal = sensors.find_distress_signal(20);
-    let distress_signal = sensors.find_distress_signal(4000000);
+    let distress_signal = sensors.find_distress_signal(max_xy)?;
 
-    distress_signal.x * 4000000 + distress_signal.y
+    Ok(distress_signal.x * 4000000 + distress_signal.y)
+}
+
+#[aoc(day15, part1)]
+pub fn day15_part1(sensors: &[Sensor]) -> Result<i64, Error> {
+    num_positions_without_beacon(sensors, PART1_ROW)
+}
+
+#[aoc(day15, part2)]
+pub fn day15_part2(sensors: &[Sensor]) -> Result<i64, Error> {
+    tuning_frequency(sensors, SEARCH_AREA)
+}
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    const DAY: u8 = 15;
+
+    type Input = Vec<Sensor>;
+    type Part1 = i64;
+    type Part2 = i64;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        day15_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Part1, Error> {
+        day15_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Part2, Error> {
+        day15_part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "Sensor at x=2, y=18: closest beacon is at x=-2, y=15
+Sensor at x=9, y=16: closest beacon is at x=10, y=16
+Sensor at x=13, y=2: closest beacon is at x=15, y=3
+Sensor at x=12, y=14: closest beacon is at x=10, y=16
+Sensor at x=10, y=20: closest beacon is at x=10, y=16
+Sensor at x=14, y=17: closest beacon is at x=10, y=16
+Sensor at x=8, y=7: closest beacon is at x=2, y=10
+Sensor at x=2, y=0: closest beacon is at x=2, y=10
+Sensor at x=0, y=11: closest beacon is at x=2, y=10
+Sensor at x=20, y=14: closest beacon is at x=25, y=17
+Sensor at x=17, y=20: closest beacon is at x=21, y=22
+Sensor at x=16, y=7: closest beacon is at x=15, y=3
+Sensor at x=14, y=3: closest beacon is at x=15, y=3
+Sensor at x=20, y=1: closest beacon is at x=15, y=3";
+
+    /// row used for part 1 in the example
+    const EXAMPLE_ROW: i64 = 10;
+
+    /// search area used for part 2 in the example
+    const EXAMPLE_SEARCH_AREA: i64 = 20;
+
+    #[test]
+    fn part1_example() {
+        let sensors = day15_input(EXAMPLE).unwrap();
+        assert_eq!(
+            num_positions_without_beacon(&sensors, EXAMPLE_ROW).unwrap(),
+            26
+        );
+    }
+
+    #[test]
+    fn part2_example() {
+        let sensors = day15_input(EXAMPLE).unwrap();
+        assert_eq!(
+            tuning_frequency(&sensors, EXAMPLE_SEARCH_AREA).unwrap(),
+            56000011
+        );
+    }
+
+    #[test]
+    fn overflow() {
+        let input = "Sensor at x=9223372036854775807, y=0: closest beacon is at x=-9223372036854775808, y=0";
+        assert!(matches!(day15_input(input), Err(Error::Overflow(_))));
+
+        // the area of the sensor fits, but its row has more positions than an
+        // `i64` can count.
+        let sensors =
+            day15_input("Sensor at x=0, y=0: closest beacon is at x=4611686018427387904, y=0")
+                .unwrap();
+        let sensors = Sensors::new(&sensors);
+        assert!(matches!(
+            sensors.num_covered_positions_for_row(0),
+            Err(Error::Overflow(_))
+        ));
+        assert_eq!(sensors.num_covered_positions_for_row(i64::MIN).unwrap(), 0);
+        assert_eq!(
+            sensors.num_covered_positions_for_row(i64::MAX - 1).unwrap(),
+            0
+        );
+    }
 }
@@ -1,4 +1,12 @@
-use thiserror::Error;
+use crate::{
+    error::Error,
+    input::{
+        self,
+        Line,
+    },
+    solution::Solution,
+    stream::Incremental,
+};
 
 #[derive(Clone, Copy, Debug)]
 enum Move {
@@ -8,7 +16,7 @@ enum Move {
 }
 
 impl Move {
-    pub fn to_score(&self) -> u64 {
+    pub fn to_score(self) -> u64 {
         match self {
             Move::Rock => 1,
             Move::Paper => 2,
@@ -25,7 +33,7 @@ enum Outcome {
 }
 
 impl Outcome {
-    pub fn to_score(&self) -> u64 {
+    pub fn to_score(self) -> u64 {
         match self {
             Outcome::Win => 6,
             Outcome::Draw => 3,
@@ -42,7 +50,7 @@ enum Column2 {
 }
 
 impl Column2 {
-    pub fn to_move(&self) -> Move {
+    pub fn to_move(self) -> Move {
         match self {
             Column2::X => Move::Rock,
             Column2::Y => Move::Paper,
@@ -50,7 +58,7 @@ impl Column2 {
         }
     }
 
-    pub fn to_outcome(&self) -> Outcome {
+    pub fn to_outcome(self) -> Outcome {
         match self {
             Column2::X => Outcome::Lose,
             Column2::Y => Outcome::Draw,
@@ -64,50 +72,36 @@ pub struct Round {
     mine: Column2,
 }
 
-#[derive(Debug, Error)]
-#[error("unable to parse round: {0}")]
-pub struct RoundParseError(String);
-
-#[aoc_generator(day2)]
-fn day2_input(input: &str) -> Result<Vec<Round>, RoundParseError> {
-    let mut rounds = vec![];
-
-    for line in input.lines() {
-        let parts = line.split_whitespace().collect::<Vec<&str>>();
-        let opponent_str = *parts
-            .get(0)
-            .ok_or_else(|| RoundParseError(line.to_owned()))?;
-        let mine_str = *parts
-            .get(1)
-            .ok_or_else(|| RoundParseError(line.to_owned()))?;
+impl Round {
+    pub fn parse(line: Line<'_>) -> Result<Self, Error> {
+        let mut parts = line.text.split_whitespace();
+        let opponent_str = parts
+            .next()
+            .ok_or_else(|| line.error(line.text, "expected opponent's move"))?;
+        let mine_str = parts
+            .next()
+            .ok_or_else(|| line.error(line.text, "expected second column"))?;
 
         let opponent = match opponent_str {
             "A" => Move::Rock,
             "B" => Move::Paper,
             "C" => Move::Scissors,
-            _ => return Err(RoundParseError(line.to_owned())),
+            _ => return Err(line.error(opponent_str, "expected A, B or C")),
         };
         let mine = match mine_str {
             "X" => Column2::X,
             "Y" => Column2::Y,
             "Z" => Column2::Z,
-            _ => return Err(RoundParseError(line.to_owned())),
+            _ => return Err(line.error(mine_str, "expected X, Y or Z")),
         };
 
-        rounds.push(Round { opponent, mine })
+        Ok(Round { opponent, mine })
     }
 
-    Ok(rounds)
-}
-
-#[aoc(day2, part1)]
-fn day2_part1(rounds: &[Round]) -> u64 {
-    let mut score = 0;
-
-    for round in rounds {
-        let mine = round.mine.to_move();
-        score += mine.to_score();
-        let outcome = match (round.opponent, mine) {
+    /// the score if the second column is my move.
+    pub fn part1_score(&self) -> u64 {
+        let mine = self.mine.to_move();
+        let outcome = match (self.opponent, mine) {
             (Move::Rock, Move::Scissors) => Outcome::Lose,
             (Move::Rock, Move::Paper) => Outcome::Win,
             (Move::Paper, Move::Rock) => Outcome::Lose,
@@ -116,19 +110,13 @@ fn day2_part1(rounds: &[Round]) -> u64 {
             (Move::Scissors, Move::Paper) => Outcome::Lose,
             _ => Outcome::Draw,
         };
-        score += outcome.to_score();
+        mine.to_score() + outcome.to_score()
     }
 
-    score
-}
-
-#[aoc(day2, part2)]
-fn day2_part2(rounds: &[Round]) -> u64 {
-    let mut score = 0;
-
-    for round in rounds {
-        let outcome = round.mine.to_outcome();
-        let mine = match (outcome, round.opponent) {
+    /// the score if the second column is the outcome of the round.
+    pub fn part2_score(&self) -> u64 {
+        let outcome = self.mine.to_outcome();
+        let mine = match (outcome, self.opponent) {
             (Outcome::Win, Move::Rock) => Move::Paper,
             (Outcome::Win, Move::Paper) => Move::Scissors,
             (Outcome::Win, Move::Scissors) => Move::Rock,
@@ -137,9 +125,101 @@ fn day2_part2(rounds: &[Round]) -> u64 {
             (Outcome::Lose, Move::Scissors) => Move::Paper,
             (Outcome::Draw, opponent) => opponent,
         };
-        score += mine.to_score();
-        score += outcome.to_score();
+        mine.to_score() + outcome.to_score()
     }
+}
 
-    score
+#[aoc_generator(day2)]
+pub fn day2_input(input: &str) -> Result<Vec<Round>, Error> {
+    input::lines(input).map(Round::parse).collect()
+}
+
+#[aoc(day2, part1)]
+pub fn day2_part1(rounds: &[Round]) -> Result<u64, Error> {
+    Ok(rounds.iter().map(Round::part1_score).sum())
+}
+
+#[aoc(day2, part2)]
+pub fn day2_part2(rounds: &[Round]) -> Result<u64, Error> {
+    Ok(rounds.iter().map(Round::part2_score).sum())
+}
+
+/// adds up the scores of both parts while the rounds arrive.
+#[derive(Clone, Debug, Default)]
+pub struct ScoreCounter {
+    part1: u64,
+    part2: u64,
+}
+
+impl Incremental for ScoreCounter {
+    const DAY: u8 = 2;
+
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn push_line(&mut self, line: Line<'_>) -> Result<(), Error> {
+        let round = Round::parse(line)?;
+        self.part1 += round.part1_score();
+        self.part2 += round.part2_score();
+        Ok(())
+    }
+
+    fn part1(&self) -> Result<u64, Error> {
+        Ok(self.part1)
+    }
+
+    fn part2(&self) -> Result<u64, Error> {
+        Ok(self.part2)
+    }
+}
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    const DAY: u8 = 2;
+
+    type Input = Vec<Round>;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        day2_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Part1, Error> {
+        day2_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Part2, Error> {
+        day2_part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "A Y
+B X
+C Z";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(day2_part1(&day2_input(EXAMPLE).unwrap()).unwrap(), 15);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(day2_part2(&day2_input(EXAMPLE).unwrap()).unwrap(), 12);
+    }
+
+    #[test]
+    fn stream_example() {
+        let mut counter = ScoreCounter::default();
+        for line in input::lines(EXAMPLE) {
+            counter.push_line(line).unwrap();
+        }
+        assert_eq!(counter.part1().unwrap(), 15);
+        assert_eq!(counter.part2().unwrap(), 12);
+    }
 }
@@ -1,5 +1,15 @@
 use std::collections::HashSet;
 
+use crate::{
+    error::Error,
+    input::{
+        self,
+        Line,
+    },
+    solution::Solution,
+    stream::Incremental,
+};
+
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
 pub struct Item(char);
 
@@ -14,18 +24,14 @@ impl Item {
     }
 }
 
-struct Rucksack {
+pub struct Rucksack {
     first: HashSet<Item>,
     second: HashSet<Item>,
 }
 
 impl Rucksack {
-    pub fn in_both(&self) -> Item {
-        let mut intersection = self.first.intersection(&self.second);
-        let item = intersection
-            .next()
-            .unwrap_or_else(|| panic!("no item in both compartments"));
-        *item
+    pub fn in_both(&self) -> Option<Item> {
+        self.first.intersection(&self.second).next().copied()
     }
 
     pub fn all_items(&self) -> HashSet<Item> {
@@ -36,7 +42,7 @@ impl Rucksack {
     }
 }
 
-fn find_badge(rucksacks: [&Rucksack; 3]) -> Item {
+fn find_badge(rucksacks: [&Rucksack; 3]) -> Result<Item, Error> {
     let items = rucksacks.map(|rucksack| rucksack.all_items());
     let intersection = items[0]
         .intersection(&items[1])
@@ -44,50 +50,206 @@ fn find_badge(rucksacks: [&Rucksack; 3]) -> Item {
         .collect::<HashSet<_>>();
     let intersection = intersection.intersection(&items[2]).collect::<Vec<_>>();
 
-    if intersection.len() != 1 {
-        panic!("bag intersection: {}", intersection.len());
+    match intersection[..] {
+        [badge] => Ok(*badge),
+        _ => {
+            Err(Error::InvalidInput(format!(
+                "expected exactly one item in all rucksacks of a group, but found {}",
+                intersection.len()
+            )))
+        }
     }
-
-    **intersection.first().unwrap()
 }
 
-#[aoc_generator(day3)]
-fn day3_input(input: &str) -> Vec<Rucksack> {
-    let mut rucksacks = vec![];
+impl Rucksack {
+    pub fn parse(line: Line<'_>) -> Result<Self, Error> {
+        if let Some((i, _)) = line
+            .text
+            .char_indices()
+            .find(|(_, c)| !c.is_ascii_alphabetic())
+        {
+            return Err(line.error(&line.text[i..], "expected an item"));
+        }
+        if !line.text.len().is_multiple_of(2) {
+            return Err(line.error(line.text, "expected an even number of items"));
+        }
 
-    for line in input.lines() {
-        let n = line.len() / 2;
-        let first = line[..n].chars().map(Item).collect();
-        let second = line[n..].chars().map(Item).collect();
-        rucksacks.push(Rucksack { first, second })
+        let n = line.text.len() / 2;
+        let first = line.text[..n].chars().map(Item).collect();
+        let second = line.text[n..].chars().map(Item).collect();
+        Ok(Rucksack { first, second })
     }
+}
 
-    rucksacks
+#[aoc_generator(day3)]
+pub fn day3_input(input: &str) -> Result<Vec<Rucksack>, Error> {
+    input::lines(input).map(Rucksack::parse).collect()
 }
 
 #[aoc(day3, part1)]
-fn day3_part1(rucksacks: &[Rucksack]) -> u64 {
-    rucksacks
-        .into_iter()
-        .map(|rucksack| rucksack.in_both().priority())
-        .sum()
+pub fn day3_part1(rucksacks: &[Rucksack]) -> Result<u64, Error> {
+    let mut priorities = 0;
+
+    for (i, rucksack) in rucksacks.iter().enumerate() {
+        let item = rucksack.in_both().ok_or_else(|| {
+            Error::InvalidInput(format!("rucksack {}: no item in both compartments", i + 1))
+        })?;
+        priorities += item.priority();
+    }
+
+    Ok(priorities)
 }
 
 #[aoc(day3, part2)]
-fn day3_part2(rucksacks: &[Rucksack]) -> u64 {
-    let mut rucksack_iter = rucksacks.into_iter();
+pub fn day3_part2(rucksacks: &[Rucksack]) -> Result<u64, Error> {
+    if !rucksacks.len().is_multiple_of(3) {
+        return Err(Error::InvalidInput(format!(
+            "number of rucksacks must be a multiple of 3, but is {}",
+            rucksacks.len()
+        )));
+    }
+
     let mut priorities = 0;
 
-    loop {
-        let Some(first) = rucksack_iter.next() else {break};
-        let group = [
-            first,
-            rucksack_iter.next().unwrap(),
-            rucksack_iter.next().unwrap(),
-        ];
-        let badge = find_badge(group);
+    for group in rucksacks.chunks_exact(3) {
+        let badge = find_badge([&group[0], &group[1], &group[2]])?;
         priorities += badge.priority();
     }
 
-    priorities
+    Ok(priorities)
+}
+
+/// adds up the priorities of both parts while the rucksacks arrive, keeping
+/// only the current group.
+///
+/// like the solvers, a part fails with the first rucksack or group that has
+/// no solution, but the other part continues.
+pub struct RucksackChecker {
+    rucksacks: usize,
+    part1: Result<u64, Error>,
+    part2: Result<u64, Error>,
+    group: Vec<Rucksack>,
+}
+
+impl Default for RucksackChecker {
+    fn default() -> Self {
+        Self {
+            rucksacks: 0,
+            part1: Ok(0),
+            part2: Ok(0),
+            group: Vec::with_capacity(3),
+        }
+    }
+}
+
+impl Incremental for RucksackChecker {
+    const DAY: u8 = 3;
+
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn push_line(&mut self, line: Line<'_>) -> Result<(), Error> {
+        let rucksack = Rucksack::parse(line)?;
+        self.rucksacks += 1;
+
+        if let Ok(priorities) = &mut self.part1 {
+            match rucksack.in_both() {
+                Some(item) => *priorities += item.priority(),
+                None => {
+                    self.part1 = Err(Error::InvalidInput(format!(
+                        "rucksack {}: no item in both compartments",
+                        self.rucksacks
+                    )));
+                }
+            }
+        }
+
+        self.group.push(rucksack);
+        if let [first, second, third] = &self.group[..] {
+            let badge = find_badge([first, second, third]);
+            if let Ok(priorities) = &mut self.part2 {
+                match badge {
+                    Ok(badge) => *priorities += badge.priority(),
+                    Err(error) => self.part2 = Err(error),
+                }
+            }
+            self.group.clear();
+        }
+
+        Ok(())
+    }
+
+    fn part1(&self) -> Result<u64, Error> {
+        self.part1.clone()
+    }
+
+    fn part2(&self) -> Result<u64, Error> {
+        if !self.group.is_empty() {
+            return Err(Error::InvalidInput(format!(
+                "number of rucksacks must be a multiple of 3, but is {}",
+                self.rucksacks
+            )));
+        }
+        self.part2.clone()
+    }
+}
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    const DAY: u8 = 3;
+
+    type Input = Vec<Rucksack>;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        day3_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Part1, Error> {
+        day3_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Part2, Error> {
+        day3_part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "vJrwpWtwJgWrhcsFMMfFFhFp
+jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+PmmdzqPrVvPwwTWBwg
+wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+ttgJtRGJQctTZtZT
+CrZsJsPPZsGzwwsLwLmpwMDw";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(day3_part1(&day3_input(EXAMPLE).unwrap()).unwrap(), 157);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(day3_part2(&day3_input(EXAMPLE).unwrap()).unwrap(), 70);
+    }
+
+    #[test]
+    fn stream_example() {
+        let mut checker = RucksackChecker::default();
+        for line in input::lines(EXAMPLE).take(4) {
+            checker.push_line(line).unwrap();
+        }
+        assert_eq!(checker.part1().unwrap(), 16 + 38 + 42 + 22);
+        assert!(matches!(checker.part2(), Err(Error::InvalidInput(_))));
+
+        for line in input::lines(EXAMPLE).skip(4) {
+            checker.push_line(line).unwrap();
+        }
+        assert_eq!(checker.part1().unwrap(), 157);
+        assert_eq!(checker.part2().unwrap(), 70);
+    }
 }
@@ -1,17 +1,30 @@
+use std::marker::PhantomData;
+
 use lazy_static::lazy_static;
 use regex::Regex;
 
+use crate::{
+    error::Error,
+    input::{
+        self,
+        Line,
+    },
+    num::Number,
+    solution::Solution,
+    stream::Incremental,
+};
+
 lazy_static! {
-    static ref REGEX_ASSIGNMENT: Regex = r"(\d+)-(\d+),(\d+)-(\d+)".parse().unwrap();
+    static ref REGEX_ASSIGNMENT: Regex = r"^(\d+)-(\d+),(\d+)-(\d+)$".parse().unwrap();
 }
 
 #[derive(Copy, Clone, Debug)]
-pub struct Assignment {
-    first: Range,
-    second: Range,
+pub struct Assignment<T = u64> {
+    first: Range<T>,
+    second: Range<T>,
 }
 
-impl Assignment {
+impl<T: Number> Assignment<T> {
     pub fn fully_contains_other(&self) -> bool {
         self.first.fully_contains(&self.second) || self.second.fully_contains(&self.first)
     }
@@ -21,18 +34,19 @@ impl Assignment {
     }
 }
 
+/// the sections from `first` to `last`, inclusive.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
-pub struct Range {
-    first: u64,
-    last: u64,
+pub struct Range<T = u64> {
+    first: T,
+    last: T,
 }
 
-impl Range {
-    pub fn fully_contains(&self, other: &Range) -> bool {
+impl<T: Number> Range<T> {
+    pub fn fully_contains(&self, other: &Self) -> bool {
         other.first >= self.first && other.last <= self.last
     }
 
-    pub fn overlaps(&self, other: &Range) -> bool {
+    pub fn overlaps(&self, other: &Self) -> bool {
         (self.first >= other.first && self.first <= other.last)
             || (self.last >= other.first && self.last <= other.last)
             || self.fully_contains(other)
@@ -40,38 +54,178 @@ impl Range {
     }
 }
 
+impl<T: Number> Assignment<T> {
+    pub fn parse(line: Line<'_>) -> Result<Self, Error> {
+        let captures = REGEX_ASSIGNMENT
+            .captures(line.text)
+            .ok_or_else(|| line.error(line.text, "expected an assignment like 2-4,6-8"))?;
+        let section = |i| line.parse(captures.get(i).unwrap().as_str(), "a section ID");
+
+        let first = Range {
+            first: section(1)?,
+            last: section(2)?,
+        };
+        let second = Range {
+            first: section(3)?,
+            last: section(4)?,
+        };
+
+        Ok(Assignment { first, second })
+    }
+}
+
+/// parses the assignments with section IDs of type `T`.
+pub fn parse_assignments<T: Number>(input: &str) -> Result<Vec<Assignment<T>>, Error> {
+    input::lines(input).map(Assignment::parse).collect()
+}
+
 #[aoc_generator(day4)]
-fn day4_input(input: &str) -> Vec<Assignment> {
-    input
-        .lines()
-        .map(|line| {
-            let captures = REGEX_ASSIGNMENT.captures(line).unwrap();
-            let first = Range {
-                first: captures.get(1).unwrap().as_str().parse().unwrap(),
-                last: captures.get(2).unwrap().as_str().parse().unwrap(),
-            };
-            let second = Range {
-                first: captures.get(3).unwrap().as_str().parse().unwrap(),
-                last: captures.get(4).unwrap().as_str().parse().unwrap(),
-            };
-
-            Assignment { first, second }
-        })
-        .collect()
+pub fn day4_input(input: &str) -> Result<Vec<Assignment>, Error> {
+    parse_assignments(input)
 }
 
 #[aoc(day4, part1)]
-fn day4_part1(assignments: &[Assignment]) -> usize {
+pub fn day4_part1(assignments: &[Assignment]) -> Result<usize, Error> {
+    Ok(count_fully_contained(assignments))
+}
+
+#[aoc(day4, part2)]
+pub fn day4_part2(assignments: &[Assignment]) -> Result<usize, Error> {
+    Ok(count_overlapping(assignments))
+}
+
+/// counts the pairs where one range contains the other.
+pub fn count_fully_contained<T: Number>(assignments: &[Assignment<T>]) -> usize {
     assignments
-        .into_iter()
+        .iter()
         .filter(|assignment| assignment.fully_contains_other())
         .count()
 }
 
-#[aoc(day4, part2)]
-fn day4_part2(assignments: &[Assignment]) -> usize {
+/// counts the pairs whose ranges overlap.
+pub fn count_overlapping<T: Number>(assignments: &[Assignment<T>]) -> usize {
     assignments
-        .into_iter()
+        .iter()
         .filter(|assignment| assignment.overlap_at_all())
         .count()
 }
+
+/// counts the pairs of both parts while the assignments arrive.
+#[derive(Clone, Debug)]
+pub struct AssignmentCounter<T = u64> {
+    fully_contained: usize,
+    overlapping: usize,
+    _sections: PhantomData<T>,
+}
+
+impl<T> Default for AssignmentCounter<T> {
+    fn default() -> Self {
+        Self {
+            fully_contained: 0,
+            overlapping: 0,
+            _sections: PhantomData,
+        }
+    }
+}
+
+impl<T: Number> Incremental for AssignmentCounter<T> {
+    const DAY: u8 = 4;
+
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn push_line(&mut self, line: Line<'_>) -> Result<(), Error> {
+        let assignment = Assignment::<T>::parse(line)?;
+        self.fully_contained += usize::from(assignment.fully_contains_other());
+        self.overlapping += usize::from(assignment.overlap_at_all());
+        Ok(())
+    }
+
+    fn part1(&self) -> Result<usize, Error> {
+        Ok(self.fully_contained)
+    }
+
+    fn part2(&self) -> Result<usize, Error> {
+        Ok(self.overlapping)
+    }
+}
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    const DAY: u8 = 4;
+
+    type Input = Vec<Assignment>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        day4_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Part1, Error> {
+        day4_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Part2, Error> {
+        day4_part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "2-4,6-8
+2-3,4-5
+5-7,7-9
+2-8,3-7
+6-6,4-6
+2-6,4-8";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(day4_part1(&day4_input(EXAMPLE).unwrap()).unwrap(), 2);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(day4_part2(&day4_input(EXAMPLE).unwrap()).unwrap(), 4);
+    }
+
+    #[test]
+    fn invalid_assignment() {
+        let error = day4_input("2-4,6-8\n2-3;4-5").unwrap_err();
+        assert!(matches!(
+            error,
+            Error::Parse {
+                line: 2,
+                column: 1,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn large_section_ids() {
+        let input =
+            "18446744073709551616-18446744073709551618,18446744073709551617-18446744073709551617";
+        assert!(matches!(
+            day4_input(input),
+            Err(Error::Parse { column: 1, .. })
+        ));
+
+        let assignments = parse_assignments::<u128>(input).unwrap();
+        assert_eq!(count_fully_contained(&assignments), 1);
+        assert_eq!(count_overlapping(&assignments), 1);
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn bigint() {
+        let input = format!("1-{0}0,{0}-{0}1", u128::MAX);
+        let assignments = parse_assignments::<num_bigint::BigUint>(&input).unwrap();
+        assert_eq!(count_fully_contained(&assignments), 0);
+        assert_eq!(count_overlapping(&assignments), 1);
+    }
+}
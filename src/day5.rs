@@ -2,14 +2,24 @@ use std::fmt;
 
 use lazy_static::lazy_static;
 use regex::Regex;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    error::Error,
+    input,
+    solution::Solution,
+};
 
 lazy_static! {
-    static ref MOVE_REGEX: Regex = r"move (\d+) from (\d+) to (\d+)".parse().unwrap();
-    static ref CRATES_REGEX: Regex = r"(    |\[[A-Z]\] )(    |\[[A-Z]\] )(    |\[[A-Z]\] )(    |\[[A-Z]\] )(    |\[[A-Z]\] )(    |\[[A-Z]\] )(    |\[[A-Z]\] )(    |\[[A-Z]\] )(   |\[[A-Z]\])".parse().unwrap();
+    static ref MOVE_REGEX: Regex = r"^move (\d+) from (\d+) to (\d+)$".parse().unwrap();
+    static ref CRATE_REGEX: Regex = r"\[([A-Z])\]".parse().unwrap();
 }
 
-#[derive(Clone, Copy)]
-struct CrateId(char);
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct CrateId(char);
 
 impl fmt::Debug for CrateId {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -17,11 +27,11 @@ impl fmt::Debug for CrateId {
     }
 }
 
-#[derive(Clone, Debug, Default)]
-struct Stacks([Vec<CrateId>; 9]);
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Stacks(Vec<Vec<CrateId>>);
 
 impl Stacks {
-    fn top_crates(&self) -> String {
+    pub fn top_crates(&self) -> String {
         let mut top_crates = String::new();
         for stack in &self.0 {
             if let Some(crate_id) = stack.last() {
@@ -32,98 +42,248 @@ impl Stacks {
     }
 }
 
-#[derive(Debug)]
-struct Move {
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Move {
     count: usize,
     from: usize,
     to: usize,
 }
 
 impl Move {
-    fn execute_9000(&self, stacks: &mut Stacks) {
-        for _ in 0..self.count {
-            let crate_id = stacks.0[self.from - 1].pop().unwrap();
-            stacks.0[self.to - 1].push(crate_id);
+    /// removes the crates that are moved from the source stack. the top crate
+    /// is last.
+    fn take_crates(&self, stacks: &mut Stacks) -> Result<Vec<CrateId>, Error> {
+        let stack = &mut stacks.0[self.from - 1];
+
+        if stack.len() < self.count {
+            return Err(Error::InvalidInput(format!(
+                "can't move {} crates from stack {} with only {} crates",
+                self.count,
+                self.from,
+                stack.len()
+            )));
         }
+
+        Ok(stack.split_off(stack.len() - self.count))
     }
 
-    fn execute_9001(&self, stacks: &mut Stacks) {
-        let mut buf = vec![];
+    pub fn execute_9000(&self, stacks: &mut Stacks) -> Result<(), Error> {
+        let mut crates = self.take_crates(stacks)?;
 
-        for _ in 0..self.count {
-            let crate_id = stacks.0[self.from - 1].pop().unwrap();
-            buf.push(crate_id);
+        // crates are moved one at a time, so they end up in reverse order,
+        // unless they are put back onto the stack they came from.
+        if self.from != self.to {
+            crates.reverse();
         }
+        stacks.0[self.to - 1].append(&mut crates);
+
+        Ok(())
+    }
 
-        buf.reverse();
-        stacks.0[self.to - 1].append(&mut buf);
+    pub fn execute_9001(&self, stacks: &mut Stacks) -> Result<(), Error> {
+        let mut crates = self.take_crates(stacks)?;
+        stacks.0[self.to - 1].append(&mut crates);
+
+        Ok(())
     }
 }
 
 #[derive(Debug)]
-struct PuzzleInput {
+pub struct PuzzleInput {
     stacks: Stacks,
     moves: Vec<Move>,
 }
 
+impl PuzzleInput {
+    /// the stacks before any crates are moved.
+    pub fn stacks(&self) -> &Stacks {
+        &self.stacks
+    }
+
+    pub fn moves(&self) -> &[Move] {
+        &self.moves
+    }
+}
+
 #[aoc_generator(day5)]
-fn day5_input(input: &str) -> PuzzleInput {
-    let mut lines = input.lines();
+pub fn day5_input(input: &str) -> Result<PuzzleInput, Error> {
+    let mut lines = input::lines(input);
     let mut stacks: Stacks = Default::default();
     let mut moves = vec![];
 
     loop {
-        let line = lines.next().unwrap();
-        if line.is_empty() {
+        let line = lines.next_line("crates or an empty line")?;
+        if line.text.is_empty() {
             break;
         }
 
-        if let Some(captures) = CRATES_REGEX.captures(line) {
-            for i in 0..9 {
-                let capture = captures.get(i + 1).unwrap().as_str();
-                let crate_id = capture[1..].chars().next().unwrap();
-                if crate_id != ' ' {
-                    stacks.0[i].push(CrateId(crate_id));
-                }
+        // every stack takes up 4 columns, so the stack index follows from where
+        // the crate starts. the line with the stack numbers doesn't match at all.
+        for captures in CRATE_REGEX.captures_iter(line.text) {
+            let capture = captures.get(0).unwrap();
+            let i = capture.start() / 4;
+            if i >= stacks.0.len() {
+                stacks.0.resize_with(i + 1, Default::default);
             }
+            let crate_id = captures.get(1).unwrap().as_str().chars().next().unwrap();
+            stacks.0[i].push(CrateId(crate_id));
         }
     }
 
-    for i in 0..9 {
-        stacks.0[i].reverse();
+    for stack in &mut stacks.0 {
+        stack.reverse();
     }
 
-    while let Some(line) = lines.next() {
-        let captures = MOVE_REGEX.captures(line).unwrap();
+    for line in lines {
+        let captures = MOVE_REGEX
+            .captures(line.text)
+            .ok_or_else(|| line.error(line.text, "expected a move"))?;
+
+        let parse_stack = |i| -> Result<usize, Error> {
+            let capture = captures.get(i).unwrap().as_str();
+            let stack = line.parse(capture, "a stack number")?;
+            if stack == 0 || stack > stacks.0.len() {
+                return Err(line.error(
+                    capture,
+                    format!("expected a stack between 1 and {}", stacks.0.len()),
+                ));
+            }
+            Ok(stack)
+        };
 
         moves.push(Move {
-            count: captures.get(1).unwrap().as_str().parse().unwrap(),
-            from: captures.get(2).unwrap().as_str().parse().unwrap(),
-            to: captures.get(3).unwrap().as_str().parse().unwrap(),
+            count: line.parse(captures.get(1).unwrap().as_str(), "a number of crates")?,
+            from: parse_stack(2)?,
+            to: parse_stack(3)?,
         })
     }
 
-    PuzzleInput { stacks, moves }
+    Ok(PuzzleInput { stacks, moves })
 }
 
 #[aoc(day5, part1)]
-fn day5_part1(input: &PuzzleInput) -> String {
+pub fn day5_part1(input: &PuzzleInput) -> Result<String, Error> {
     let mut stacks = input.stacks.clone();
 
     for mov in &input.moves {
-        mov.execute_9000(&mut stacks);
+        mov.execute_9000(&mut stacks)?;
     }
 
-    stacks.top_crates()
+    Ok(stacks.top_crates())
 }
 
 #[aoc(day5, part2)]
-fn day5_part2(input: &PuzzleInput) -> String {
+pub fn day5_part2(input: &PuzzleInput) -> Result<String, Error> {
     let mut stacks = input.stacks.clone();
 
     for mov in &input.moves {
-        mov.execute_9001(&mut stacks);
+        mov.execute_9001(&mut stacks)?;
+    }
+
+    Ok(stacks.top_crates())
+}
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    const DAY: u8 = 5;
+
+    type Input = PuzzleInput;
+    type Part1 = String;
+    type Part2 = String;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        day5_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Part1, Error> {
+        day5_part1(input)
     }
 
-    stacks.top_crates()
+    fn part2(input: &Self::Input) -> Result<Self::Part2, Error> {
+        day5_part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "    [D]
+[N] [C]
+[Z] [M] [P]
+ 1   2   3
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(day5_part1(&day5_input(EXAMPLE).unwrap()).unwrap(), "CMZ");
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(day5_part2(&day5_input(EXAMPLE).unwrap()).unwrap(), "MCD");
+    }
+
+    #[test]
+    fn nine_stacks() {
+        // the real inputs have 9 stacks, some of them empty at the top.
+        let input = "                [H]
+[A]     [C]     [E]             [I]
+ 1   2   3   4   5   6   7   8   9
+
+move 1 from 1 to 2
+move 1 from 9 to 4";
+        assert_eq!(day5_part1(&day5_input(input).unwrap()).unwrap(), "ACIH");
+    }
+
+    #[test]
+    fn move_from_unknown_stack() {
+        let input = EXAMPLE.replace("move 1 from 2 to 1", "move 1 from 4 to 1");
+        let error = day5_input(&input).unwrap_err();
+        assert!(matches!(
+            error,
+            Error::Parse {
+                line: 6,
+                column: 13,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn move_onto_same_stack() {
+        let input = "[G] [A]\n 1   2\n\nmove 1 from 1 to 2\nmove 2 from 2 to 2";
+        assert_eq!(day5_part1(&day5_input(input).unwrap()).unwrap(), "G");
+        assert_eq!(day5_part2(&day5_input(input).unwrap()).unwrap(), "G");
+    }
+
+    #[test]
+    fn execute_onto_same_stack() {
+        let crates = |ids: &str| ids.chars().map(CrateId).collect::<Vec<_>>();
+        let mov = Move {
+            count: 2,
+            from: 1,
+            to: 1,
+        };
+
+        // taking crates off a stack and putting them back changes nothing,
+        // with either crane.
+        let mut stacks = Stacks(vec![crates("ABC")]);
+        mov.execute_9000(&mut stacks).unwrap();
+        assert_eq!(format!("{:?}", stacks.0[0]), "[[A], [B], [C]]");
+        mov.execute_9001(&mut stacks).unwrap();
+        assert_eq!(format!("{:?}", stacks.0[0]), "[[A], [B], [C]]");
+    }
+
+    #[test]
+    fn move_from_empty_stack() {
+        let input = EXAMPLE.replace("move 1 from 2 to 1", "move 4 from 2 to 1");
+        let error = day5_part1(&day5_input(&input).unwrap()).unwrap_err();
+        assert!(matches!(error, Error::InvalidInput(_)));
+    }
 }
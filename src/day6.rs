@@ -1,23 +1,22 @@
 use std::collections::{
     hash_map::Entry,
     HashMap,
+    VecDeque,
 };
 
-#[derive(Debug)]
+use crate::{
+    error::Error,
+    input::Line,
+    solution::Solution,
+    stream::Incremental,
+};
+
+#[derive(Debug, Default)]
 struct SignalBuffer {
     buf: HashMap<char, usize>,
     unique_count: usize,
 }
 
-impl Default for SignalBuffer {
-    fn default() -> Self {
-        Self {
-            buf: HashMap::new(),
-            unique_count: 0,
-        }
-    }
-}
-
 impl SignalBuffer {
     pub fn remove(&mut self, c: char) {
         let entry = self.buf.get_mut(&c).unwrap();
@@ -48,33 +47,205 @@ impl SignalBuffer {
     }
 }
 
-fn find_start_marker(length: usize, signal: &[char]) -> usize {
-    let mut buf = SignalBuffer::default();
+/// looks for the first `length` characters that are all different, one
+/// character at a time.
+#[derive(Debug)]
+struct MarkerFinder {
+    length: usize,
+    window: VecDeque<char>,
+    buf: SignalBuffer,
+    /// the number of characters seen so far.
+    position: usize,
+    marker: Option<usize>,
+}
 
-    for add_index in 0..signal.len() {
-        if let Some(remove_index) = add_index.checked_sub(length) {
-            buf.remove(signal[remove_index]);
+impl MarkerFinder {
+    pub fn new(length: usize) -> Self {
+        Self {
+            length,
+            window: VecDeque::with_capacity(length + 1),
+            buf: SignalBuffer::default(),
+            position: 0,
+            marker: None,
         }
-        buf.insert(signal[add_index]);
-        if buf.unique_count() == length {
-            return add_index + 1;
+    }
+
+    /// takes the next character, and returns the end of the marker once it's
+    /// found.
+    pub fn push(&mut self, c: char) -> Option<usize> {
+        if self.marker.is_none() {
+            self.position += 1;
+            self.window.push_back(c);
+            self.buf.insert(c);
+            if self.window.len() > self.length {
+                let removed = self.window.pop_front().unwrap();
+                self.buf.remove(removed);
+            }
+            if self.buf.unique_count() == self.length {
+                self.marker = Some(self.position);
+            }
         }
+        self.marker
+    }
+
+    pub fn marker(&self) -> Result<usize, Error> {
+        self.marker.ok_or_else(|| {
+            Error::NoSolution(format!("no start marker of length {} found", self.length))
+        })
     }
+}
 
-    panic!("no start marker found");
+fn find_start_marker(length: usize, signal: &[char]) -> Result<usize, Error> {
+    let mut finder = MarkerFinder::new(length);
+    signal.iter().find_map(|c| finder.push(*c));
+    finder.marker()
 }
 
 #[aoc_generator(day6)]
-fn day6_input(input: &str) -> Vec<char> {
-    input.chars().collect()
+pub fn day6_input(input: &str) -> Result<Vec<char>, Error> {
+    Ok(input.chars().collect())
 }
 
 #[aoc(day6, part1)]
-fn day6_part1(signal: &[char]) -> usize {
+pub fn day6_part1(signal: &[char]) -> Result<usize, Error> {
     find_start_marker(4, signal)
 }
 
 #[aoc(day6, part2)]
-fn day6_part2(signal: &[char]) -> usize {
+pub fn day6_part2(signal: &[char]) -> Result<usize, Error> {
     find_start_marker(14, signal)
 }
+
+/// looks for both markers while the signal arrives.
+#[derive(Debug)]
+pub struct SignalScanner {
+    packet: MarkerFinder,
+    message: MarkerFinder,
+    /// the number of the line that the last piece was on.
+    line: usize,
+}
+
+impl Default for SignalScanner {
+    fn default() -> Self {
+        Self {
+            packet: MarkerFinder::new(4),
+            message: MarkerFinder::new(14),
+            line: 0,
+        }
+    }
+}
+
+impl SignalScanner {
+    fn push(&mut self, c: char) {
+        self.packet.push(c);
+        self.message.push(c);
+    }
+}
+
+impl Incremental for SignalScanner {
+    const DAY: u8 = 6;
+    const SPLIT_LINES: bool = true;
+
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn push_line(&mut self, line: Line<'_>) -> Result<(), Error> {
+        // the solvers see the line breaks of a signal that spans several lines
+        // as characters too.
+        if self.line != 0 && line.number != self.line {
+            self.push('\n');
+        }
+        self.line = line.number;
+
+        for c in line.text.chars() {
+            self.push(c);
+        }
+        Ok(())
+    }
+
+    fn part1(&self) -> Result<usize, Error> {
+        self.packet.marker()
+    }
+
+    fn part2(&self) -> Result<usize, Error> {
+        self.message.marker()
+    }
+}
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    const DAY: u8 = 6;
+
+    type Input = Vec<char>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        day6_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Part1, Error> {
+        day6_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Part2, Error> {
+        day6_part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLES: [(&str, usize, usize); 5] = [
+        ("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 7, 19),
+        ("bvwbjplbgvbhsrlpgdmjqwftvncz", 5, 23),
+        ("nppdvjthqldpwncqszvftbrmjlhg", 6, 23),
+        ("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 10, 29),
+        ("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 11, 26),
+    ];
+
+    #[test]
+    fn part1_examples() {
+        for (input, expected, _) in EXAMPLES {
+            assert_eq!(
+                day6_part1(&day6_input(input).unwrap()).unwrap(),
+                expected,
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn part2_examples() {
+        for (input, _, expected) in EXAMPLES {
+            assert_eq!(
+                day6_part2(&day6_input(input).unwrap()).unwrap(),
+                expected,
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn no_start_marker() {
+        let error = day6_part1(&day6_input("abcabc").unwrap()).unwrap_err();
+        assert!(matches!(error, Error::NoSolution(_)));
+    }
+
+    #[test]
+    fn stream_in_pieces() {
+        for (input, part1, part2) in EXAMPLES {
+            let mut scanner = SignalScanner::default();
+            for piece in input.as_bytes().chunks(3) {
+                let text = std::str::from_utf8(piece).unwrap();
+                scanner.push_line(Line { number: 1, text }).unwrap();
+            }
+            assert_eq!(scanner.part1().unwrap(), part1, "{}", input);
+            assert_eq!(scanner.part2().unwrap(), part2, "{}", input);
+        }
+    }
+}
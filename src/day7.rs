@@ -7,19 +7,27 @@ use std::{
     },
 };
 
+use tracing::debug;
+
+use crate::{
+    error::Error,
+    input,
+    solution::Solution,
+};
+
 #[derive(Debug)]
-enum LsEntry {
+pub enum LsEntry {
     Dir { name: String },
     File { name: String, file_size: u64 },
 }
 
 #[derive(Debug)]
-enum Command {
+pub enum Command {
     Ls { files: Vec<LsEntry> },
     Cd { path: String },
 }
 
-enum FsNode {
+pub enum FsNode {
     File {
         name: String,
         file_size: u64,
@@ -34,16 +42,11 @@ enum FsNode {
 }
 
 impl FsNode {
-    pub fn parent(&self) -> Rc<FsNode> {
+    /// returns the parent directory, or `None` for the root directory.
+    pub fn parent(&self) -> Option<Rc<FsNode>> {
         match self {
-            FsNode::File { parent, .. } => parent.clone(),
-            FsNode::Directory { parent, .. } => {
-                parent
-                    .as_ref()
-                    .expect("no parent directory")
-                    .upgrade()
-                    .unwrap()
-            }
+            FsNode::File { parent, .. } => Some(parent.clone()),
+            FsNode::Directory { parent, .. } => parent.as_ref().and_then(Weak::upgrade),
         }
     }
 
@@ -88,7 +91,7 @@ impl FsNode {
                 files, total_size, ..
             } => {
                 let size = *total_size.borrow();
-                if size < 100000 {
+                if size <= 100000 {
                     total += size;
                 }
                 for file in files.borrow().values() {
@@ -100,7 +103,7 @@ impl FsNode {
         total
     }
 
-    pub fn find_smallest_above(self: Rc<Self>, above_size: u64) -> Option<Rc<FsNode>> {
+    pub fn find_smallest_at_least(self: Rc<Self>, min_size: u64) -> Option<Rc<FsNode>> {
         match self.as_ref() {
             FsNode::File { .. } => {}
             FsNode::Directory {
@@ -109,7 +112,7 @@ impl FsNode {
                 let mut min_dir: Option<Rc<FsNode>> = None;
 
                 for file in files.borrow().values() {
-                    if let Some(smallest_dir) = file.clone().find_smallest_above(above_size) {
+                    if let Some(smallest_dir) = file.clone().find_smallest_at_least(min_size) {
                         if let Some(min_dir) = &mut min_dir {
                             if smallest_dir.total_size() < min_dir.total_size() {
                                 *min_dir = smallest_dir;
@@ -125,7 +128,7 @@ impl FsNode {
                     return Some(min_dir);
                 }
 
-                if *total_size.borrow() > above_size {
+                if *total_size.borrow() >= min_size {
                     return Some(self.clone());
                 }
             }
@@ -135,7 +138,7 @@ impl FsNode {
     }
 }
 
-fn build_fs_from_commands(commands: &[Command]) -> Rc<FsNode> {
+fn build_fs_from_commands(commands: &[Command]) -> Result<Rc<FsNode>, Error> {
     let root = Rc::new(FsNode::Directory {
         name: "/".to_owned(),
         files: RefCell::new(HashMap::new()),
@@ -148,7 +151,9 @@ fn build_fs_from_commands(commands: &[Command]) -> Rc<FsNode> {
         match command {
             Command::Ls { files } => {
                 match current.as_ref() {
-                    FsNode::File { .. } => panic!("current is not a directory"),
+                    FsNode::File { name, .. } => {
+                        return Err(Error::InvalidInput(format!("ls in file {}", name)))
+                    }
                     FsNode::Directory {
                         files: dir_files, ..
                     } => {
@@ -182,12 +187,19 @@ fn build_fs_from_commands(commands: &[Command]) -> Rc<FsNode> {
                 }
             }
             Command::Cd { path } => {
-                if path == ".." {
-                    current = current.parent();
+                if path == "/" {
+                    current = root.clone();
+                }
+                else if path == ".." {
+                    current = current
+                        .parent()
+                        .ok_or_else(|| Error::InvalidInput("cd .. in root directory".to_owned()))?;
                 }
                 else {
                     let new_current = match current.as_ref() {
-                        FsNode::File { .. } => panic!("current is not a directory"),
+                        FsNode::File { name, .. } => {
+                            return Err(Error::InvalidInput(format!("cd in file {}", name)))
+                        }
                         FsNode::Directory { files, .. } => {
                             let mut files = files.borrow_mut();
                             if let Some(dir) = files.get(path) {
@@ -211,77 +223,212 @@ fn build_fs_from_commands(commands: &[Command]) -> Rc<FsNode> {
         }
     }
 
-    root
+    Ok(root)
 }
 
 #[aoc_generator(day7)]
-fn day7_input(input: &str) -> Rc<FsNode> {
-    let mut lines = input.lines().peekable();
+pub fn day7_input(input: &str) -> Result<Rc<FsNode>, Error> {
+    let mut lines = input::lines(input);
     let mut commands = vec![];
 
-    while let Some(command) = lines.next() {
-        let command = command.split_whitespace().collect::<Vec<_>>();
-        assert_eq!(command[0], "$");
+    while let Some(line) = lines.next() {
+        let command = line.strip_prefix("$ ")?;
+        let mut args = command.split_whitespace();
 
-        match command[1] {
-            "ls" => {
+        match args.next() {
+            Some("ls") => {
                 let mut files = vec![];
-                loop {
-                    let Some(line) = lines.peek() else {break};
-                    if line.starts_with("$") {
+                while let Some(line) = lines.peek() {
+                    if line.starts_with('$') {
                         break;
                     }
 
-                    let output = lines.next().unwrap().split_whitespace().collect::<Vec<_>>();
-                    let name = output[1].to_string();
+                    let line = lines.next().unwrap();
+                    let (size_or_dir, name) = line.split_once(" ")?;
+                    let name = name.to_string();
 
-                    if output[0] == "dir" {
+                    if size_or_dir == "dir" {
                         files.push(LsEntry::Dir { name });
                     }
                     else {
-                        let file_size = output[0].parse().unwrap();
+                        let file_size = line.parse(size_or_dir, "`dir` or a file size")?;
                         files.push(LsEntry::File { name, file_size })
                     }
-                    //output.push();
                 }
                 commands.push(Command::Ls { files });
             }
-            "cd" => {
+            Some("cd") => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| line.error(command, "expected a path"))?;
                 commands.push(Command::Cd {
-                    path: command[2].to_string(),
+                    path: path.to_string(),
                 })
             }
-            _ => panic!("invalid command: {}", command[1]),
+            Some(name) => return Err(line.error(name, "invalid command")),
+            None => return Err(line.error(command, "expected a command")),
         }
     }
 
-    let fs = build_fs_from_commands(&commands);
+    let fs = build_fs_from_commands(&commands)?;
     fs.compute_total_size();
 
-    fs
+    Ok(fs)
 }
 
 #[aoc(day7, part1)]
-fn day7_part1(fs: &Rc<FsNode>) -> u64 {
-    fs.find_directories_with_atmost_100000()
+pub fn day7_part1(fs: &Rc<FsNode>) -> Result<u64, Error> {
+    Ok(fs.find_directories_with_atmost_100000())
 }
 
-#[aoc(day7, part2)]
-fn day7_part2(fs: &Rc<FsNode>) -> u64 {
-    let disk_size = 70000000;
+/// size of the whole filesystem
+const DISK_SIZE: u64 = 70000000;
+
+/// free space needed to run the update
+const REQUIRED_FREE_SPACE: u64 = 30000000;
+
+fn find_directory_to_delete(
+    fs: &Rc<FsNode>,
+    disk_size: u64,
+    required_free_space: u64,
+) -> Result<u64, Error> {
     let total_size = fs.total_size();
-    let free_space = disk_size - total_size;
-    let need_to_free = 30000000 - free_space;
-
-    println!("total_size: {}", total_size);
-    println!("free_space: {}", free_space);
-    println!("need_to_free: {}", need_to_free);
-
-    let smallest_dir = fs.clone().find_smallest_above(need_to_free).unwrap();
-    println!(
-        "smallest dir: {} {}",
-        smallest_dir.name(),
-        smallest_dir.total_size()
+    let free_space = disk_size.checked_sub(total_size).ok_or_else(|| {
+        Error::InvalidInput(format!(
+            "total size {} is larger than the disk size {}",
+            total_size, disk_size
+        ))
+    })?;
+    let need_to_free = required_free_space
+        .checked_sub(free_space)
+        .filter(|need_to_free| *need_to_free > 0)
+        .ok_or_else(|| Error::NoSolution("there is already enough free space".to_owned()))?;
+
+    debug!(total_size, free_space, need_to_free, "free space");
+
+    let smallest_dir = fs
+        .clone()
+        .find_smallest_at_least(need_to_free)
+        .ok_or_else(|| Error::NoSolution("no directory is large enough".to_owned()))?;
+    debug!(
+        name = smallest_dir.name(),
+        size = smallest_dir.total_size(),
+        "smallest directory to delete"
     );
-    smallest_dir.total_size()
+    Ok(smallest_dir.total_size())
+}
+
+#[aoc(day7, part2)]
+pub fn day7_part2(fs: &Rc<FsNode>) -> Result<u64, Error> {
+    find_directory_to_delete(fs, DISK_SIZE, REQUIRED_FREE_SPACE)
+}
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    const DAY: u8 = 7;
+
+    type Input = Rc<FsNode>;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        day7_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Part1, Error> {
+        day7_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Part2, Error> {
+        day7_part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd a
+$ ls
+dir e
+29116 f
+2557 g
+62596 h.lst
+$ cd e
+$ ls
+584 i
+$ cd ..
+$ cd ..
+$ cd d
+$ ls
+4060174 j
+8033020 d.log
+5626152 d.ext
+7214296 k";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(day7_part1(&day7_input(EXAMPLE).unwrap()).unwrap(), 95437);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(day7_part2(&day7_input(EXAMPLE).unwrap()).unwrap(), 24933642);
+    }
+
+    #[test]
+    fn directory_of_exactly_100000() {
+        // the puzzle asks for directories of at most 100000.
+        let fs = day7_input("$ cd /\n$ ls\ndir a\n1 b\n$ cd a\n$ ls\n100000 c").unwrap();
+        assert_eq!(day7_part1(&fs).unwrap(), 100000);
+    }
+
+    #[test]
+    fn cd_to_root() {
+        // `cd /` goes back to the root instead of creating a directory `/`.
+        let fs = day7_input("$ cd /\n$ ls\ndir a\n$ cd a\n$ cd /\n$ ls\n5 b").unwrap();
+        assert_eq!(day7_part1(&fs).unwrap(), 5);
+    }
+
+    #[test]
+    fn part2_example_with_smaller_disk() {
+        // with a smaller disk only the root directory is big enough.
+        let fs = day7_input(EXAMPLE).unwrap();
+        assert_eq!(
+            find_directory_to_delete(&fs, 50000000, 30000000).unwrap(),
+            48381165
+        );
+    }
+
+    #[test]
+    fn directory_of_exactly_the_needed_size() {
+        let fs = day7_input("$ cd /\n$ ls\ndir a\n40000000 b\n$ cd a\n$ ls\n1 c").unwrap();
+        assert_eq!(
+            find_directory_to_delete(&fs, 70000000, 30000000).unwrap(),
+            1
+        );
+    }
+
+    #[test]
+    fn enough_free_space() {
+        // nothing needs to be deleted, so there's no directory to answer with.
+        let fs = day7_input("$ cd /\n$ ls\ndir a\n$ cd a\n$ ls\n1000 b").unwrap();
+        assert!(matches!(day7_part2(&fs), Err(Error::NoSolution(_))));
+    }
+
+    #[test]
+    fn exactly_enough_free_space() {
+        let fs = day7_input("$ cd /\n$ ls\n35000000 a\n5000000 b").unwrap();
+        assert!(matches!(
+            find_directory_to_delete(&fs, 70000000, 30000000),
+            Err(Error::NoSolution(_))
+        ));
+    }
 }
@@ -1,58 +1,50 @@
-use std::collections::HashSet;
-
-pub struct Grid {
-    data: Vec<Vec<u8>>,
-    width: usize,
-    height: usize,
+use std::{
+    collections::HashSet,
+    iter,
+};
+
+use crate::{
+    error::Error,
+    geometry::{
+        Direction,
+        Point2,
+    },
+    grid::Grid,
+    solution::Solution,
+};
+
+#[derive(Clone, Debug)]
+pub struct Forest {
+    trees: Grid<u8>,
 }
 
-impl Grid {
-    pub fn get(&self, x: usize, y: usize) -> i8 {
-        self.data[y][x] as i8
-    }
-
+impl Forest {
     pub fn visible_trees(&self) -> usize {
         let mut visible = HashSet::new();
 
-        for y in 0..self.height {
-            let mut max_height = -1;
-            for x in 0..self.width {
-                let height = self.get(x, y);
-                if height > max_height {
-                    visible.insert((x, y));
-                    max_height = height;
-                }
-            }
-        }
-
-        for y in 0..self.height {
-            let mut max_height = -1;
-            for x in (0..self.width).rev() {
-                let height = self.get(x, y);
-                if height > max_height {
-                    visible.insert((x, y));
-                    max_height = height;
-                }
-            }
-        }
-
-        for x in 0..self.width {
-            let mut max_height = -1;
-            for y in 0..self.height {
-                let height = self.get(x, y);
-                if height > max_height {
-                    visible.insert((x, y));
-                    max_height = height;
-                }
-            }
-        }
-
-        for x in 0..self.width {
+        // look into the forest from every tree on the edge
+        let right = self.trees.width() - 1;
+        let bottom = self.trees.height() - 1;
+        let edges = (0..self.trees.height())
+            .flat_map(|y| {
+                [
+                    (Point2::new(0, y), Direction::Right),
+                    (Point2::new(right, y), Direction::Left),
+                ]
+            })
+            .chain((0..self.trees.width()).flat_map(|x| {
+                [
+                    (Point2::new(x, 0), Direction::Down),
+                    (Point2::new(x, bottom), Direction::Up),
+                ]
+            }));
+
+        for (start, direction) in edges {
             let mut max_height = -1;
-            for y in (0..self.height).rev() {
-                let height = self.get(x, y);
+            for position in iter::once(start).chain(self.trees.ray(start, direction)) {
+                let height = self.trees[position] as i8;
                 if height > max_height {
-                    visible.insert((x, y));
+                    visible.insert(position);
                     max_height = height;
                 }
             }
@@ -61,98 +53,106 @@ impl Grid {
         visible.len()
     }
 
-    pub fn scenic_score(&self, x0: usize, y0: usize) -> usize {
-        let h0 = self.get(x0, y0);
-
-        let mut west = 0;
-        for x in (0..x0).rev() {
-            let h = self.get(x, y0);
-            west += 1;
-            if h >= h0 {
-                break;
-            }
-        }
-
-        let mut east = 0;
-        for x in (x0 + 1)..self.width {
-            let h = self.get(x, y0);
-            east += 1;
-            if h >= h0 {
-                break;
-            }
-        }
+    /// returns how many trees can be seen from `position` in `direction`.
+    pub fn viewing_distance(&self, position: Point2<usize>, direction: Direction) -> usize {
+        let h0 = self.trees[position];
+        let mut distance = 0;
 
-        let mut north = 0;
-        for y in (0..y0).rev() {
-            let h = self.get(x0, y);
-            north += 1;
-            if h >= h0 {
+        for other in self.trees.ray(position, direction) {
+            distance += 1;
+            if self.trees[other] >= h0 {
                 break;
             }
         }
 
-        let mut south = 0;
-        for y in (y0 + 1)..self.height {
-            let h = self.get(x0, y);
-            south += 1;
-            if h >= h0 {
-                break;
-            }
-        }
+        distance
+    }
 
-        west * east * north * south
+    pub fn scenic_score(&self, position: Point2<usize>) -> usize {
+        Direction::ALL
+            .into_iter()
+            .map(|direction| self.viewing_distance(position, direction))
+            .product()
     }
 
     pub fn best_scenic_score(&self) -> usize {
-        let mut best = None;
-
-        for y in 0..self.height {
-            for x in 0..self.width {
-                let score = self.scenic_score(x, y);
-
-                if let Some((best_at, best_score)) = &mut best {
-                    if score > *best_score {
-                        *best_at = (x, y);
-                        *best_score = score;
-                    }
-                }
-                else {
-                    best = Some(((x, y), score));
-                }
-            }
-        }
-
-        best.unwrap().1
+        self.trees
+            .positions()
+            .map(|position| self.scenic_score(position))
+            .max()
+            .expect("the forest has trees")
     }
 }
 
 #[aoc_generator(day8)]
-fn day8_input(input: &str) -> Grid {
-    let data = input
-        .lines()
-        .map(|line| {
-            line.chars()
-                .map(|c| c.to_string().parse().unwrap())
-                .collect::<Vec<u8>>()
-        })
-        .collect::<Vec<Vec<u8>>>();
-
-    let width = data[0].len();
-    let height = data.len();
-
-    Grid {
-        data,
-        width,
-        height,
-    }
+pub fn day8_input(input: &str) -> Result<Forest, Error> {
+    let trees = Grid::parse(input, "a tree height", |c| {
+        c.to_digit(10).map(|height| height as u8)
+    })?;
+
+    Ok(Forest { trees })
 }
 
 #[aoc(day8, part1)]
-fn day8_part1(grid: &Grid) -> usize {
-    grid.visible_trees()
+pub fn day8_part1(forest: &Forest) -> Result<usize, Error> {
+    Ok(forest.visible_trees())
 }
 
 #[aoc(day8, part2)]
-fn day8_part2(grid: &Grid) -> usize {
-    grid.best_scenic_score()
+pub fn day8_part2(forest: &Forest) -> Result<usize, Error> {
+    Ok(forest.best_scenic_score())
+}
+
+pub struct Day8;
+
+impl Solution for Day8 {
+    const DAY: u8 = 8;
+
+    type Input = Forest;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        day8_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Part1, Error> {
+        day8_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Part2, Error> {
+        day8_part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "30373
+25512
+65332
+33549
+35390";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(day8_part1(&day8_input(EXAMPLE).unwrap()).unwrap(), 21);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(day8_part2(&day8_input(EXAMPLE).unwrap()).unwrap(), 8);
+    }
+
+    #[test]
+    fn scenic_score_example() {
+        let forest = day8_input(EXAMPLE).unwrap();
+        let position = Point2::new(2, 3);
+        assert_eq!(forest.viewing_distance(position, Direction::Up), 2);
+        assert_eq!(forest.viewing_distance(position, Direction::Left), 2);
+        assert_eq!(forest.viewing_distance(position, Direction::Down), 1);
+        assert_eq!(forest.viewing_distance(position, Direction::Right), 2);
+        assert_eq!(forest.scenic_score(position), 8);
+    }
 }
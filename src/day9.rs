@@ -1,71 +1,99 @@
 use std::{
     collections::HashSet,
+    hash::Hash,
     str::FromStr,
 };
 
 use lazy_static::lazy_static;
-use nalgebra::Vector2;
+use num_traits::Signed;
 use regex::Regex;
-use thiserror::Error;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use tracing::trace;
 
-lazy_static! {
-    pub static ref MOVEMENT_REGEX: Regex = r"([UDLR]) (\d+)".parse().unwrap();
-}
+use crate::{
+    error::Error,
+    geometry::{
+        BoundingBox,
+        Coordinate,
+        Direction,
+        Point2,
+    },
+    input::{
+        self,
+        Line,
+    },
+    num,
+    render::{
+        Animation,
+        Cell,
+        Frame,
+        FrameSink,
+        Render,
+        RenderError,
+        Rgb,
+    },
+    simulation::Simulation,
+    solution::Solution,
+    stream::Incremental,
+};
 
-#[derive(Copy, Clone, Debug)]
-pub enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
+lazy_static! {
+    pub static ref MOVEMENT_REGEX: Regex = r"^([UDLR]) (\d+)$".parse().unwrap();
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
 pub struct Movement {
     direction: Direction,
     count: u64,
 }
 
-#[derive(Debug, Error)]
-#[error("failed to parse movement: {0}")]
-pub struct MovementParseError(String);
-
 impl FromStr for Movement {
-    type Err = MovementParseError;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let err = || MovementParseError(s.to_owned());
-        let captures = MOVEMENT_REGEX.captures(s).ok_or_else(err)?;
-        let direction = match captures.get(1).ok_or_else(err)?.as_str() {
+        let captures = MOVEMENT_REGEX
+            .captures(s)
+            .ok_or_else(|| Error::parse(1, s, s, "expected a movement"))?;
+        let direction = match captures.get(1).unwrap().as_str() {
             "U" => Direction::Up,
             "D" => Direction::Down,
             "L" => Direction::Left,
             "R" => Direction::Right,
-            _ => return Err(err()),
+            _ => unreachable!("direction is matched by regex"),
         };
-        let count = captures
-            .get(2)
-            .ok_or_else(err)?
-            .as_str()
+        let count = captures.get(2).unwrap().as_str();
+        let count = count
             .parse()
-            .map_err(|_| err())?;
+            .map_err(|_| Error::parse(1, s, count, "expected a number of steps"))?;
         Ok(Movement { direction, count })
     }
 }
 
-#[derive(Debug)]
-pub struct Rope {
-    knots: Vec<Vector2<i32>>,
-    tail_positions: HashSet<Vector2<i32>>,
+/// a rope with knots at positions of type `T`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "T: Serialize",
+    deserialize = "T: Deserialize<'de> + Eq + Hash"
+))]
+pub struct Rope<T = i32> {
+    knots: Vec<Point2<T>>,
+    tail_positions: HashSet<Point2<T>>,
 }
 
-impl Rope {
+impl<T: Coordinate + Signed> Rope<T> {
+    /// creates a rope of `length` knots at the start. panics if `length` is 0,
+    /// since every rope has a head.
     pub fn new(length: usize) -> Self {
+        assert!(length > 0, "a rope needs at least one knot");
+
         let mut knots = Vec::with_capacity(length);
         knots.resize_with(length, Default::default);
 
         let mut tail_positions = HashSet::new();
-        tail_positions.insert(Vector2::new(0, 0));
+        tail_positions.insert(Point2::zero());
 
         Self {
             knots,
@@ -73,118 +101,506 @@ impl Rope {
         }
     }
 
-    pub fn apply(&mut self, movement: Movement) {
-        //println!("== {:?} ==", movement);
-
-        for _ in 0..movement.count {
-            self.move_head(movement.direction);
-            //self.print();
-        }
-    }
-
-    fn move_head(&mut self, direction: Direction) {
-        // move head
-        let d_head = match direction {
-            Direction::Up => Vector2::new(0, 1),
-            Direction::Down => Vector2::new(0, -1),
-            Direction::Left => Vector2::new(-1, 0),
-            Direction::Right => Vector2::new(1, 0),
-        };
-        self.knots[0] += d_head;
+    /// moves the head one position in `direction`, or returns an error if
+    /// that position doesn't fit into `T`.
+    fn move_head(&mut self, direction: Direction) -> Result<(), Error> {
+        // move head. the other knots never get further from the start than
+        // the head, so only the head can overflow.
+        self.knots[0] = self.knots[0].checked_step(direction).ok_or_else(|| {
+            num::overflow::<T>(format_args!(
+                "moving the head {:?} from {:?}",
+                direction, self.knots[0]
+            ))
+        })?;
 
         // move knots
         for i in 1..self.knots.len() {
-            let d = self.knots[i - 1] - self.knots[i];
-
-            if d.x.abs() > 1 || d.y.abs() > 1 {
-                self.knots[i].x += d.x.signum();
-                self.knots[i].y += d.y.signum();
+            if self.knots[i - 1].chebyshev_distance(self.knots[i]) > T::one() {
+                let step = (self.knots[i - 1] - self.knots[i]).signum();
+                self.knots[i] += step;
             }
         }
 
         // track tail
         self.tail_positions.insert(self.knots[self.knots.len() - 1]);
+
+        Ok(())
     }
 
-    pub fn print(&self) {
-        for y in (0..5).rev() {
-            for x in 0..6 {
-                let x = Vector2::new(x, y);
-
-                if self.knots[0] == x {
-                    print!("H");
-                }
-                else {
-                    let mut knot_printed = false;
-
-                    for i in 1..self.knots.len() {
-                        if x == self.knots[i] {
-                            print!("{}", i);
-                            knot_printed = true;
-                            break;
-                        }
-                    }
-
-                    if !knot_printed {
-                        if x == Vector2::zeros() {
-                            print!("s");
-                        }
-                        else {
-                            print!(".");
-                        }
-                    }
-                }
+    pub fn num_tail_positions(&self) -> usize {
+        self.tail_positions.len()
+    }
+}
+
+impl Rope {
+    /// returns a view of the rope that only shows where the tail has been.
+    pub fn tail_trail(&self) -> TailTrail<'_> {
+        TailTrail(self)
+    }
+}
+
+/// a rope whose head follows `movements`. a step moves the head by one
+/// position.
+///
+/// the simulation stops if the head would leave the range of `T`, which
+/// [`RopeSimulation::try_step`] reports as an error.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "T: Serialize",
+    deserialize = "T: Deserialize<'de> + Eq + Hash"
+))]
+pub struct RopeSimulation<T = i32> {
+    rope: Rope<T>,
+    movements: Vec<Movement>,
+    /// the current movement, and how far the head moved along it. movements
+    /// the head moved all the way along are skipped right away, so the
+    /// simulation is finished once `movement` is past the last one.
+    movement: usize,
+    moved: u64,
+    steps: usize,
+}
+
+impl<T: Coordinate + Signed> RopeSimulation<T> {
+    pub fn new(length: usize, movements: Vec<Movement>) -> Self {
+        let mut simulation = Self {
+            rope: Rope::new(length),
+            movements,
+            movement: 0,
+            moved: 0,
+            steps: 0,
+        };
+        simulation.skip_finished_movements();
+        simulation
+    }
+
+    fn skip_finished_movements(&mut self) {
+        while let Some(movement) = self.movements.get(self.movement) {
+            if self.moved < movement.count {
+                break;
             }
-            println!("");
+            self.movement += 1;
+            self.moved = 0;
         }
-        println!("");
-    }
-
-    pub fn print_tail(&self) {
-        for y in (0..5).rev() {
-            for x in 0..6 {
-                let x = Vector2::<i32>::new(x, y);
-                if x == Vector2::zeros() {
-                    print!("s");
-                }
-                else if self.tail_positions.contains(&x) {
-                    print!("#");
-                }
-                else {
-                    print!(".");
-                }
-            }
-            println!("");
+    }
+
+    pub fn rope(&self) -> &Rope<T> {
+        &self.rope
+    }
+
+    pub fn into_rope(self) -> Rope<T> {
+        self.rope
+    }
+
+    /// takes the next step like [`Simulation::step`], but returns an error
+    /// without changing anything if the head would overflow.
+    pub fn try_step(&mut self) -> Result<bool, Error> {
+        let Some(movement) = self.movements.get(self.movement)
+        else {
+            return Ok(false);
+        };
+
+        if self.moved == 0 {
+            trace!(?movement, "moving head");
         }
-        println!("");
+        self.rope.move_head(movement.direction)?;
+        self.moved += 1;
+        self.steps += 1;
+        self.skip_finished_movements();
+        Ok(true)
     }
 
-    pub fn num_tail_positions(&self) -> usize {
-        self.tail_positions.len()
+    /// runs until the simulation is finished, or returns an error once the
+    /// head would overflow.
+    pub fn try_run(&mut self) -> Result<usize, Error> {
+        let mut steps = 0;
+        while self.try_step()? {
+            steps += 1;
+        }
+        Ok(steps)
+    }
+}
+
+/// stops before the head would overflow, without finishing.
+impl<T: Coordinate + Signed> Simulation for RopeSimulation<T> {
+    fn step(&mut self) -> bool {
+        self.try_step().unwrap_or(false)
+    }
+
+    fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// finished once the head moved all the way along the last movement.
+    fn is_finished(&self) -> bool {
+        self.movement >= self.movements.len()
+    }
+}
+
+const EMPTY: Cell = Cell::new('.', Rgb(16, 16, 32));
+const START: Cell = Cell::new('s', Rgb(64, 96, 255));
+const TRAIL: Cell = Cell::new('#', Rgb(40, 160, 80));
+const HEAD_COLOR: Rgb = Rgb(255, 64, 64);
+const KNOT_COLOR: Rgb = Rgb(255, 180, 64);
+
+/// draws the tail positions and the starting position of `rope` into a new
+/// frame.
+fn render_trail(rope: &Rope, area: BoundingBox<i32>) -> Frame {
+    let mut frame = Frame::for_area(area, EMPTY);
+
+    for position in &rope.tail_positions {
+        frame.set_point(area, *position, TRAIL);
+    }
+    frame.set_point(area, Point2::zero(), START);
+
+    frame
+}
+
+fn knot_cell(i: usize) -> Cell {
+    if i == 0 {
+        Cell::new('H', HEAD_COLOR)
+    }
+    else {
+        let glyph = char::from_digit((i % 10) as u32, 10).unwrap();
+        Cell::new(glyph, KNOT_COLOR)
+    }
+}
+
+/// shows the knots on top of the tail positions. knots closer to the head
+/// cover the ones behind them.
+impl Render for Rope {
+    fn bounds(&self) -> BoundingBox<i32> {
+        let mut area = BoundingBox::from_point(Point2::zero());
+        for position in self.knots.iter().chain(&self.tail_positions) {
+            area.include(*position);
+        }
+        area
+    }
+
+    fn render_area(&self, area: BoundingBox<i32>) -> Frame {
+        let mut frame = render_trail(self, area);
+
+        for (i, knot) in self.knots.iter().enumerate().rev() {
+            frame.set_point(area, *knot, knot_cell(i));
+        }
+
+        frame
+    }
+}
+
+impl Render for RopeSimulation {
+    fn bounds(&self) -> BoundingBox<i32> {
+        self.rope.bounds()
+    }
+
+    fn render_area(&self, area: BoundingBox<i32>) -> Frame {
+        self.rope.render_area(area)
     }
 }
 
-fn simulate_rope(length: usize, movements: &[Movement]) -> usize {
-    let mut rope = Rope::new(length);
+/// the positions the tail of a [`Rope`] has visited.
+pub struct TailTrail<'a>(&'a Rope);
 
+impl<'a> Render for TailTrail<'a> {
+    fn bounds(&self) -> BoundingBox<i32> {
+        let mut area = BoundingBox::from_point(Point2::zero());
+        for position in &self.0.tail_positions {
+            area.include(*position);
+        }
+        area
+    }
+
+    fn render_area(&self, area: BoundingBox<i32>) -> Frame {
+        render_trail(self.0, area)
+    }
+}
+
+/// simulates a rope of `length` knots and pushes a frame after every step of
+/// the head.
+///
+/// every frame shows the area the head visits during the whole simulation.
+pub fn animate_rope(
+    length: usize,
+    movements: &[Movement],
+    sink: &mut dyn FrameSink,
+) -> Result<(), RenderError> {
+    // the knots follow the head, so they never leave the area the head visits.
+    let mut area = BoundingBox::from_point(Point2::zero());
+    let mut head = Point2::<i32>::zero();
     for movement in movements {
-        rope.apply(*movement);
+        // the simulation stops at the last position that fits into an `i32`
+        let count = i64::try_from(movement.count).unwrap_or(i64::MAX);
+        let offset = movement.direction.offset::<i64>();
+        let target = Point2::new(
+            i64::from(head.x).saturating_add(offset.x.saturating_mul(count)),
+            i64::from(head.y).saturating_add(offset.y.saturating_mul(count)),
+        );
+        let fits = |c: i64| i32::try_from(c).unwrap_or(if c < 0 { i32::MIN } else { i32::MAX });
+        head = Point2::new(fits(target.x), fits(target.y));
+        area.include(head);
+        if Point2::new(i64::from(head.x), i64::from(head.y)) != target {
+            break;
+        }
+    }
+
+    let mut animation = Animation::new(sink, area);
+    RopeSimulation::new(length, movements.to_vec()).run_with(&mut animation);
+    animation.finish()
+}
+
+/// returns the rope of `length` knots after all `movements`, or after the
+/// last step before the head would overflow.
+pub fn simulate_rope_state(length: usize, movements: &[Movement]) -> Rope {
+    let mut simulation = RopeSimulation::new(length, movements.to_vec());
+    simulation.run();
+    let rope = simulation.into_rope();
+
+    trace!(
+        "tail visited {} positions:\n{}",
+        rope.num_tail_positions(),
+        rope.tail_trail().render()
+    );
+
+    rope
+}
+
+/// returns the number of positions the tail of a rope of `length` knots
+/// visits, with positions of type `T`.
+pub fn simulate_rope<T: Coordinate + Signed>(
+    length: usize,
+    movements: &[Movement],
+) -> Result<usize, Error> {
+    let mut simulation = RopeSimulation::<T>::new(length, movements.to_vec());
+    simulation.try_run()?;
+    Ok(simulation.rope().num_tail_positions())
+}
+
+/// moves the ropes of both parts while the movements arrive.
+///
+/// unlike the other incremental days, this has to remember every position the
+/// tails visited, so its memory grows with the area they cover.
+#[derive(Clone, Debug)]
+pub struct TailTracker<T = i32> {
+    short: Rope<T>,
+    long: Rope<T>,
+}
+
+impl<T: Coordinate + Signed> Default for TailTracker<T> {
+    fn default() -> Self {
+        Self {
+            short: Rope::new(2),
+            long: Rope::new(10),
+        }
+    }
+}
+
+impl<T: Coordinate + Signed> Incremental for TailTracker<T> {
+    const DAY: u8 = 9;
+
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn push_line(&mut self, line: Line<'_>) -> Result<(), Error> {
+        let movement: Movement = line
+            .text
+            .parse()
+            .map_err(|e: Error| e.at_line(line.number))?;
+        for _ in 0..movement.count {
+            self.short.move_head(movement.direction)?;
+            self.long.move_head(movement.direction)?;
+        }
+        Ok(())
     }
 
-    rope.num_tail_positions()
+    fn part1(&self) -> Result<usize, Error> {
+        Ok(self.short.num_tail_positions())
+    }
+
+    fn part2(&self) -> Result<usize, Error> {
+        Ok(self.long.num_tail_positions())
+    }
 }
 
 #[aoc_generator(day9)]
-fn day9_input(input: &str) -> Vec<Movement> {
-    input.lines().map(|line| line.parse().unwrap()).collect()
+pub fn day9_input(input: &str) -> Result<Vec<Movement>, Error> {
+    input::lines(input)
+        .map(|line| line.text.parse().map_err(|e: Error| e.at_line(line.number)))
+        .collect()
 }
 
 #[aoc(day9, part1)]
-fn day9_part1(movements: &[Movement]) -> usize {
-    simulate_rope(2, movements)
+pub fn day9_part1(movements: &[Movement]) -> Result<usize, Error> {
+    simulate_rope::<i32>(2, movements)
 }
 
 #[aoc(day9, part2)]
-fn day9_part2(movements: &[Movement]) -> usize {
-    simulate_rope(10, movements)
+pub fn day9_part2(movements: &[Movement]) -> Result<usize, Error> {
+    simulate_rope::<i32>(10, movements)
+}
+
+pub struct Day9;
+
+impl Solution for Day9 {
+    const DAY: u8 = 9;
+
+    type Input = Vec<Movement>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        day9_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Part1, Error> {
+        day9_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Part2, Error> {
+        day9_part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "R 4
+U 4
+L 3
+D 1
+R 4
+D 1
+L 5
+R 2";
+
+    const LARGER_EXAMPLE: &str = "R 5
+U 8
+L 8
+D 3
+R 17
+D 10
+L 25
+U 20";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(day9_part1(&day9_input(EXAMPLE).unwrap()).unwrap(), 13);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(day9_part2(&day9_input(EXAMPLE).unwrap()).unwrap(), 1);
+    }
+
+    #[test]
+    fn part2_larger_example() {
+        assert_eq!(
+            day9_part2(&day9_input(LARGER_EXAMPLE).unwrap()).unwrap(),
+            36
+        );
+    }
+
+    #[test]
+    fn stream_larger_example() {
+        let mut tracker = TailTracker::<i32>::default();
+        for line in input::lines(LARGER_EXAMPLE) {
+            tracker.push_line(line).unwrap();
+        }
+        assert_eq!(tracker.part2().unwrap(), 36);
+
+        let error = tracker
+            .push_line(Line {
+                number: 9,
+                text: "X 1",
+            })
+            .unwrap_err();
+        assert!(matches!(error, Error::Parse { line: 9, .. }));
+    }
+
+    #[test]
+    fn simulation_steps() {
+        let mut simulation = RopeSimulation::new(2, day9_input(EXAMPLE).unwrap());
+        assert_eq!(
+            simulation.run_until(|simulation| simulation.steps() == 4),
+            4
+        );
+        assert_eq!(simulation.rope().knots[0], Point2::new(4, 0));
+        assert!(!simulation.is_finished());
+
+        // one step per position the head moves
+        assert_eq!(simulation.run(), 20);
+        assert!(simulation.is_finished());
+        assert_eq!(simulation.rope().num_tail_positions(), 13);
+    }
+
+    #[test]
+    fn overflow() {
+        let movements = day9_input("R 100\nL 30\nR 60").unwrap();
+        assert!(matches!(
+            simulate_rope::<i8>(2, &movements),
+            Err(Error::Overflow(_))
+        ));
+        assert_eq!(simulate_rope::<i16>(2, &movements).unwrap(), 130);
+
+        // the simulation stops at the last position that fits
+        let mut simulation = RopeSimulation::<i8>::new(2, movements);
+        assert_eq!(simulation.run(), 187);
+        assert!(!simulation.is_finished());
+        assert_eq!(simulation.rope().knots[0], Point2::new(127, 0));
+        assert!(simulation.try_step().is_err());
+    }
+
+    #[test]
+    fn finished_movements() {
+        let movements = day9_input("R 0\nU 1\nL 0").unwrap();
+        let mut simulation = RopeSimulation::<i32>::new(2, movements);
+        assert!(!simulation.is_finished());
+        assert_eq!(simulation.run(), 1);
+        assert!(simulation.is_finished());
+
+        let movements = day9_input(&format!("R {}\nL 1", u64::MAX)).unwrap();
+        let mut simulation = RopeSimulation::<i8>::new(2, movements);
+        assert_eq!(simulation.run(), 127);
+        assert!(!simulation.is_finished());
+    }
+
+    #[test]
+    #[should_panic(expected = "a rope needs at least one knot")]
+    fn rope_without_knots() {
+        Rope::<i32>::new(0);
+    }
+
+    #[test]
+    fn render_example() {
+        let rope = simulate_rope_state(10, &day9_input(EXAMPLE).unwrap());
+        assert_eq!(
+            rope.render().to_ascii(),
+            ".1H3
+.5..
+6...
+"
+        );
+        assert_eq!(rope.tail_trail().render().to_ascii(), "s\n");
+    }
+
+    #[test]
+    fn animate_example() {
+        let movements = day9_input(EXAMPLE).unwrap();
+        let mut frames = vec![];
+        animate_rope(2, &movements, &mut frames).unwrap();
+
+        // the initial state and one frame per step
+        assert_eq!(frames.len(), 25);
+        assert!(frames
+            .iter()
+            .all(|frame| frame.width() == 6 && frame.height() == 5));
+        assert_eq!(
+            frames.last().unwrap().to_ascii(),
+            "..##..
+...##.
+.1H##.
+....#.
+s###..
+"
+        );
+    }
 }
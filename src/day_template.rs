@@ -1,9 +1,60 @@
+use crate::{
+    error::Error,
+    solution::Solution,
+};
+
 #[aoc_generator(dayX)]
-fn dayX_input(input: &str) -> () {
-    todo!();
+pub fn dayX_input(input: &str) -> Result<Vec<String>, Error> {
+    Ok(input.lines().map(str::to_owned).collect())
 }
 
 #[aoc(dayX, part1)]
-fn dayX_part1(_: &()) -> String {
-    todo!();
+pub fn dayX_part1(_input: &[String]) -> Result<usize, Error> {
+    Err(Error::NoSolution("part 1 is not solved yet".to_owned()))
+}
+
+#[aoc(dayX, part2)]
+pub fn dayX_part2(_input: &[String]) -> Result<usize, Error> {
+    Err(Error::NoSolution("part 2 is not solved yet".to_owned()))
+}
+
+pub struct DayX;
+
+impl Solution for DayX {
+    const DAY: u8 = X;
+
+    type Input = Vec<String>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        dayX_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Part1, Error> {
+        dayX_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Part2, Error> {
+        dayX_part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "";
+
+    #[test]
+    #[ignore = "not solved yet"]
+    fn part1_example() {
+        assert_eq!(dayX_part1(&dayX_input(EXAMPLE).unwrap()).unwrap(), 0);
+    }
+
+    #[test]
+    #[ignore = "not solved yet"]
+    fn part2_example() {
+        assert_eq!(dayX_part2(&dayX_input(EXAMPLE).unwrap()).unwrap(), 0);
+    }
 }
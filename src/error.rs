@@ -0,0 +1,122 @@
+use thiserror::Error;
+
+/// errors returned by the generators and solvers.
+///
+/// line and column numbers start at 1.
+#[derive(Clone, Debug, Error)]
+pub enum Error {
+    #[error("line {line}, column {column}: {message}: {text:?}")]
+    Parse {
+        line: usize,
+        column: usize,
+        text: String,
+        message: String,
+    },
+    #[error("line {line}: unexpected end of input, expected {expected}")]
+    UnexpectedEnd { line: usize, expected: String },
+    #[error("invalid input: {0}")]
+    InvalidInput(String),
+    #[error("no solution: {0}")]
+    NoSolution(String),
+    #[error("overflow: {0}")]
+    Overflow(String),
+}
+
+impl Error {
+    /// creates a parse error for `text`, which should be a substring of
+    /// `line`. the column is derived from where `text` is in `line`.
+    pub fn parse(line_number: usize, line: &str, text: &str, message: impl Into<String>) -> Self {
+        Error::Parse {
+            line: line_number,
+            column: column_of(line, text),
+            text: text.to_owned(),
+            message: message.into(),
+        }
+    }
+
+    /// moves an error for a single line (e.g. from a [`FromStr`] impl) to the
+    /// given line of the whole input.
+    ///
+    /// [`FromStr`]: std::str::FromStr
+    pub fn at_line(self, line_number: usize) -> Self {
+        match self {
+            Error::Parse {
+                column,
+                text,
+                message,
+                ..
+            } => {
+                Error::Parse {
+                    line: line_number,
+                    column,
+                    text,
+                    message,
+                }
+            }
+            Error::UnexpectedEnd { expected, .. } => {
+                Error::UnexpectedEnd {
+                    line: line_number,
+                    expected,
+                }
+            }
+            error => error,
+        }
+    }
+}
+
+/// returns the column at which `text` starts in `line`, or 1 if `text` isn't
+/// part of `line`.
+fn column_of(line: &str, text: &str) -> usize {
+    let line_start = line.as_ptr() as usize;
+    let text_start = text.as_ptr() as usize;
+
+    if text_start >= line_start && text_start <= line_start + line.len() {
+        let offset = text_start - line_start;
+        line.get(..offset)
+            .map(|before| before.chars().count() + 1)
+            .unwrap_or(1)
+    }
+    else {
+        1
+    }
+}
+
+/// formats an error together with its sources.
+pub fn error_chain(error: &dyn std::error::Error) -> String {
+    let mut s = error.to_string();
+    let mut source = error.source();
+    while let Some(error) = source {
+        s.push_str(&format!(": {}", error));
+        source = error.source();
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn column_of_substring() {
+        let line = "move 3 from 1 to 2";
+        assert_eq!(column_of(line, &line[5..6]), 6);
+        assert_eq!(column_of(line, line), 1);
+        assert_eq!(column_of(line, &line[line.len()..]), line.len() + 1);
+    }
+
+    #[test]
+    fn column_of_unrelated_text() {
+        let text = String::from("3");
+        assert_eq!(column_of("move 3 from 1 to 2", &text), 1);
+    }
+
+    #[test]
+    fn display() {
+        let line = "1-2,x-4";
+        let error = Error::parse(3, line, &line[4..5], "expected a number");
+        assert_eq!(
+            error.to_string(),
+            "line 3, column 5: expected a number: \"x\""
+        );
+    }
+}
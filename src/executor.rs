@@ -0,0 +1,739 @@
+//! runs many days at once on a thread pool.
+//!
+//! every part is a separate job, so the two parts of a day run in parallel
+//! too, and each of them parses the input on its own. a panic only fails the
+//! job it happened in.
+//!
+//! since the jobs compete for the cores, the times are a bit higher than when
+//! running a single day.
+//!
+//! the [`Report`] of a run is printed as a table, or as JSON, Markdown or
+//! JUnit XML for other tools to pick up. with the `alloc-profile` feature, it
+//! also has the allocations of every part (see [`memory`](crate::memory)).
+
+use std::{
+    any::Any,
+    fmt::{
+        self,
+        Write,
+    },
+    panic::{
+        self,
+        AssertUnwindSafe,
+    },
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use rayon::{
+    prelude::*,
+    ThreadPoolBuildError,
+    ThreadPoolBuilder,
+};
+use serde::{
+    Serialize,
+    Serializer,
+};
+
+use crate::{
+    error::error_chain,
+    input,
+    memory::{
+        AllocStats,
+        Bytes,
+    },
+    runner::Part,
+    solution::Solver,
+};
+
+/// the columns for [`PartReport::allocs`].
+const ALLOC_HEADERS: [&str; 6] = [
+    "parse allocs",
+    "parse bytes",
+    "parse peak",
+    "solve allocs",
+    "solve bytes",
+    "solve peak",
+];
+
+/// one part of a day to run.
+#[derive(Clone, Copy)]
+pub struct Job<'a> {
+    pub solver: &'a dyn Solver,
+    pub part: Part,
+    pub input: &'a str,
+}
+
+/// how a part ended.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum Outcome {
+    Solved {
+        answer: String,
+        #[serde(rename = "parse_seconds", serialize_with = "seconds")]
+        parse_time: Duration,
+        #[serde(rename = "solve_seconds", serialize_with = "seconds")]
+        solve_time: Duration,
+        /// the allocations of the generator, with the `alloc-profile` feature.
+        #[serde(rename = "parse_memory", skip_serializing_if = "Option::is_none")]
+        parse_allocs: Option<AllocStats>,
+        /// the allocations of the part, with the `alloc-profile` feature.
+        #[serde(rename = "solve_memory", skip_serializing_if = "Option::is_none")]
+        solve_allocs: Option<AllocStats>,
+    },
+    Failed {
+        error: String,
+    },
+    Panicked {
+        error: String,
+    },
+}
+
+impl Outcome {
+    fn status(&self) -> &'static str {
+        match self {
+            Self::Solved { .. } => "solved",
+            Self::Failed { .. } => "failed",
+            Self::Panicked { .. } => "panicked",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct PartReport {
+    pub day: u8,
+    #[serde(serialize_with = "part_number")]
+    pub part: Part,
+    /// the hash of the input (see [`input::hash`]), unless it couldn't be
+    /// read.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input_hash: Option<String>,
+    #[serde(flatten)]
+    pub outcome: Outcome,
+}
+
+impl PartReport {
+    pub fn is_solved(&self) -> bool {
+        matches!(self.outcome, Outcome::Solved { .. })
+    }
+
+    /// the time it took to parse the input and solve the part.
+    fn time(&self) -> Duration {
+        match self.outcome {
+            Outcome::Solved {
+                parse_time,
+                solve_time,
+                ..
+            } => parse_time + solve_time,
+            _ => Duration::ZERO,
+        }
+    }
+
+    /// the parse and solve time, formatted for tables.
+    fn times(&self) -> (String, String) {
+        match &self.outcome {
+            Outcome::Solved {
+                parse_time,
+                solve_time,
+                ..
+            } => (format!("{:.2?}", parse_time), format!("{:.2?}", solve_time)),
+            _ => ("-".to_owned(), "-".to_owned()),
+        }
+    }
+
+    /// whether the allocations of the part were counted.
+    fn has_allocs(&self) -> bool {
+        matches!(
+            self.outcome,
+            Outcome::Solved {
+                parse_allocs: Some(_),
+                solve_allocs: Some(_),
+                ..
+            }
+        )
+    }
+
+    /// the allocations, bytes and peak bytes of parsing and solving, formatted
+    /// for tables.
+    fn allocs(&self) -> [String; 6] {
+        let (parse_allocs, solve_allocs) = match self.outcome {
+            Outcome::Solved {
+                parse_allocs,
+                solve_allocs,
+                ..
+            } => (parse_allocs, solve_allocs),
+            _ => (None, None),
+        };
+        let cells = |allocs: Option<AllocStats>| {
+            match allocs {
+                Some(allocs) => {
+                    [
+                        allocs.allocations.to_string(),
+                        Bytes(allocs.bytes).to_string(),
+                        Bytes(allocs.peak_bytes).to_string(),
+                    ]
+                }
+                None => ["-".to_owned(), "-".to_owned(), "-".to_owned()],
+            }
+        };
+
+        let [parse_count, parse_bytes, parse_peak] = cells(parse_allocs);
+        let [solve_count, solve_bytes, solve_peak] = cells(solve_allocs);
+        [
+            parse_count,
+            parse_bytes,
+            parse_peak,
+            solve_count,
+            solve_bytes,
+            solve_peak,
+        ]
+    }
+
+    /// the answer without the newlines around it, or the error.
+    fn text(&self) -> &str {
+        match &self.outcome {
+            Outcome::Solved { answer, .. } => answer.trim_matches('\n'),
+            Outcome::Failed { error } | Outcome::Panicked { error } => error,
+        }
+    }
+}
+
+/// the outcome of every part, ordered by day and part.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Report {
+    #[serde(rename = "elapsed_seconds", serialize_with = "seconds")]
+    pub elapsed: Duration,
+    pub parts: Vec<PartReport>,
+}
+
+impl Report {
+    /// runs `jobs` on a pool of `threads` threads, or one per core if it's
+    /// `None`.
+    pub fn run(jobs: &[Job], threads: Option<usize>) -> Result<Self, ThreadPoolBuildError> {
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(threads.unwrap_or(0))
+            .build()?;
+
+        let start_time = Instant::now();
+        let parts = pool.install(|| jobs.par_iter().map(run_job).collect());
+        let mut report = Report {
+            elapsed: start_time.elapsed(),
+            parts,
+        };
+        report.sort();
+
+        Ok(report)
+    }
+
+    /// adds a part that failed before it could run, e.g. because its input is
+    /// missing.
+    pub fn add_failure(&mut self, day: u8, part: Part, error: String) {
+        self.parts.push(PartReport {
+            day,
+            part,
+            input_hash: None,
+            outcome: Outcome::Failed { error },
+        });
+        self.sort();
+    }
+
+    fn sort(&mut self) {
+        self.parts.sort_by_key(|part| (part.day, part.part));
+    }
+
+    /// whether the allocations of any part were counted, so that the tables
+    /// need columns for them.
+    fn has_allocs(&self) -> bool {
+        self.parts.iter().any(PartReport::has_allocs)
+    }
+
+    pub fn all_solved(&self) -> bool {
+        self.parts.iter().all(PartReport::is_solved)
+    }
+
+    fn count(&self, status: &str) -> usize {
+        self.parts
+            .iter()
+            .filter(|part| part.outcome.status() == status)
+            .count()
+    }
+
+    /// a Markdown table with a row per part. multi-line answers are split
+    /// with `<br>`, and input hashes are shortened to 12 digits.
+    pub fn markdown(&self) -> String {
+        let mut markdown = String::new();
+        let has_allocs = self.has_allocs();
+        markdown.push_str("| day | part | status | answer | parse | solve |");
+        if has_allocs {
+            for header in ALLOC_HEADERS {
+                write!(markdown, " {} |", header).expect("writing to a string can't fail");
+            }
+        }
+        markdown.push_str(" input |\n");
+        markdown.push_str("|----:|-----:|--------|--------|------:|------:|");
+        if has_allocs {
+            markdown.push_str(&"-----:|".repeat(ALLOC_HEADERS.len()));
+        }
+        markdown.push_str("-------|\n");
+
+        for part in &self.parts {
+            let (parse_time, solve_time) = part.times();
+            let text = part
+                .text()
+                .lines()
+                .map(|line| format!("`{}`", line.replace('|', "\\|")))
+                .collect::<Vec<_>>()
+                .join("<br>");
+            let input_hash = match &part.input_hash {
+                Some(input_hash) => format!("`{}`", &input_hash[..12]),
+                None => "-".to_owned(),
+            };
+
+            write!(
+                markdown,
+                "| {} | {} | {} | {} | {} | {} |",
+                part.day,
+                part.part.number(),
+                part.outcome.status(),
+                text,
+                parse_time,
+                solve_time,
+            )
+            .expect("writing to a string can't fail");
+            if has_allocs {
+                for cell in part.allocs() {
+                    write!(markdown, " {} |", cell).expect("writing to a string can't fail");
+                }
+            }
+            writeln!(markdown, " {} |", input_hash).expect("writing to a string can't fail");
+        }
+
+        writeln!(
+            markdown,
+            "\n{} parts in {:.2?}: {} solved, {} failed, {} panicked",
+            self.parts.len(),
+            self.elapsed,
+            self.count("solved"),
+            self.count("failed"),
+            self.count("panicked")
+        )
+        .expect("writing to a string can't fail");
+
+        markdown
+    }
+
+    /// JUnit XML with a test case per part, grouped by day. failed parts are
+    /// failures and panicked ones errors. the answer, the input hash and the
+    /// allocations are in the output of the test case.
+    pub fn junit(&self) -> String {
+        let mut xml = String::new();
+        let mut push_line = |line: String| {
+            xml.push_str(&line);
+            xml.push('\n');
+        };
+
+        push_line(r#"<?xml version="1.0" encoding="UTF-8"?>"#.to_owned());
+        push_line(format!(
+            r#"<testsuite name="aoc2022" tests="{}" failures="{}" errors="{}" time="{:.6}">"#,
+            self.parts.len(),
+            self.count("failed"),
+            self.count("panicked"),
+            self.elapsed.as_secs_f64()
+        ));
+
+        for part in &self.parts {
+            push_line(format!(
+                r#"  <testcase classname="aoc2022.day{}" name="day {} - {}" time="{:.6}">"#,
+                part.day,
+                part.day,
+                part.part,
+                part.time().as_secs_f64()
+            ));
+
+            let text = xml_escape(part.text());
+            match &part.outcome {
+                Outcome::Solved {
+                    parse_allocs,
+                    solve_allocs,
+                    ..
+                } => {
+                    let mut output = format!("answer: {}", text);
+                    if let Some(input_hash) = &part.input_hash {
+                        write!(output, "\ninput hash: {}", input_hash)
+                            .expect("writing to a string can't fail");
+                    }
+                    if let Some(allocs) = parse_allocs {
+                        write!(output, "\nparse memory: {}", allocs)
+                            .expect("writing to a string can't fail");
+                    }
+                    if let Some(allocs) = solve_allocs {
+                        write!(output, "\nsolve memory: {}", allocs)
+                            .expect("writing to a string can't fail");
+                    }
+                    push_line(format!("    <system-out>{}</system-out>", output));
+                }
+                Outcome::Failed { .. } => {
+                    push_line(format!(
+                        r#"    <failure message="{}" type="failed">{}</failure>"#,
+                        text, text
+                    ));
+                }
+                Outcome::Panicked { .. } => {
+                    push_line(format!(
+                        r#"    <error message="{}" type="panicked">{}</error>"#,
+                        text, text
+                    ));
+                }
+            }
+
+            push_line("  </testcase>".to_owned());
+        }
+
+        push_line("</testsuite>".to_owned());
+        xml
+    }
+}
+
+/// a table with a row per part, followed by a summary. multi-line answers,
+/// like the screen of day 10, continue on the following lines.
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let has_allocs = self.has_allocs();
+        let mut indent = 45;
+
+        write!(
+            f,
+            "{:>3}  {:>4}  {:<8}  {:>10}  {:>10}  ",
+            "day", "part", "status", "parse", "solve"
+        )?;
+        if has_allocs {
+            for header in ALLOC_HEADERS {
+                write!(f, "{:>12}  ", header)?;
+            }
+            indent += 14 * ALLOC_HEADERS.len();
+        }
+        writeln!(f, "answer")?;
+
+        for part in &self.parts {
+            let (parse_time, solve_time) = part.times();
+            let mut lines = part.text().lines();
+            write!(
+                f,
+                "{:>3}  {:>4}  {:<8}  {:>10}  {:>10}  ",
+                part.day,
+                part.part.number(),
+                part.outcome.status(),
+                parse_time,
+                solve_time,
+            )?;
+            if has_allocs {
+                for cell in part.allocs() {
+                    write!(f, "{:>12}  ", cell)?;
+                }
+            }
+            writeln!(f, "{}", lines.next().unwrap_or_default())?;
+            for line in lines {
+                writeln!(f, "{:indent$}{}", "", line)?;
+            }
+        }
+
+        write!(
+            f,
+            "{} parts in {:.2?}: {} solved, {} failed, {} panicked",
+            self.parts.len(),
+            self.elapsed,
+            self.count("solved"),
+            self.count("failed"),
+            self.count("panicked")
+        )
+    }
+}
+
+/// runs a single job and catches its panics.
+fn run_job(job: &Job) -> PartReport {
+    let result = panic::catch_unwind(AssertUnwindSafe(|| job.solver.run(job.input, &[job.part])));
+
+    let outcome = match result {
+        Ok(Ok(day_run)) => {
+            let part_run = &day_run.parts[0];
+            Outcome::Solved {
+                answer: part_run.answer.clone(),
+                parse_time: day_run.generator_time,
+                solve_time: part_run.runner_time,
+                parse_allocs: day_run.generator_allocs,
+                solve_allocs: part_run.runner_allocs,
+            }
+        }
+        Ok(Err(e)) => {
+            Outcome::Failed {
+                error: error_chain(&e),
+            }
+        }
+        Err(payload) => {
+            Outcome::Panicked {
+                error: panic_message(payload.as_ref()),
+            }
+        }
+    };
+
+    PartReport {
+        day: job.solver.day(),
+        part: job.part,
+        input_hash: Some(input::hash(job.input)),
+        outcome,
+    }
+}
+
+/// panics carry a `&str` or a `String`, depending on whether they were
+/// formatted.
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        (*message).to_owned()
+    }
+    else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    }
+    else {
+        "unknown panic".to_owned()
+    }
+}
+
+/// escapes text for XML attributes and elements.
+fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn seconds<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(duration.as_secs_f64())
+}
+
+fn part_number<S: Serializer>(part: &Part, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u8(part.number())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        error::Error,
+        solution::Solution,
+    };
+
+    /// a day whose first part panics.
+    struct Panics;
+
+    impl Solution for Panics {
+        const DAY: u8 = 99;
+
+        type Input = ();
+        type Part1 = u8;
+        type Part2 = u8;
+
+        fn parse(_: &str) -> Result<Self::Input, Error> {
+            Ok(())
+        }
+
+        fn part1(_: &Self::Input) -> Result<Self::Part1, Error> {
+            panic!("part {} panicked", 1)
+        }
+
+        fn part2(_: &Self::Input) -> Result<Self::Part2, Error> {
+            Ok(2)
+        }
+    }
+
+    fn jobs<'a>(solver: &'a dyn Solver, input: &'a str) -> Vec<Job<'a>> {
+        Part::ALL
+            .iter()
+            .map(|part| {
+                Job {
+                    solver,
+                    part: *part,
+                    input,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    #[cfg(all(feature = "day2", feature = "day6"))]
+    fn runs_all_parts() {
+        let day2 = crate::solution::solver(2).unwrap();
+        let day6 = crate::solution::solver(6).unwrap();
+        let mut jobs = jobs(day6.as_ref(), "abcabc");
+        jobs.extend(self::jobs(day2.as_ref(), "A Y\nB X\nC Z"));
+
+        let report = Report::run(&jobs, Some(2)).unwrap();
+
+        let summary = report
+            .parts
+            .iter()
+            .map(|part| (part.day, part.part.number(), part.outcome.status()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            [
+                (2, 1, "solved"),
+                (2, 2, "solved"),
+                (6, 1, "failed"),
+                (6, 2, "failed")
+            ]
+        );
+        assert!(matches!(
+            &report.parts[1].outcome,
+            Outcome::Solved { answer, .. } if answer == "12"
+        ));
+        assert!(!report.all_solved());
+    }
+
+    #[test]
+    fn isolates_panics() {
+        let report = Report::run(&jobs(&Panics, ""), None).unwrap();
+
+        assert_eq!(
+            report.parts[0].outcome,
+            Outcome::Panicked {
+                error: "part 1 panicked".to_owned()
+            }
+        );
+        assert!(report.parts[1].is_solved());
+    }
+
+    /// a solved part with a multi-line answer, and a part without an input.
+    fn example_report() -> Report {
+        let mut report = Report {
+            elapsed: Duration::from_millis(12),
+            parts: vec![PartReport {
+                day: 10,
+                part: Part::Part2,
+                input_hash: Some(input::hash("noop")),
+                outcome: Outcome::Solved {
+                    answer: "\n##..\n..##\n".to_owned(),
+                    parse_time: Duration::from_micros(5),
+                    solve_time: Duration::from_micros(20),
+                    parse_allocs: None,
+                    solve_allocs: None,
+                },
+            }],
+        };
+        report.add_failure(3, Part::Part1, "no <input>".to_owned());
+        report
+    }
+
+    #[test]
+    fn table() {
+        let report = example_report();
+
+        assert_eq!(
+            report.to_string(),
+            "\
+day  part  status         parse       solve  answer
+  3     1  failed             -           -  no <input>
+ 10     2  solved        5.00µs     20.00µs  ##..
+                                             ..##
+2 parts in 12.00ms: 1 solved, 1 failed, 0 panicked"
+        );
+    }
+
+    #[test]
+    fn alloc_columns() {
+        let mut report = example_report();
+        let allocs = AllocStats {
+            allocations: 3,
+            bytes: 4096,
+            peak_bytes: 1536,
+        };
+        if let Outcome::Solved {
+            parse_allocs,
+            solve_allocs,
+            ..
+        } = &mut report.parts[1].outcome
+        {
+            *parse_allocs = Some(allocs);
+            *solve_allocs = Some(AllocStats::default());
+        }
+
+        assert_eq!(
+            report.to_string(),
+            "\
+day  part  status         parse       solve  parse allocs   parse bytes    parse peak  solve allocs   solve bytes    solve peak  answer
+  3     1  failed             -           -             -             -             -             -             -             -  no <input>
+ 10     2  solved        5.00µs     20.00µs             3       4.00KiB       1.50KiB             0            0B            0B  ##..
+                                                                                                                                 ..##
+2 parts in 12.00ms: 1 solved, 1 failed, 0 panicked"
+        );
+
+        assert!(report.markdown().contains(
+            "| 10 | 2 | solved | `##..`<br>`..##` | 5.00µs | 20.00µs | 3 | 4.00KiB | 1.50KiB | 0 | 0B | 0B |"
+        ));
+        assert!(report
+            .junit()
+            .contains("parse memory: 3 allocations, 4.00KiB allocated, 1.50KiB peak"));
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["parts"][1]["parse_memory"]["peak_bytes"], 1536);
+        assert!(json["parts"][0].get("parse_memory").is_none());
+    }
+
+    #[test]
+    fn json() {
+        let report = Report::run(&jobs(&Panics, ""), Some(1)).unwrap();
+        let json = serde_json::to_value(&report).unwrap();
+
+        assert_eq!(json["parts"][0]["day"], 99);
+        assert_eq!(json["parts"][0]["part"], 1);
+        assert_eq!(json["parts"][0]["status"], "panicked");
+        assert_eq!(json["parts"][0]["error"], "part 1 panicked");
+        assert_eq!(json["parts"][0]["input_hash"], input::hash(""));
+        assert_eq!(json["parts"][1]["status"], "solved");
+        assert_eq!(json["parts"][1]["answer"], "2");
+        assert!(json["parts"][1]["solve_seconds"].is_f64());
+    }
+
+    #[test]
+    fn markdown() {
+        assert_eq!(
+            example_report().markdown(),
+            "\
+| day | part | status | answer | parse | solve | input |
+|----:|-----:|--------|--------|------:|------:|-------|
+| 3 | 1 | failed | `no <input>` | - | - | - |
+| 10 | 2 | solved | `##..`<br>`..##` | 5.00µs | 20.00µs | `f4d960247b7a` |
+
+2 parts in 12.00ms: 1 solved, 1 failed, 0 panicked
+"
+        );
+    }
+
+    #[test]
+    fn junit() {
+        let xml = example_report().junit();
+
+        assert!(xml.starts_with("<?xml"));
+        assert!(xml.contains(
+            "<testsuite name=\"aoc2022\" tests=\"2\" failures=\"1\" errors=\"0\" time=\"0.012000\">"
+        ));
+        assert!(xml.contains(
+            "<testcase classname=\"aoc2022.day3\" name=\"day 3 - part 1\" time=\"0.000000\">\n    \
+             <failure message=\"no &lt;input&gt;\" type=\"failed\">no &lt;input&gt;</failure>"
+        ));
+        assert!(xml.contains(&format!(
+            "<system-out>answer: ##..\n..##\ninput hash: {}</system-out>",
+            input::hash("noop")
+        )));
+        assert!(xml.ends_with("</testsuite>\n"));
+    }
+}
@@ -0,0 +1,362 @@
+//! examples from the puzzle text, as test fixtures.
+//!
+//! the examples are extracted from a locally saved puzzle page: an example
+//! input is a `<pre><code>` block right after a paragraph that introduces an
+//! example and ends with a colon, like "For example:" or "Here's a larger
+//! example:". the answer of a part is the last emphasized code
+//! (`<code><em>13</em></code>`) after an example in the text of that part.
+//! part 2 often starts with the example of part 1, so its answers belong to
+//! the first example until it introduces a new one.
+//!
+//! fixtures are stored in `fixtures/day<N>.toml`, with a table per example:
+//!
+//! ```toml
+//! [[example]]
+//! input = """
+//! R 4
+//! U 4"""
+//! part1 = "13"
+//! part2 = "1"
+//! ```
+//!
+//! every fixture is checked against the solver of its day by the tests of
+//! this module. answers that only apply to the example, e.g. because day 15
+//! checks a different row for it, can be excluded with `skip = "<reason>"`.
+
+use std::{
+    fs,
+    io,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use thiserror::Error;
+
+use crate::runner::Part;
+
+lazy_static! {
+    static ref ARTICLE_REGEX: Regex = r#"(?s)<article class="day-desc">(.*?)</article>"#
+        .parse()
+        .unwrap();
+    static ref TOKEN_REGEX: Regex =
+        r"(?s)<pre><code>(.*?)</code></pre>|<code><em>(.*?)</em></code>|<em><code>(.*?)</code></em>"
+            .parse()
+            .unwrap();
+    static ref PARAGRAPH_REGEX: Regex = r"(?s)<p>(.*?)</p>".parse().unwrap();
+    static ref TAG_REGEX: Regex = r"<[^>]*>".parse().unwrap();
+}
+
+#[derive(Debug, Error)]
+pub enum FixtureError {
+    #[error("io error")]
+    Io(#[from] io::Error),
+    #[error("failed to parse fixture")]
+    Parse(#[from] toml::de::Error),
+    #[error("failed to serialize fixture")]
+    Serialize(#[from] toml::ser::Error),
+}
+
+/// an example input, with the answers given for it.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Example {
+    pub input: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub part1: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub part2: Option<String>,
+    /// why the answers can't be checked against the solver.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skip: Option<String>,
+}
+
+impl Example {
+    pub fn answer(&self, part: Part) -> Option<&str> {
+        match part {
+            Part::Part1 => self.part1.as_deref(),
+            Part::Part2 => self.part2.as_deref(),
+        }
+    }
+
+    fn answer_mut(&mut self, part: Part) -> &mut Option<String> {
+        match part {
+            Part::Part1 => &mut self.part1,
+            Part::Part2 => &mut self.part2,
+        }
+    }
+}
+
+/// the examples of a day.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fixture {
+    #[serde(rename = "example", default)]
+    pub examples: Vec<Example>,
+}
+
+impl Fixture {
+    /// extracts the examples from a saved puzzle page. the page only has the
+    /// text of part 2 once part 1 is solved.
+    pub fn extract(html: &str) -> Self {
+        let mut examples: Vec<Example> = vec![];
+
+        for (article, part) in ARTICLE_REGEX.captures_iter(html).zip(Part::ALL) {
+            let article = article.get(1).unwrap().as_str();
+            let mut example = if examples.is_empty() { None } else { Some(0) };
+            let mut previous_end = 0;
+
+            for token in TOKEN_REGEX.captures_iter(article) {
+                let whole = token.get(0).unwrap();
+                let before = &article[previous_end..whole.start()];
+                previous_end = whole.end();
+
+                if let Some(block) = token.get(1) {
+                    if introduces_example(before) {
+                        examples.push(Example {
+                            input: text(block.as_str()).trim_end_matches('\n').to_owned(),
+                            ..Default::default()
+                        });
+                        example = Some(examples.len() - 1);
+                    }
+                }
+                else if let Some(i) = example {
+                    let answer = token.get(2).or_else(|| token.get(3)).unwrap();
+                    *examples[i].answer_mut(part) = Some(text(answer.as_str()));
+                }
+            }
+        }
+
+        Self { examples }
+    }
+
+    /// the path of the fixture of `day` in `dir`.
+    pub fn path(dir: &Path, day: u8) -> PathBuf {
+        dir.join(format!("day{}.toml", day))
+    }
+
+    pub fn load(path: &Path) -> Result<Self, FixtureError> {
+        Ok(toml::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), FixtureError> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, toml::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// checks whether the last paragraph of `html` introduces an example.
+fn introduces_example(html: &str) -> bool {
+    PARAGRAPH_REGEX
+        .captures_iter(html)
+        .last()
+        .map(|paragraph| text(paragraph.get(1).unwrap().as_str()))
+        .is_some_and(|paragraph| {
+            paragraph.to_lowercase().contains("example") && paragraph.trim_end().ends_with(':')
+        })
+}
+
+/// strips the tags from `html` and decodes its entities.
+fn text(html: &str) -> String {
+    let text = TAG_REGEX.replace_all(html, "");
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = &*text;
+
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let entity = rest
+            .find(';')
+            .and_then(|end| Some((decode_entity(&rest[1..end])?, end)));
+        match entity {
+            Some((c, end)) => {
+                decoded.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+
+    decoded
+}
+
+/// decodes the name of an entity without `&` and `;`, e.g. `lt` or `#39`.
+fn decode_entity(name: &str) -> Option<char> {
+    match name {
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "amp" => Some('&'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        _ => {
+            let number = name.strip_prefix('#')?;
+            let code = match number.strip_prefix('x') {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => number.parse().ok()?,
+            };
+            char::from_u32(code)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution;
+
+    /// the structure of a saved page, with a second example in part 2 and
+    /// intermediate states that aren't examples.
+    const PAGE: &str = r#"<!DOCTYPE html>
+<html lang="en-us">
+<head><title>Day 9 - Advent of Code 2022</title></head>
+<body>
+<main>
+<article class="day-desc"><h2>--- Day 9: Rope Bridge ---</h2>
+<p>Consider a rope with a knot at each end.</p>
+<p>For example:</p>
+<pre><code>R 4
+U 4
+L 3
+</code></pre>
+<p>This series of motions results in the following steps:</p>
+<pre><code>== R 4 ==
+
+......
+<em>H</em>T....
+</code></pre>
+<p>The tail visits <code><em>7</em></code> positions, not <code>3</code> or <em>8</em>.</p>
+</article>
+<p>Your puzzle answer was <code>6271</code>.</p>
+<article class="day-desc"><h2 id="part2">--- Part Two ---</h2>
+<p>In the example above, the tail only visits <code><em>1</em></code> position.</p>
+<p>Here's a larger example with &lt;arrows&gt; &amp; &#39;quotes&#39;:</p>
+<pre><code>R 5
+U 8 -&gt; &lt;
+</code></pre>
+<p>Now, the tail visits <em><code>36</code></em> positions.</p>
+</article>
+</main>
+</body>
+</html>"#;
+
+    #[test]
+    fn extract() {
+        assert_eq!(
+            Fixture::extract(PAGE).examples,
+            [
+                Example {
+                    input: "R 4\nU 4\nL 3".to_owned(),
+                    part1: Some("7".to_owned()),
+                    part2: Some("1".to_owned()),
+                    skip: None,
+                },
+                Example {
+                    input: "R 5\nU 8 -> <".to_owned(),
+                    part1: None,
+                    part2: Some("36".to_owned()),
+                    skip: None,
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn extract_part1_only() {
+        let page = PAGE
+            .split("<article class=\"day-desc\"><h2 id=\"part2\">")
+            .next()
+            .unwrap();
+        let examples = Fixture::extract(page).examples;
+        assert_eq!(examples.len(), 1);
+        assert_eq!(examples[0].answer(Part::Part2), None);
+    }
+
+    #[test]
+    fn entities() {
+        assert_eq!(
+            text("a &lt;b&gt; &amp; &#39;c&#x27; &copy; &"),
+            "a <b> & 'c' &copy; &"
+        );
+    }
+
+    #[test]
+    fn toml_roundtrip() {
+        let fixture = Fixture::extract(PAGE);
+        let toml = toml::to_string_pretty(&fixture).unwrap();
+        assert!(toml.starts_with("[[example]]\n"));
+        assert_eq!(toml::from_str::<Fixture>(&toml).unwrap(), fixture);
+    }
+
+    /// the harness: every fixture in `fixtures/` has the answers of the
+    /// solver of its day. days that are disabled are skipped, and so are
+    /// examples with a `skip` reason, e.g. of days that aren't solved yet.
+    #[test]
+    fn fixtures() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures");
+        let mut failures = vec![];
+
+        for entry in fs::read_dir(&dir).unwrap() {
+            let path = entry.unwrap().path();
+            let Some(day) = path
+                .file_stem()
+                .and_then(|stem| stem.to_str()?.strip_prefix("day")?.parse().ok())
+            else {
+                continue;
+            };
+            let Ok(solver) = solution::solver(day)
+            else {
+                continue;
+            };
+            let fixture = Fixture::load(&path).unwrap();
+
+            for (i, example) in fixture.examples.iter().enumerate() {
+                if example.skip.is_some() {
+                    continue;
+                }
+                for part in Part::ALL {
+                    let Some(expected) = example.answer(part)
+                    else {
+                        continue;
+                    };
+                    match solver.run(&example.input, &[part]) {
+                        Ok(day_run) if day_run.parts[0].answer == expected => {}
+                        Ok(day_run) => {
+                            failures.push(format!(
+                                "day {} example {} {}: expected {}, got {}",
+                                day,
+                                i + 1,
+                                part,
+                                expected,
+                                day_run.parts[0].answer
+                            ))
+                        }
+                        Err(e) => {
+                            failures.push(format!(
+                                "day {} example {} {}: {}",
+                                day,
+                                i + 1,
+                                part,
+                                crate::error::error_chain(&e)
+                            ))
+                        }
+                    }
+                }
+            }
+        }
+
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+    }
+}
@@ -0,0 +1,843 @@
+//! deterministic random puzzle inputs.
+//!
+//! every day has a [`Generator`] that produces a valid input from a seed and
+//! a size. the same seed and size always produce the same input, so a
+//! generated input that breaks a solver can be reproduced from just these two
+//! numbers.
+//!
+//! like the inputs cargo-aoc passes to the generators, the generated inputs
+//! don't end with a newline.
+
+use std::{
+    cmp::Ordering,
+    fmt,
+    ops::RangeInclusive,
+};
+
+use itertools::Itertools;
+use rand::{
+    seq::SliceRandom,
+    Rng,
+    SeedableRng,
+};
+use rand_chacha::ChaCha8Rng;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum GenerateError {
+    #[error("no generator for day {0}")]
+    UnknownDay(u8),
+    #[error("size {size} is out of range for day {day}, expected {} to {}", .sizes.start(), .sizes.end())]
+    InvalidSize {
+        day: u8,
+        size: usize,
+        sizes: RangeInclusive<usize>,
+    },
+}
+
+/// generates inputs for a single day.
+pub struct Generator {
+    pub day: u8,
+
+    /// what the size counts, e.g. "elves" for day 1.
+    pub unit: &'static str,
+
+    /// size of a typical puzzle input.
+    pub default_size: usize,
+
+    /// sizes for which valid inputs can be generated.
+    pub sizes: RangeInclusive<usize>,
+
+    generate: fn(&mut ChaCha8Rng, usize) -> String,
+}
+
+impl Generator {
+    pub fn generate(&self, seed: u64, size: usize) -> Result<String, GenerateError> {
+        if !self.sizes.contains(&size) {
+            return Err(GenerateError::InvalidSize {
+                day: self.day,
+                size,
+                sizes: self.sizes.clone(),
+            });
+        }
+
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        Ok((self.generate)(&mut rng, size))
+    }
+}
+
+const GENERATORS: [Generator; 15] = [
+    Generator {
+        day: 1,
+        unit: "elves",
+        default_size: 250,
+        // part 2 needs the top three elves
+        sizes: 3..=1_000_000,
+        generate: day1,
+    },
+    Generator {
+        day: 2,
+        unit: "rounds",
+        default_size: 2500,
+        sizes: 1..=10_000_000,
+        generate: day2,
+    },
+    Generator {
+        day: 3,
+        unit: "groups of rucksacks",
+        default_size: 100,
+        sizes: 1..=1_000_000,
+        generate: day3,
+    },
+    Generator {
+        day: 4,
+        unit: "pairs",
+        default_size: 1000,
+        sizes: 1..=10_000_000,
+        generate: day4,
+    },
+    Generator {
+        day: 5,
+        unit: "moves",
+        default_size: 500,
+        // the empty line between crates and moves would be trimmed without
+        // any moves.
+        sizes: 1..=1_000_000,
+        generate: day5,
+    },
+    Generator {
+        day: 6,
+        unit: "characters before the markers",
+        default_size: 4000,
+        sizes: 0..=100_000_000,
+        generate: day6,
+    },
+    Generator {
+        day: 7,
+        unit: "directories",
+        default_size: 200,
+        sizes: 1..=100_000,
+        generate: day7,
+    },
+    Generator {
+        day: 8,
+        unit: "trees per side",
+        default_size: 99,
+        sizes: 1..=5000,
+        generate: day8,
+    },
+    Generator {
+        day: 9,
+        unit: "movements",
+        default_size: 2000,
+        sizes: 1..=1_000_000,
+        generate: day9,
+    },
+    Generator {
+        day: 10,
+        unit: "instructions",
+        default_size: 140,
+        sizes: 1..=10_000_000,
+        generate: day10,
+    },
+    Generator {
+        day: 11,
+        unit: "monkeys",
+        default_size: 8,
+        // monkey ids are a single byte, and monkey 0 doesn't get any items.
+        sizes: 3..=256,
+        generate: day11,
+    },
+    Generator {
+        day: 12,
+        unit: "columns",
+        default_size: 160,
+        // every column is one step up, and there have to be 26 of them to
+        // get from `a` to `z`.
+        sizes: 26..=10_000,
+        generate: day12,
+    },
+    Generator {
+        day: 13,
+        unit: "packet pairs",
+        default_size: 150,
+        sizes: 1..=1_000_000,
+        generate: day13,
+    },
+    Generator {
+        day: 14,
+        unit: "rock paths",
+        default_size: 150,
+        sizes: 1..=10_000,
+        generate: day14,
+    },
+    Generator {
+        day: 15,
+        unit: "sensors",
+        default_size: 26,
+        // it takes 4 sensors to cover everything but the distress signal
+        sizes: 4..=10_000,
+        generate: day15,
+    },
+];
+
+/// returns the generators for all days.
+pub fn generators() -> &'static [Generator] {
+    &GENERATORS
+}
+
+/// returns the generator for a specific day.
+pub fn generator(day: u8) -> Result<&'static Generator, GenerateError> {
+    GENERATORS
+        .iter()
+        .find(|generator| generator.day == day)
+        .ok_or(GenerateError::UnknownDay(day))
+}
+
+/// generates an input for `day`. uses the day's default size if `size` is
+/// `None`.
+pub fn generate(day: u8, seed: u64, size: Option<usize>) -> Result<String, GenerateError> {
+    let generator = generator(day)?;
+    generator.generate(seed, size.unwrap_or(generator.default_size))
+}
+
+const LOWERCASE: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+
+fn random_name(rng: &mut ChaCha8Rng, max_length: usize) -> String {
+    let length = rng.gen_range(1..=max_length);
+    (0..length)
+        .map(|_| *LOWERCASE.choose(rng).unwrap() as char)
+        .collect()
+}
+
+/// returns a random number in `-max..=max` that isn't 0.
+fn random_nonzero(rng: &mut ChaCha8Rng, max: i32) -> i32 {
+    let n = rng.gen_range(1..=max);
+    if rng.gen() {
+        n
+    }
+    else {
+        -n
+    }
+}
+
+fn day1(rng: &mut ChaCha8Rng, elves: usize) -> String {
+    (0..elves)
+        .map(|_| {
+            let num_items = rng.gen_range(1..=15);
+            (0..num_items)
+                .map(|_| rng.gen_range(1000..=60000))
+                .join("\n")
+        })
+        .join("\n\n")
+}
+
+fn day2(rng: &mut ChaCha8Rng, rounds: usize) -> String {
+    (0..rounds)
+        .map(|_| {
+            format!(
+                "{} {}",
+                (b'A' + rng.gen_range(0..3)) as char,
+                (b'X' + rng.gen_range(0..3)) as char
+            )
+        })
+        .join("\n")
+}
+
+fn day3(rng: &mut ChaCha8Rng, groups: usize) -> String {
+    const ITEMS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+    let mut rucksacks = vec![];
+
+    for _ in 0..groups {
+        let mut items = ITEMS.to_vec();
+        items.shuffle(rng);
+
+        // apart from the badge every rucksack of a group uses its own items,
+        // so the badge is the only item they all have in common.
+        let badge = items[0];
+        for pool in items[1..].chunks(17) {
+            let mut items = pool.to_vec();
+            items.push(badge);
+
+            // and the compartments only have `in_both` in common.
+            let in_both = *items.choose(rng).unwrap();
+            items.retain(|item| *item != in_both);
+            items.shuffle(rng);
+            let (first_items, second_items) = items.split_at(items.len() / 2);
+
+            let length = rng.gen_range(8..=16);
+            let mut first = vec![in_both];
+            first.extend((1..length).map(|_| *first_items.choose(rng).unwrap()));
+            let mut second = vec![in_both];
+            second.extend((1..length).map(|_| *second_items.choose(rng).unwrap()));
+
+            if in_both != badge {
+                if first_items.contains(&badge) {
+                    first[1] = badge;
+                }
+                else {
+                    second[1] = badge;
+                }
+            }
+
+            first.shuffle(rng);
+            second.shuffle(rng);
+            first.append(&mut second);
+            rucksacks.push(String::from_utf8(first).unwrap());
+        }
+    }
+
+    rucksacks.join("\n")
+}
+
+fn day4(rng: &mut ChaCha8Rng, pairs: usize) -> String {
+    let assignment = |rng: &mut ChaCha8Rng| {
+        let a = rng.gen_range(1..=99);
+        let b = rng.gen_range(1..=99);
+        format!("{}-{}", a.min(b), a.max(b))
+    };
+
+    (0..pairs)
+        .map(|_| format!("{},{}", assignment(rng), assignment(rng)))
+        .join("\n")
+}
+
+fn day5(rng: &mut ChaCha8Rng, moves: usize) -> String {
+    const NUM_STACKS: usize = 9;
+
+    // every stack starts with at least one crate, so all of them show up in the
+    // drawing.
+    let num_crates = 2 * NUM_STACKS + moves / 10;
+    let mut stacks = vec![vec![]; NUM_STACKS];
+    for i in 0..num_crates {
+        let stack = if i < NUM_STACKS {
+            i
+        }
+        else {
+            rng.gen_range(0..NUM_STACKS)
+        };
+        stacks[stack].push(rng.gen_range(b'A'..=b'Z') as char);
+    }
+
+    let height = stacks.iter().map(Vec::len).max().unwrap();
+    let mut lines = vec![];
+    for level in (0..height).rev() {
+        let line = stacks
+            .iter()
+            .map(|stack| {
+                stack
+                    .get(level)
+                    .map(|crate_id| format!("[{}]", crate_id))
+                    .unwrap_or_else(|| "   ".to_owned())
+            })
+            .join(" ");
+        lines.push(line.trim_end().to_owned());
+    }
+    lines.push(
+        (1..=NUM_STACKS)
+            .map(|i| format!(" {} ", i))
+            .join(" ")
+            .trim_end()
+            .to_owned(),
+    );
+    lines.push(String::new());
+
+    // both crane models move the same number of crates, so it's enough to
+    // track the stack sizes to only generate moves that are possible. no stack
+    // is ever emptied, so that the answers have a crate for every stack.
+    let mut sizes = stacks.iter().map(Vec::len).collect::<Vec<_>>();
+    for _ in 0..moves {
+        let sources = (0..NUM_STACKS)
+            .filter(|i| sizes[*i] > 1)
+            .collect::<Vec<_>>();
+        let from = *sources.choose(rng).unwrap();
+        let to = (from + rng.gen_range(1..NUM_STACKS)) % NUM_STACKS;
+        let count = rng.gen_range(1..sizes[from].min(31));
+        sizes[from] -= count;
+        sizes[to] += count;
+        lines.push(format!("move {} from {} to {}", count, from + 1, to + 1));
+    }
+
+    lines.join("\n")
+}
+
+fn day6(rng: &mut ChaCha8Rng, length: usize) -> String {
+    // with only 3 different letters there can't be a start-of-packet marker,
+    // and with only 13 there can't be a start-of-message marker.
+    let prefix = length / 3;
+    let mut signal = (0..prefix)
+        .map(|_| *b"abc".choose(rng).unwrap())
+        .collect::<Vec<_>>();
+    signal.extend((prefix..length).map(|_| *b"abcdefghijklm".choose(rng).unwrap()));
+
+    let mut marker = LOWERCASE.to_vec();
+    marker.shuffle(rng);
+    signal.extend_from_slice(&marker[..14]);
+    signal.extend((0..100).map(|_| *LOWERCASE.choose(rng).unwrap()));
+
+    String::from_utf8(signal).unwrap()
+}
+
+fn day7(rng: &mut ChaCha8Rng, num_directories: usize) -> String {
+    struct Directory {
+        name: String,
+        children: Vec<usize>,
+        files: Vec<(u64, String)>,
+    }
+
+    fn visit(
+        rng: &mut ChaCha8Rng,
+        directories: &[Directory],
+        directory: usize,
+        lines: &mut Vec<String>,
+    ) {
+        let directory = &directories[directory];
+        let mut entries = directory
+            .children
+            .iter()
+            .map(|child| format!("dir {}", directories[*child].name))
+            .chain(
+                directory
+                    .files
+                    .iter()
+                    .map(|(size, name)| format!("{} {}", size, name)),
+            )
+            .collect::<Vec<_>>();
+        entries.shuffle(rng);
+
+        lines.push("$ ls".to_owned());
+        lines.append(&mut entries);
+
+        for child in &directory.children {
+            lines.push(format!("$ cd {}", directories[*child].name));
+            visit(rng, directories, *child, lines);
+            lines.push("$ cd ..".to_owned());
+        }
+    }
+
+    // names have to be unique within a directory, which is easiest with a
+    // different suffix for every entry.
+    let mut num_names = 0;
+    let mut unique_name = |rng: &mut ChaCha8Rng| {
+        num_names += 1;
+        format!("{}{}", random_name(rng, 6), num_names)
+    };
+
+    let mut directories = vec![Directory {
+        name: "/".to_owned(),
+        children: vec![],
+        files: vec![],
+    }];
+    for i in 1..num_directories {
+        let parent = rng.gen_range(0..i);
+        directories[parent].children.push(i);
+        directories.push(Directory {
+            name: unique_name(rng),
+            children: vec![],
+            files: vec![],
+        });
+    }
+
+    // part 2 needs a total size between the 40000000 that can be used and the
+    // 70000000 of the disk.
+    let num_files = num_directories * 3 / 2 + 1;
+    let total_size = rng.gen_range(41_000_000..=69_000_000) as f64;
+    let weights = (0..num_files).map(|_| rng.gen::<f64>()).collect::<Vec<_>>();
+    let scale = total_size / weights.iter().sum::<f64>();
+    for weight in weights {
+        let extension = *["", ".txt", ".dat", ".log", ".lst"].choose(rng).unwrap();
+        let name = format!("{}{}", unique_name(rng), extension);
+        let size = ((weight * scale) as u64).max(1);
+        let directory = rng.gen_range(0..num_directories);
+        directories[directory].files.push((size, name));
+    }
+
+    let mut lines = vec!["$ cd /".to_owned()];
+    visit(rng, &directories, 0, &mut lines);
+
+    lines.join("\n")
+}
+
+fn day8(rng: &mut ChaCha8Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            (0..size)
+                .map(|_| (rng.gen_range(0..=9) + rng.gen_range(0..=9)) / 2)
+                .join("")
+        })
+        .join("\n")
+}
+
+fn day9(rng: &mut ChaCha8Rng, movements: usize) -> String {
+    (0..movements)
+        .map(|_| {
+            format!(
+                "{} {}",
+                *["U", "D", "L", "R"].choose(rng).unwrap(),
+                rng.gen_range(1..=20)
+            )
+        })
+        .join("\n")
+}
+
+fn day10(rng: &mut ChaCha8Rng, instructions: usize) -> String {
+    let mut x = 1;
+
+    (0..instructions)
+        .map(|_| {
+            if rng.gen_bool(0.35) {
+                "noop".to_owned()
+            }
+            else {
+                // keep the sprite around the screen
+                let mut v = rng.gen_range(-12..=12);
+                if !(-5..=45).contains(&(x + v)) {
+                    v = -v;
+                }
+                x += v;
+                format!("addx {}", v)
+            }
+        })
+        .join("\n")
+}
+
+fn day11(rng: &mut ChaCha8Rng, num_monkeys: usize) -> String {
+    // distinct primes are coprime, just like in the real inputs.
+    let mut primes = (2..)
+        .filter(|n: &u64| {
+            (2..*n)
+                .take_while(|d| d * d <= *n)
+                .all(|d| !n.is_multiple_of(d))
+        })
+        .take(num_monkeys)
+        .collect::<Vec<_>>();
+    primes.shuffle(rng);
+
+    (0..num_monkeys)
+        .map(|i| {
+            let num_items = rng.gen_range(1..=8);
+            let items = (0..num_items).map(|_| rng.gen_range(50..=99)).join(", ");
+
+            let operation = if i == 0 {
+                "old * old".to_owned()
+            }
+            else if rng.gen() {
+                format!("old * {}", rng.gen_range(2..=19))
+            }
+            else {
+                format!("old + {}", rng.gen_range(1..=8))
+            };
+
+            // in part 1 worry levels are only divided by 3, so items that keep
+            // coming back to monkey 0 would grow without bounds.
+            let others = (1..num_monkeys).filter(|j| *j != i).collect::<Vec<_>>();
+            let mut targets = others.choose_multiple(rng, 2);
+            let if_true = *targets.next().unwrap();
+            let if_false = targets.next().copied().unwrap_or(if_true);
+
+            format!(
+                "Monkey {}:
+  Starting items: {}
+  Operation: new = {}
+  Test: divisible by {}
+    If true: throw to monkey {}
+    If false: throw to monkey {}",
+                i, items, operation, primes[i], if_true, if_false
+            )
+        })
+        .join("\n\n")
+}
+
+fn day12(rng: &mut ChaCha8Rng, width: usize) -> String {
+    // the elevation rises from left to right. the middle row climbs one step
+    // at a time, so there is always a path from S to E along it.
+    let height = width / 4 + 1;
+    let middle = height / 2;
+
+    (0..height)
+        .map(|y| {
+            (0..width)
+                .map(|x| {
+                    if y == middle && x == 0 {
+                        return 'S';
+                    }
+                    if y == middle && x == width - 1 {
+                        return 'E';
+                    }
+
+                    let mut elevation = (x * 26 / width).min(25) as u8;
+                    if y != middle {
+                        elevation =
+                            elevation.saturating_sub(*[0, 0, 0, 1, 2, 3].choose(rng).unwrap());
+                    }
+                    (b'a' + elevation) as char
+                })
+                .collect::<String>()
+        })
+        .join("\n")
+}
+
+fn day13(rng: &mut ChaCha8Rng, pairs: usize) -> String {
+    enum Packet {
+        Integer(u32),
+        List(Vec<Packet>),
+    }
+
+    impl Packet {
+        fn random(rng: &mut ChaCha8Rng, depth: usize) -> Self {
+            let length = rng.gen_range(0..=5);
+            Packet::List(
+                (0..length)
+                    .map(|_| {
+                        if depth < 4 && rng.gen_bool(0.3) {
+                            Packet::random(rng, depth + 1)
+                        }
+                        else {
+                            Packet::Integer(rng.gen_range(0..=10))
+                        }
+                    })
+                    .collect(),
+            )
+        }
+
+        fn compare(&self, other: &Self) -> Ordering {
+            match (self, other) {
+                (Packet::Integer(a), Packet::Integer(b)) => a.cmp(b),
+                (Packet::List(a), Packet::List(b)) => {
+                    a.iter()
+                        .zip(b)
+                        .map(|(a, b)| a.compare(b))
+                        .find(|ordering| ordering.is_ne())
+                        .unwrap_or_else(|| a.len().cmp(&b.len()))
+                }
+                (Packet::Integer(a), list) => Packet::List(vec![Packet::Integer(*a)]).compare(list),
+                (list, Packet::Integer(b)) => {
+                    list.compare(&Packet::List(vec![Packet::Integer(*b)]))
+                }
+            }
+        }
+    }
+
+    impl fmt::Display for Packet {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Packet::Integer(n) => write!(f, "{}", n),
+                Packet::List(items) => write!(f, "[{}]", items.iter().join(",")),
+            }
+        }
+    }
+
+    (0..pairs)
+        .map(|_| {
+            // equal packets are neither in the right nor in the wrong order.
+            loop {
+                let left = Packet::random(rng, 0);
+                let right = Packet::random(rng, 0);
+                if left.compare(&right).is_ne() {
+                    break format!("{}\n{}", left, right);
+                }
+            }
+        })
+        .join("\n\n")
+}
+
+fn day14(rng: &mut ChaCha8Rng, num_paths: usize) -> String {
+    // the cave gets deeper with more paths, but slower than the number of
+    // paths, so that the amount of sand in part 2 doesn't explode.
+    const MIN_Y: i32 = 2;
+    let max_y = 20 + 12 * (num_paths as f64).sqrt() as i32;
+
+    (0..num_paths)
+        .map(|_| {
+            let mut x = rng.gen_range(500 - max_y / 2..=500 + max_y / 2);
+            let mut y = rng.gen_range(MIN_Y..=max_y);
+            let mut points = vec![(x, y)];
+            let mut horizontal = rng.gen();
+
+            for _ in 0..rng.gen_range(1..=5) {
+                if horizontal {
+                    x += random_nonzero(rng, 8);
+                }
+                else {
+                    let next_y = (y + random_nonzero(rng, 8)).clamp(MIN_Y, max_y);
+                    y = match next_y.cmp(&y) {
+                        Ordering::Equal if y < max_y => y + 1,
+                        Ordering::Equal => y - 1,
+                        _ => next_y,
+                    };
+                }
+                points.push((x, y));
+                horizontal = !horizontal;
+            }
+
+            points
+                .iter()
+                .map(|(x, y)| format!("{},{}", x, y))
+                .join(" -> ")
+        })
+        .join("\n")
+}
+
+fn day15(rng: &mut ChaCha8Rng, num_sensors: usize) -> String {
+    day15_with_search_area(rng, num_sensors, 4_000_000)
+}
+
+/// generates sensors that cover all of `0..=search_area` in both coordinates
+/// except for a single point.
+fn day15_with_search_area(rng: &mut ChaCha8Rng, num_sensors: usize, search_area: i64) -> String {
+    let distress_signal = (rng.gen_range(1..search_area), rng.gen_range(1..search_area));
+    let mut sensors = vec![];
+
+    // the area covered by a sensor is a square in rotated coordinates
+    // (u = x + y, v = x - y). four huge squares to the left, right, top and
+    // bottom of the distress signal cover everything else.
+    let u = distress_signal.0 + distress_signal.1;
+    let v = distress_signal.0 - distress_signal.1;
+    let mut add_square = |edge_u: i64, edge_v: i64, direction_u: i64, direction_v: i64| {
+        // the center has to be at integer coordinates, so u and v of the
+        // center have to be both even or both odd.
+        let mut radius = 4 * search_area + 1;
+        if (edge_u + direction_u * radius - edge_v - direction_v * radius) % 2 != 0 {
+            radius += 1;
+        }
+        let center_u = edge_u + direction_u * radius;
+        let center_v = edge_v + direction_v * radius;
+        let position = ((center_u + center_v) / 2, (center_u - center_v) / 2);
+        sensors.push((position, (position.0 + radius, position.1)));
+    };
+    add_square(u - 1, v, -1, 0);
+    add_square(u + 1, v, 1, 0);
+    add_square(u, v - 1, 0, -1);
+    add_square(u, v + 1, 0, 1);
+
+    // the other sensors are random, but stop short of the distress signal.
+    while sensors.len() < num_sensors {
+        let position = (
+            rng.gen_range(0..=search_area),
+            rng.gen_range(0..=search_area),
+        );
+        let distance =
+            (position.0 - distress_signal.0).abs() + (position.1 - distress_signal.1).abs();
+        if distance < 2 {
+            continue;
+        }
+
+        let radius = rng.gen_range(1..=(distance - 1).min(search_area * 3 / 8).max(1));
+        let dx = rng.gen_range(-radius..=radius);
+        let dy = (radius - dx.abs()) * if rng.gen() { 1 } else { -1 };
+        sensors.push((position, (position.0 + dx, position.1 + dy)));
+    }
+
+    sensors.shuffle(rng);
+    sensors
+        .iter()
+        .map(|(sensor, beacon)| {
+            format!(
+                "Sensor at x={}, y={}: closest beacon is at x={}, y={}",
+                sensor.0, sensor.1, beacon.0, beacon.1
+            )
+        })
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "day15")]
+    use crate::day15::{
+        day15_input,
+        Sensors,
+    };
+    use crate::{
+        runner::Part,
+        solution,
+    };
+
+    #[test]
+    fn same_seed_same_input() {
+        for generator in generators() {
+            let size = generator.default_size;
+            assert_eq!(
+                generator.generate(7, size).unwrap(),
+                generator.generate(7, size).unwrap(),
+                "day {}",
+                generator.day
+            );
+            assert_ne!(
+                generator.generate(7, size).unwrap(),
+                generator.generate(8, size).unwrap(),
+                "day {}",
+                generator.day
+            );
+        }
+    }
+
+    /// the generators of enabled days, with their solvers.
+    fn enabled() -> impl Iterator<Item = (&'static Generator, Box<dyn solution::Solver>)> {
+        generators()
+            .iter()
+            .filter_map(|generator| Some((generator, solution::solver(generator.day).ok()?)))
+    }
+
+    #[test]
+    fn default_size_parses() {
+        for (generator, solver) in enabled() {
+            for seed in 0..3 {
+                let input = generator.generate(seed, generator.default_size).unwrap();
+                if let Err(e) = solver.run(&input, &[]) {
+                    panic!("day {} seed {}: {:?}", generator.day, seed, e);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn smallest_size_solves() {
+        // day 15 always searches 4000000 rows, see `day15_exactly_one_gap`.
+        for (generator, solver) in enabled().filter(|(generator, _)| generator.day != 15) {
+            for seed in 0..3 {
+                let input = generator.generate(seed, *generator.sizes.start()).unwrap();
+                if let Err(e) = solver.run(&input, &Part::ALL) {
+                    panic!("day {} seed {}: {:?}\n{}", generator.day, seed, e, input);
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "day15")]
+    fn day15_exactly_one_gap() {
+        const SEARCH_AREA: i64 = 300;
+
+        for seed in 0..3 {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            let input = day15_with_search_area(&mut rng, 20, SEARCH_AREA);
+            let sensors = day15_input(&input).unwrap();
+            let sensors = Sensors::new(&sensors);
+
+            let mut num_uncovered = 0;
+            for y in 0..=SEARCH_AREA {
+                for gap in sensors
+                    .covered_positions_for_row(y)
+                    .gaps(&(0..=SEARCH_AREA))
+                {
+                    num_uncovered += gap.end() - gap.start() + 1;
+                }
+            }
+            assert_eq!(num_uncovered, 1, "seed {}", seed);
+            sensors.find_distress_signal(SEARCH_AREA).unwrap();
+        }
+    }
+
+    #[test]
+    fn invalid_size() {
+        assert!(matches!(
+            generate(11, 0, Some(300)),
+            Err(GenerateError::InvalidSize { day: 11, .. })
+        ));
+        assert!(matches!(
+            generate(16, 0, None),
+            Err(GenerateError::UnknownDay(16))
+        ));
+    }
+}
@@ -0,0 +1,405 @@
+//! points, directions and bounding boxes shared by the days.
+//!
+//! coordinates follow the puzzle inputs: x grows to the right and y grows
+//! downwards, so [`Direction::Up`] decreases y.
+
+use std::{
+    fmt,
+    hash::Hash,
+    ops::{
+        Add,
+        AddAssign,
+        Mul,
+        Neg,
+        Sub,
+        SubAssign,
+    },
+};
+
+use num_traits::{
+    PrimInt,
+    Signed,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+/// an integer type that can be used for coordinates.
+pub trait Coordinate: PrimInt + Hash + fmt::Debug + Default {}
+
+impl<T: PrimInt + Hash + fmt::Debug + Default> Coordinate for T {}
+
+/// `|a - b|` that also works for unsigned types.
+fn abs_diff<T: Coordinate>(a: T, b: T) -> T {
+    if a > b {
+        a - b
+    }
+    else {
+        b - a
+    }
+}
+
+/// a point or vector in 2D.
+#[derive(
+    Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub struct Point2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+/// a point or vector in 3D.
+#[derive(
+    Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub struct Point3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+/// implements everything that doesn't depend on the number of dimensions.
+macro_rules! impl_point {
+    ($point:ident { $($c:ident),+ }) => {
+        impl<T: Coordinate> $point<T> {
+            pub fn new($($c: T),+) -> Self {
+                Self { $($c),+ }
+            }
+
+            pub fn zero() -> Self {
+                Self { $($c: T::zero()),+ }
+            }
+
+            /// sum of the distances along all axes.
+            pub fn manhattan_distance(self, other: Self) -> T {
+                T::zero() $(+ abs_diff(self.$c, other.$c))+
+            }
+
+            /// largest distance along any axis, i.e. the number of king moves
+            /// between two points.
+            pub fn chebyshev_distance(self, other: Self) -> T {
+                T::zero() $(.max(abs_diff(self.$c, other.$c)))+
+            }
+
+            /// returns the smaller coordinate on each axis.
+            pub fn component_min(self, other: Self) -> Self {
+                Self { $($c: self.$c.min(other.$c)),+ }
+            }
+
+            /// returns the larger coordinate on each axis.
+            pub fn component_max(self, other: Self) -> Self {
+                Self { $($c: self.$c.max(other.$c)),+ }
+            }
+
+            /// converts to another coordinate type, or returns `None` if a
+            /// coordinate doesn't fit.
+            pub fn cast<U: Coordinate>(self) -> Option<$point<U>> {
+                Some($point { $($c: U::from(self.$c)?),+ })
+            }
+        }
+
+        impl<T: Coordinate + Signed> $point<T> {
+            /// returns -1, 0 or 1 on each axis, i.e. the unit step towards
+            /// the point.
+            pub fn signum(self) -> Self {
+                Self { $($c: self.$c.signum()),+ }
+            }
+        }
+
+        impl<T: Coordinate> Add for $point<T> {
+            type Output = Self;
+
+            fn add(self, other: Self) -> Self {
+                Self { $($c: self.$c + other.$c),+ }
+            }
+        }
+
+        impl<T: Coordinate> AddAssign for $point<T> {
+            fn add_assign(&mut self, other: Self) {
+                *self = *self + other;
+            }
+        }
+
+        impl<T: Coordinate> Sub for $point<T> {
+            type Output = Self;
+
+            fn sub(self, other: Self) -> Self {
+                Self { $($c: self.$c - other.$c),+ }
+            }
+        }
+
+        impl<T: Coordinate> SubAssign for $point<T> {
+            fn sub_assign(&mut self, other: Self) {
+                *self = *self - other;
+            }
+        }
+
+        impl<T: Coordinate> Mul<T> for $point<T> {
+            type Output = Self;
+
+            fn mul(self, factor: T) -> Self {
+                Self { $($c: self.$c * factor),+ }
+            }
+        }
+
+        impl<T: Coordinate + Signed> Neg for $point<T> {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                Self { $($c: -self.$c),+ }
+            }
+        }
+    };
+}
+
+impl_point!(Point2 { x, y });
+impl_point!(Point3 { x, y, z });
+
+impl<T: Coordinate> Point2<T> {
+    /// moves one unit in `direction`, or returns `None` if that leaves the
+    /// range of `T`, e.g. moving left from `x = 0` with unsigned coordinates.
+    pub fn checked_step(self, direction: Direction) -> Option<Self> {
+        let one = T::one();
+        Some(match direction {
+            Direction::Up => Self::new(self.x, self.y.checked_sub(&one)?),
+            Direction::Right => Self::new(self.x.checked_add(&one)?, self.y),
+            Direction::Down => Self::new(self.x, self.y.checked_add(&one)?),
+            Direction::Left => Self::new(self.x.checked_sub(&one)?, self.y),
+        })
+    }
+}
+
+impl<T: Coordinate + Signed> Point2<T> {
+    /// moves one unit in `direction`.
+    pub fn step(self, direction: Direction) -> Self {
+        self + direction.offset()
+    }
+}
+
+impl<T: Coordinate> From<(T, T)> for Point2<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Self::new(x, y)
+    }
+}
+
+impl<T: Coordinate> From<(T, T, T)> for Point3<T> {
+    fn from((x, y, z): (T, T, T)) -> Self {
+        Self::new(x, y, z)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Point2<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Point3<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
+}
+
+/// one of the four directions on a grid.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// all directions, clockwise starting with [`Direction::Up`].
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    pub fn rotate_cw(self) -> Self {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
+
+    pub fn rotate_ccw(self) -> Self {
+        match self {
+            Direction::Up => Direction::Left,
+            Direction::Right => Direction::Up,
+            Direction::Down => Direction::Right,
+            Direction::Left => Direction::Down,
+        }
+    }
+
+    pub fn opposite(self) -> Self {
+        self.rotate_cw().rotate_cw()
+    }
+
+    /// returns the vector of one unit in this direction.
+    pub fn offset<T: Coordinate + Signed>(self) -> Point2<T> {
+        let one = T::one();
+        match self {
+            Direction::Up => Point2::new(T::zero(), -one),
+            Direction::Right => Point2::new(one, T::zero()),
+            Direction::Down => Point2::new(T::zero(), one),
+            Direction::Left => Point2::new(-one, T::zero()),
+        }
+    }
+}
+
+/// the smallest rectangle containing a set of points. both corners are
+/// inclusive.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct BoundingBox<T> {
+    pub min: Point2<T>,
+    pub max: Point2<T>,
+}
+
+impl<T: Coordinate> BoundingBox<T> {
+    /// returns a box containing just `point`.
+    pub fn from_point(point: Point2<T>) -> Self {
+        Self {
+            min: point,
+            max: point,
+        }
+    }
+
+    /// returns the bounding box of `points`, or `None` if there are none.
+    pub fn from_points(points: impl IntoIterator<Item = Point2<T>>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let mut bounding_box = Self::from_point(points.next()?);
+        for point in points {
+            bounding_box.include(point);
+        }
+        Some(bounding_box)
+    }
+
+    /// grows the box so that it contains `point`.
+    pub fn include(&mut self, point: Point2<T>) {
+        self.min = self.min.component_min(point);
+        self.max = self.max.component_max(point);
+    }
+
+    /// returns the box grown by `margin` in every direction, saturating at
+    /// the bounds of `T`.
+    pub fn expand(&self, margin: T) -> Self {
+        Self {
+            min: Point2::new(
+                self.min.x.saturating_sub(margin),
+                self.min.y.saturating_sub(margin),
+            ),
+            max: Point2::new(
+                self.max.x.saturating_add(margin),
+                self.max.y.saturating_add(margin),
+            ),
+        }
+    }
+
+    pub fn contains(&self, point: Point2<T>) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x) && (self.min.y..=self.max.y).contains(&point.y)
+    }
+
+    pub fn width(&self) -> T {
+        self.max.x - self.min.x + T::one()
+    }
+
+    pub fn height(&self) -> T {
+        self.max.y - self.min.y + T::one()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn metrics() {
+        let a = Point2::new(2i64, 18);
+        let b = Point2::new(-2, 15);
+        assert_eq!(a.manhattan_distance(b), 7);
+        assert_eq!(a.chebyshev_distance(b), 4);
+
+        let a = Point3::new(1u32, 2, 3);
+        let b = Point3::new(4, 0, 3);
+        assert_eq!(a.manhattan_distance(b), 5);
+        assert_eq!(b.manhattan_distance(a), 5);
+        assert_eq!(a.chebyshev_distance(b), 3);
+    }
+
+    #[test]
+    fn arithmetic() {
+        let mut p = Point2::new(1, 2) + Point2::new(3, -4);
+        assert_eq!(p, Point2::new(4, -2));
+        p -= Point2::new(1, 1);
+        assert_eq!(p, Point2::new(3, -3));
+        assert_eq!(-p * 2, Point2::new(-6, 6));
+        assert_eq!(p.signum(), Point2::new(1, -1));
+        assert_eq!(Point3::new(1, 2, 3) - Point3::zero(), Point3::new(1, 2, 3));
+        assert_eq!(Point2::new(300i32, 2).cast::<u8>(), None);
+        assert_eq!(Point2::new(3i32, 2).cast::<u8>(), Some(Point2::new(3, 2)));
+    }
+
+    #[test]
+    fn directions() {
+        assert_eq!(Direction::Up.offset::<i32>(), Point2::new(0, -1));
+        assert_eq!(Point2::new(0, 0).step(Direction::Left), Point2::new(-1, 0));
+
+        for direction in Direction::ALL {
+            assert_eq!(direction.rotate_cw().rotate_ccw(), direction);
+            assert_eq!(
+                direction.opposite().offset::<i32>(),
+                -direction.offset::<i32>()
+            );
+        }
+        assert_eq!(Direction::Left.rotate_cw(), Direction::Up);
+        assert_eq!(Direction::Up.rotate_ccw(), Direction::Left);
+    }
+
+    #[test]
+    fn checked_step() {
+        let origin = Point2::new(0usize, 0);
+        assert_eq!(origin.checked_step(Direction::Up), None);
+        assert_eq!(origin.checked_step(Direction::Left), None);
+        assert_eq!(
+            origin.checked_step(Direction::Down),
+            Some(Point2::new(0, 1))
+        );
+        assert_eq!(
+            origin.checked_step(Direction::Right),
+            Some(Point2::new(1, 0))
+        );
+    }
+
+    #[test]
+    fn bounding_box() {
+        let mut bounding_box = BoundingBox::from_point(Point2::new(500, 0));
+        bounding_box.include(Point2::new(498, 4));
+        bounding_box.include(Point2::new(503, 9));
+        assert_eq!(bounding_box.min, Point2::new(498, 0));
+        assert_eq!(bounding_box.max, Point2::new(503, 9));
+        assert_eq!((bounding_box.width(), bounding_box.height()), (6, 10));
+        assert!(bounding_box.contains(Point2::new(498, 9)));
+        assert!(!bounding_box.contains(Point2::new(497, 9)));
+
+        let expanded = bounding_box.expand(1);
+        assert_eq!(expanded.min, Point2::new(497, -1));
+        assert_eq!(expanded.max, Point2::new(504, 10));
+
+        assert_eq!(
+            BoundingBox::from_points([Point2::new(0u8, 5), Point2::new(3, 1)])
+                .unwrap()
+                .expand(2),
+            BoundingBox {
+                min: Point2::new(0, 0),
+                max: Point2::new(5, 7),
+            }
+        );
+        assert_eq!(BoundingBox::<i32>::from_points([]), None);
+    }
+}
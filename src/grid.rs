@@ -0,0 +1,509 @@
+//! dense 2D grids, e.g. of the character maps in the puzzle inputs.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    iter,
+    ops::{
+        Index,
+        IndexMut,
+    },
+};
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    error::Error,
+    geometry::{
+        Direction,
+        Point2,
+    },
+    input,
+};
+
+/// a rectangular grid of cells, stored row by row.
+///
+/// positions are [`Point2<usize>`] with `(0, 0)` in the top left corner.
+///
+/// grids are serialized as their width and cells. the height follows from
+/// them, and is checked when deserializing.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "GridCells<T>")]
+pub struct Grid<T> {
+    width: usize,
+    #[serde(skip_serializing)]
+    height: usize,
+    cells: Vec<T>,
+}
+
+#[derive(Deserialize)]
+struct GridCells<T> {
+    width: usize,
+    cells: Vec<T>,
+}
+
+impl<T> TryFrom<GridCells<T>> for Grid<T> {
+    type Error = String;
+
+    fn try_from(grid: GridCells<T>) -> Result<Self, Self::Error> {
+        let num_cells = grid.cells.len();
+        Self::from_cells(grid.width, grid.cells).ok_or_else(|| {
+            format!(
+                "{} cells don't fill complete rows of {}",
+                num_cells, grid.width
+            )
+        })
+    }
+}
+
+/// the most memory the cells of a grid may take up. larger grids are refused
+/// instead of aborting on a failed allocation.
+pub const MAX_BYTES: usize = 1 << 30;
+
+impl<T: Clone> Grid<T> {
+    /// returns a grid with every cell set to `cell`, or `None` if it would be
+    /// larger than [`MAX_BYTES`].
+    pub fn new(width: usize, height: usize, cell: T) -> Option<Self> {
+        let num_cells = Self::num_cells(width, height)?;
+        Some(Self {
+            width,
+            height,
+            cells: vec![cell; num_cells],
+        })
+    }
+}
+
+impl<T> Grid<T> {
+    /// returns the number of cells of a `width` x `height` grid, or `None` if
+    /// it would be larger than [`MAX_BYTES`].
+    pub fn num_cells(width: usize, height: usize) -> Option<usize> {
+        let num_cells = width.checked_mul(height)?;
+        let num_bytes = num_cells.checked_mul(std::mem::size_of::<T>())?;
+        (num_bytes <= MAX_BYTES).then_some(num_cells)
+    }
+
+    /// creates a grid from its cells, row by row. returns `None` if the
+    /// cells don't fill complete rows of `width`.
+    pub fn from_cells(width: usize, cells: Vec<T>) -> Option<Self> {
+        let height = match width {
+            0 if cells.is_empty() => 0,
+            0 => return None,
+            _ if !cells.len().is_multiple_of(width) => return None,
+            _ => cells.len() / width,
+        };
+
+        Some(Self {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    /// parses a map with one character per cell. `what` describes what
+    /// `parse_cell` accepts, e.g. "a tree height".
+    pub fn parse(
+        input: &str,
+        what: &str,
+        parse_cell: impl FnMut(char) -> Option<T>,
+    ) -> Result<Self, Error> {
+        Self::parse_with_markers(input, &[], what, parse_cell).map(|(grid, _)| grid)
+    }
+
+    /// like [`Grid::parse`], but also returns where the `markers` are.
+    ///
+    /// markers are passed to `parse_cell` like every other character, since
+    /// they usually stand for a regular cell too, e.g. the start `S` of day 12
+    /// is at elevation `a`.
+    pub fn parse_with_markers(
+        input: &str,
+        markers: &[char],
+        what: &str,
+        mut parse_cell: impl FnMut(char) -> Option<T>,
+    ) -> Result<(Self, Markers), Error> {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = vec![];
+        let mut found = Markers::default();
+
+        for line in input::lines(input) {
+            let mut row_width = 0;
+
+            for (x, (i, c)) in line.text.char_indices().enumerate() {
+                if markers.contains(&c) {
+                    found.0.entry(c).or_default().push(Point2::new(x, height));
+                }
+                let cell = parse_cell(c).ok_or_else(|| {
+                    line.error(
+                        &line.text[i..i + c.len_utf8()],
+                        format!("expected {}", what),
+                    )
+                })?;
+                cells.push(cell);
+                row_width += 1;
+            }
+
+            match width {
+                None => width = Some(row_width),
+                Some(width) if width != row_width => {
+                    return Err(line.error(line.text, format!("expected a row of width {}", width)));
+                }
+                Some(_) => {}
+            }
+            height += 1;
+        }
+
+        let width = width
+            .filter(|width| *width > 0)
+            .ok_or_else(|| Error::InvalidInput("the map is empty".to_owned()))?;
+
+        let grid = Self {
+            width,
+            height,
+            cells,
+        };
+        Ok((grid, found))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, position: Point2<usize>) -> bool {
+        position.x < self.width && position.y < self.height
+    }
+
+    fn index_of(&self, position: Point2<usize>) -> Option<usize> {
+        self.contains(position)
+            .then_some(position.y * self.width + position.x)
+    }
+
+    pub fn get(&self, position: Point2<usize>) -> Option<&T> {
+        self.index_of(position).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, position: Point2<usize>) -> Option<&mut T> {
+        self.index_of(position).map(|i| &mut self.cells[i])
+    }
+
+    /// returns all positions, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Point2<usize>> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| Point2::new(x, y)))
+    }
+
+    /// returns all cells with their positions, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Point2<usize>, &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // `chunks` panics for a width of 0, but then there are no cells anyway
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn row(&self, y: usize) -> Option<&[T]> {
+        (y < self.height).then(|| &self.cells[y * self.width..(y + 1) * self.width])
+    }
+
+    /// returns the cells of column `x` from top to bottom.
+    pub fn column(&self, x: usize) -> Option<impl Iterator<Item = &T>> {
+        (x < self.width).then(|| (0..self.height).map(move |y| &self.cells[y * self.width + x]))
+    }
+
+    /// returns the positions next to `position` in [`Direction::ALL`] order,
+    /// skipping the ones outside of the grid.
+    pub fn neighbors4(&self, position: Point2<usize>) -> impl Iterator<Item = Point2<usize>> + '_ {
+        Direction::ALL
+            .into_iter()
+            .filter_map(move |direction| position.checked_step(direction))
+            .filter(|neighbor| self.contains(*neighbor))
+    }
+
+    /// like [`Grid::neighbors4`], but including the diagonal neighbors. they
+    /// are returned clockwise, starting with the one above `position`.
+    pub fn neighbors8(&self, position: Point2<usize>) -> impl Iterator<Item = Point2<usize>> + '_ {
+        Direction::ALL
+            .into_iter()
+            .flat_map(move |direction| {
+                let straight = position.checked_step(direction);
+                let diagonal = straight.and_then(|p| p.checked_step(direction.rotate_cw()));
+                [straight, diagonal]
+            })
+            .flatten()
+            .filter(|neighbor| self.contains(*neighbor))
+    }
+
+    /// returns the positions from `start` to the edge of the grid in
+    /// `direction`, not including `start` itself.
+    pub fn ray(
+        &self,
+        start: Point2<usize>,
+        direction: Direction,
+    ) -> impl Iterator<Item = Point2<usize>> + '_ {
+        iter::successors(start.checked_step(direction), move |position| {
+            position.checked_step(direction)
+        })
+        .take_while(|position| self.contains(*position))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T> Index<Point2<usize>> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, position: Point2<usize>) -> &T {
+        match self.index_of(position) {
+            Some(i) => &self.cells[i],
+            None => {
+                panic!(
+                    "{} is outside of the {}x{} grid",
+                    position, self.width, self.height
+                )
+            }
+        }
+    }
+}
+
+impl<T> IndexMut<Point2<usize>> for Grid<T> {
+    fn index_mut(&mut self, position: Point2<usize>) -> &mut T {
+        match self.index_of(position) {
+            Some(i) => &mut self.cells[i],
+            None => {
+                panic!(
+                    "{} is outside of the {}x{} grid",
+                    position, self.width, self.height
+                )
+            }
+        }
+    }
+}
+
+/// writes every cell with its `Display` impl, one line per row.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// positions of the markers found by [`Grid::parse_with_markers`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Markers(HashMap<char, Vec<Point2<usize>>>);
+
+impl Markers {
+    /// returns all positions of `marker`, row by row.
+    pub fn get(&self, marker: char) -> &[Point2<usize>] {
+        self.0.get(&marker).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// returns the position of a marker that has to appear exactly once.
+    /// `what` describes the marker in errors, e.g. "start position".
+    pub fn single(&self, marker: char, what: &str) -> Result<Point2<usize>, Error> {
+        match self.get(marker) {
+            [position] => Ok(*position),
+            [] => Err(Error::InvalidInput(format!("no {} ({:?})", what, marker))),
+            positions => {
+                Err(Error::InvalidInput(format!(
+                    "expected one {} ({:?}), but found {}",
+                    what,
+                    marker,
+                    positions.len()
+                )))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAP: &str = "#.S
+.E.
+..#
+E#.";
+
+    fn parse_map() -> (Grid<bool>, Markers) {
+        Grid::parse_with_markers(MAP, &['S', 'E'], "a tile", |c| {
+            match c {
+                '#' => Some(true),
+                '.' | 'S' | 'E' => Some(false),
+                _ => None,
+            }
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn parse() {
+        let (grid, markers) = parse_map();
+        assert_eq!((grid.width(), grid.height()), (3, 4));
+        assert_eq!(grid.get(Point2::new(0, 0)), Some(&true));
+        assert_eq!(grid.get(Point2::new(1, 3)), Some(&true));
+        assert_eq!(grid.get(Point2::new(3, 0)), None);
+        assert_eq!(grid.get(Point2::new(0, 4)), None);
+
+        assert_eq!(markers.single('S', "start").unwrap(), Point2::new(2, 0));
+        assert_eq!(markers.get('E'), &[Point2::new(1, 1), Point2::new(0, 3)]);
+        assert!(matches!(
+            markers.single('E', "end"),
+            Err(Error::InvalidInput(_))
+        ));
+        assert!(matches!(
+            markers.single('X', "treasure"),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn parse_errors() {
+        let digit = |c: char| c.to_digit(10);
+
+        assert!(matches!(
+            Grid::parse("123\n45x", "a digit", digit),
+            Err(Error::Parse {
+                line: 2,
+                column: 3,
+                ..
+            })
+        ));
+        assert!(matches!(
+            Grid::parse("123\n45", "a digit", digit),
+            Err(Error::Parse {
+                line: 2,
+                column: 1,
+                ..
+            })
+        ));
+        assert!(matches!(
+            Grid::parse("", "a digit", digit),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn display() {
+        let (grid, _) = parse_map();
+        let display = grid
+            .map(|wall| {
+                if *wall {
+                    '#'
+                }
+                else {
+                    '.'
+                }
+            })
+            .to_string();
+        assert_eq!(display, "#..\n...\n..#\n.#.\n");
+    }
+
+    #[test]
+    fn access() {
+        let mut grid = Grid::from_cells(3, (0..6).collect()).unwrap();
+        assert_eq!(grid[Point2::new(2, 1)], 5);
+        *grid.get_mut(Point2::new(0, 1)).unwrap() = 30;
+        grid[Point2::new(1, 0)] = 10;
+
+        assert_eq!(grid.row(1), Some(&[30, 4, 5][..]));
+        assert_eq!(grid.row(2), None);
+        assert_eq!(
+            grid.column(1).unwrap().copied().collect::<Vec<_>>(),
+            vec![10, 4]
+        );
+        assert!(grid.column(3).is_none());
+        assert_eq!(grid.iter().nth(4), Some((Point2::new(1, 1), &4)));
+
+        assert!(Grid::from_cells(4, vec![0; 6]).is_none());
+        assert_eq!(Grid::<u8>::from_cells(0, vec![]).unwrap().height(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "(3, 0) is outside of the 3x2 grid")]
+    fn index_out_of_bounds() {
+        let grid = Grid::new(3, 2, 0).unwrap();
+        let _ = grid[Point2::new(3, 0)];
+    }
+
+    #[test]
+    fn too_large() {
+        assert!(Grid::new(usize::MAX, 2, ()).is_none());
+        assert!(Grid::new(1 << 16, 1 << 15, 0u8).is_none());
+        assert!(Grid::new(1 << 16, 1 << 13, 0u32).is_none());
+        assert_eq!(Grid::<u32>::num_cells(1 << 16, 1 << 12), Some(1 << 28));
+        assert_eq!(Grid::<()>::num_cells(usize::MAX, 1), Some(usize::MAX));
+    }
+
+    #[test]
+    fn neighbors() {
+        let grid = Grid::new(3, 3, ()).unwrap();
+
+        assert_eq!(
+            grid.neighbors4(Point2::new(0, 0)).collect::<Vec<_>>(),
+            vec![Point2::new(1, 0), Point2::new(0, 1)]
+        );
+        assert_eq!(grid.neighbors4(Point2::new(1, 1)).count(), 4);
+        assert_eq!(
+            grid.neighbors8(Point2::new(1, 1)).collect::<Vec<_>>(),
+            vec![
+                Point2::new(1, 0),
+                Point2::new(2, 0),
+                Point2::new(2, 1),
+                Point2::new(2, 2),
+                Point2::new(1, 2),
+                Point2::new(0, 2),
+                Point2::new(0, 1),
+                Point2::new(0, 0),
+            ]
+        );
+        assert_eq!(
+            grid.neighbors8(Point2::new(2, 2)).collect::<Vec<_>>(),
+            vec![Point2::new(2, 1), Point2::new(1, 2), Point2::new(1, 1)]
+        );
+    }
+
+    #[test]
+    fn ray() {
+        let grid = Grid::new(4, 3, ()).unwrap();
+
+        assert_eq!(
+            grid.ray(Point2::new(1, 1), Direction::Right)
+                .collect::<Vec<_>>(),
+            vec![Point2::new(2, 1), Point2::new(3, 1)]
+        );
+        assert_eq!(grid.ray(Point2::new(1, 0), Direction::Up).count(), 0);
+        assert_eq!(grid.ray(Point2::new(1, 0), Direction::Down).count(), 2);
+    }
+
+    #[test]
+    fn serde() {
+        let grid = Grid::from_cells(2, vec![1, 2, 3, 4, 5, 6]).unwrap();
+        let json = serde_json::to_string(&grid).unwrap();
+        assert_eq!(json, r#"{"width":2,"cells":[1,2,3,4,5,6]}"#);
+        assert_eq!(serde_json::from_str::<Grid<i32>>(&json).unwrap(), grid);
+
+        let error =
+            serde_json::from_str::<Grid<i32>>(r#"{"width":4,"cells":[1,2,3,4,5,6]}"#).unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("6 cells don't fill complete rows of 4"));
+    }
+}
@@ -0,0 +1,115 @@
+use std::{
+    iter::Peekable,
+    str::{
+        self,
+        FromStr,
+    },
+};
+
+#[cfg(any(feature = "answers", feature = "executor"))]
+use sha2::{
+    Digest,
+    Sha256,
+};
+
+use crate::error::Error;
+
+/// a line of the puzzle input together with its line number.
+#[derive(Copy, Clone, Debug)]
+pub struct Line<'a> {
+    pub number: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    /// creates a parse error for `part`, which should be a substring of this
+    /// line.
+    pub fn error(&self, part: &str, message: impl Into<String>) -> Error {
+        Error::parse(self.number, self.text, part, message)
+    }
+
+    /// parses `part`, which should be a substring of this line.
+    ///
+    /// `what` describes what was expected, e.g. "a number".
+    pub fn parse<T: FromStr>(&self, part: &str, what: &str) -> Result<T, Error> {
+        part.parse()
+            .map_err(|_| self.error(part, format!("expected {}", what)))
+    }
+
+    /// strips `prefix` from the line and returns the rest.
+    pub fn strip_prefix(&self, prefix: &str) -> Result<&'a str, Error> {
+        self.text
+            .strip_prefix(prefix)
+            .ok_or_else(|| self.error(self.text, format!("expected {:?}", prefix)))
+    }
+
+    /// splits the line once at `delimiter`.
+    pub fn split_once(&self, delimiter: &str) -> Result<(&'a str, &'a str), Error> {
+        self.text
+            .split_once(delimiter)
+            .ok_or_else(|| self.error(self.text, format!("expected {:?}", delimiter)))
+    }
+}
+
+/// iterator over the lines of the puzzle input that keeps track of line
+/// numbers.
+pub struct Lines<'a> {
+    lines: Peekable<str::Lines<'a>>,
+    number: usize,
+}
+
+impl<'a> Lines<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self {
+            lines: input.lines().peekable(),
+            number: 0,
+        }
+    }
+
+    pub fn peek(&mut self) -> Option<&&'a str> {
+        self.lines.peek()
+    }
+
+    /// returns the next line, or an error if the input ended.
+    ///
+    /// `expected` describes what was expected on that line.
+    pub fn next_line(&mut self, expected: &str) -> Result<Line<'a>, Error> {
+        let number = self.number + 1;
+        self.next().ok_or_else(|| {
+            Error::UnexpectedEnd {
+                line: number,
+                expected: expected.to_owned(),
+            }
+        })
+    }
+}
+
+impl<'a> Iterator for Lines<'a> {
+    type Item = Line<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let text = self.lines.next()?;
+        self.number += 1;
+        Some(Line {
+            number: self.number,
+            text,
+        })
+    }
+}
+
+/// returns an iterator over the lines of `input` with line numbers.
+pub fn lines(input: &str) -> Lines<'_> {
+    Lines::new(input)
+}
+
+/// returns a hash that identifies a puzzle input, e.g. in the answers file.
+///
+/// trailing newlines are ignored, so the hash doesn't depend on how the input
+/// was saved.
+#[cfg(any(feature = "answers", feature = "executor"))]
+pub fn hash(input: &str) -> String {
+    Sha256::digest(input.trim_end_matches('\n'))
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
@@ -1,22 +1,80 @@
-#![allow(dead_code)]
-
 #[macro_use]
 extern crate aoc_runner_derive;
 
-mod day1;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
+#[cfg(feature = "answers")]
+pub mod answers;
+#[cfg(all(
+    feature = "checkpoint",
+    any(
+        feature = "day5",
+        feature = "day9",
+        feature = "day10",
+        feature = "day11",
+        feature = "day14"
+    )
+))]
+pub mod checkpoint;
+#[cfg(feature = "client")]
+pub mod client;
+#[cfg(feature = "day1")]
+pub mod day1;
+#[cfg(feature = "day10")]
+pub mod day10;
+#[cfg(feature = "day11")]
+pub mod day11;
+#[cfg(feature = "day12")]
+pub mod day12;
+#[cfg(feature = "day13")]
+pub mod day13;
+#[cfg(feature = "day14")]
+pub mod day14;
+#[cfg(feature = "day15")]
+pub mod day15;
+#[cfg(feature = "day2")]
+pub mod day2;
+#[cfg(feature = "day3")]
+pub mod day3;
+#[cfg(feature = "day4")]
+pub mod day4;
+#[cfg(feature = "day5")]
+pub mod day5;
+#[cfg(feature = "day6")]
+pub mod day6;
+#[cfg(feature = "day7")]
+pub mod day7;
+#[cfg(feature = "day8")]
+pub mod day8;
+#[cfg(feature = "day9")]
+pub mod day9;
+pub mod error;
+#[cfg(feature = "executor")]
+pub mod executor;
+#[cfg(feature = "fixtures")]
+pub mod fixtures;
+#[cfg(feature = "generate")]
+pub mod generate;
+pub mod geometry;
+pub mod grid;
+pub mod input;
+pub mod memory;
+pub mod num;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(test)]
+mod reference;
+pub mod render;
+pub mod runner;
+#[cfg(feature = "answers")]
+pub mod scaffold;
+pub mod simulation;
+pub mod solution;
+pub mod stream;
+#[cfg(all(
+    feature = "tui",
+    any(feature = "day9", feature = "day10", feature = "day14")
+))]
+pub mod tui;
+
+pub use crate::error::Error;
 
-aoc_main! { year = 2022 }
+aoc_lib! { year = 2022 }
@@ -0,0 +1,1521 @@
+use std::{
+    fs,
+    io::{
+        self,
+        BufRead,
+        BufReader,
+        IsTerminal,
+        Read,
+        Write,
+    },
+    path::{
+        Path,
+        PathBuf,
+    },
+    process::ExitCode,
+};
+
+#[cfg(any(
+    feature = "day5",
+    feature = "day9",
+    feature = "day10",
+    feature = "day11",
+    feature = "day14"
+))]
+use aoc2022::checkpoint::Checkpoint;
+#[cfg(feature = "day10")]
+use aoc2022::day10;
+#[cfg(feature = "day14")]
+use aoc2022::day14;
+#[cfg(feature = "day9")]
+use aoc2022::day9;
+#[cfg(any(feature = "day9", feature = "day10", feature = "day14"))]
+use aoc2022::render::{
+    Frame,
+    FrameDirectory,
+    FrameSink,
+    GifAnimation,
+    ImageFormat,
+    Render,
+    RenderError,
+    TextFrames,
+};
+#[cfg(all(
+    feature = "tui",
+    any(feature = "day9", feature = "day10", feature = "day14")
+))]
+use aoc2022::tui;
+use aoc2022::{
+    answers::{
+        self,
+        Answers,
+        Verdict,
+    },
+    client::{
+        self,
+        Client,
+        SubmitOutcome,
+        SubmitResult,
+    },
+    error::error_chain,
+    executor::{
+        Job,
+        Report,
+    },
+    fixtures::Fixture,
+    generate,
+    input,
+    runner::{
+        DayRun,
+        Part,
+        RunError,
+    },
+    scaffold::Scaffold,
+    solution::{
+        self,
+        Solver,
+    },
+    stream::{
+        self,
+        StreamError,
+        StreamSolver,
+    },
+};
+use clap::{
+    Parser,
+    Subcommand,
+    ValueEnum,
+};
+use tracing_subscriber::EnvFilter;
+
+#[cfg(feature = "alloc-profile")]
+#[global_allocator]
+static ALLOCATOR: aoc2022::memory::CountingAllocator = aoc2022::memory::CountingAllocator;
+
+/// runs the advent of code 2022 solutions without cargo-aoc.
+#[derive(Debug, Parser)]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+
+    /// logs more details to stderr: -v for timings, -vv for debug output of
+    /// the days and -vvv for traces.
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    verbose: u8,
+
+    /// additional log filters, e.g. `aoc2022::day15=trace` to trace the
+    /// sensors of day 15 only.
+    #[arg(long, global = true, env = "AOC_LOG")]
+    log: Option<String>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// runs a single day.
+    Run {
+        /// the day to run.
+        #[arg(short, long)]
+        day: u8,
+
+        /// the part to run. runs both parts if omitted.
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+
+        /// path to the puzzle input, or `-` to read it from stdin. defaults to
+        /// `input/2022/day<N>.txt` in the input directory.
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+
+        /// directory containing the puzzle inputs.
+        #[arg(long, default_value = "input/2022")]
+        input_dir: PathBuf,
+    },
+    /// runs all implemented days in parallel, and prints a report of the
+    /// answers, timings and input hashes.
+    RunAll {
+        /// directory containing the puzzle inputs.
+        #[arg(long, default_value = "input/2022")]
+        input_dir: PathBuf,
+
+        /// the number of threads. defaults to one per core.
+        #[arg(short, long)]
+        jobs: Option<usize>,
+
+        /// how to print the results.
+        #[arg(long, value_enum, default_value_t = ReportFormat::Table)]
+        format: ReportFormat,
+
+        /// where to write the report. prints it to stdout if omitted.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// runs a single day while reading its input line by line, for inputs
+    /// that don't fit into memory. see `aoc2022::stream` for the days that
+    /// support this.
+    Stream {
+        /// the day to run.
+        #[arg(short, long)]
+        day: u8,
+
+        /// the part to run. runs both parts if omitted.
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+
+        /// path to the puzzle input, or `-` to read it from stdin. defaults to
+        /// `input/2022/day<N>.txt` in the input directory.
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+
+        /// directory containing the puzzle inputs.
+        #[arg(long, default_value = "input/2022")]
+        input_dir: PathBuf,
+
+        /// prints the answers so far to stderr after every this many lines.
+        #[arg(long)]
+        progress: Option<usize>,
+    },
+    #[cfg(any(feature = "day9", feature = "day10", feature = "day14"))]
+    /// renders the final state of day 9, 10 or 14, or an animation of how it
+    /// gets there.
+    Render {
+        /// the day to render.
+        #[arg(short, long)]
+        day: u8,
+
+        /// the part to render. day 9 uses a longer rope and day 14 a floor
+        /// for part 2.
+        #[arg(short, long, default_value_t = 1, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+
+        /// path to the puzzle input, or `-` to read it from stdin. defaults to
+        /// `input/2022/day<N>.txt` in the input directory.
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+
+        /// directory containing the puzzle inputs.
+        #[arg(long, default_value = "input/2022")]
+        input_dir: PathBuf,
+
+        /// where to write the output. prints text to stdout if omitted.
+        ///
+        /// images are written as `.txt`, `.ppm` or `.png` depending on the
+        /// extension. animations are written as `.gif`, or as one image per
+        /// frame if the path has no extension.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// renders every step instead of only the final state.
+        #[arg(short, long)]
+        animate: bool,
+
+        /// format of the images when writing frames to a directory.
+        #[arg(long, value_enum, default_value_t = FrameFormat::Png)]
+        frame_format: FrameFormat,
+
+        /// size of a single cell in pixels.
+        #[arg(long, default_value_t = 4)]
+        scale: usize,
+
+        /// time between frames of a GIF in hundredths of a second.
+        #[arg(long, default_value_t = 5)]
+        delay: u16,
+    },
+    #[cfg(all(
+        feature = "tui",
+        any(feature = "day9", feature = "day10", feature = "day14")
+    ))]
+    /// plays the simulation of day 9, 10 or 14 in the terminal.
+    ///
+    /// the simulation can be paused, stepped and rewound, and zoomed and
+    /// scrolled with the keys shown at the bottom.
+    Tui {
+        /// the day to play.
+        #[arg(short, long)]
+        day: u8,
+
+        /// the part to play. day 9 uses a longer rope and day 14 a floor for
+        /// part 2.
+        #[arg(short, long, default_value_t = 1, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+
+        /// path to the puzzle input, or `-` to read it from stdin. defaults to
+        /// `input/2022/day<N>.txt` in the input directory.
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+
+        /// directory containing the puzzle inputs.
+        #[arg(long, default_value = "input/2022")]
+        input_dir: PathBuf,
+    },
+    #[cfg(any(
+        feature = "day5",
+        feature = "day9",
+        feature = "day10",
+        feature = "day11",
+        feature = "day14"
+    ))]
+    /// runs the simulation of day 5, 9, 10, 11 or 14 for some steps and saves
+    /// its state.
+    ///
+    /// a step is a move of crates for day 5, a step of the head by one position
+    /// for day 9, an instruction for day 10, a round for day 11 and a grain
+    /// of sand for day 14.
+    Checkpoint {
+        /// the day to simulate.
+        #[arg(short, long)]
+        day: u8,
+
+        /// the part to simulate.
+        #[arg(short, long, default_value_t = 1, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+
+        /// path to the puzzle input, or `-` to read it from stdin. defaults to
+        /// `input/2022/day<N>.txt` in the input directory.
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+
+        /// directory containing the puzzle inputs.
+        #[arg(long, default_value = "input/2022")]
+        input_dir: PathBuf,
+
+        /// the number of steps to take before saving.
+        #[arg(short, long)]
+        steps: usize,
+
+        /// where to save the checkpoint, as `.json` or `.bin`.
+        #[arg(short, long)]
+        output: PathBuf,
+    },
+    #[cfg(any(
+        feature = "day5",
+        feature = "day9",
+        feature = "day10",
+        feature = "day11",
+        feature = "day14"
+    ))]
+    /// continues a simulation from a checkpoint, and prints the answer once
+    /// it's finished.
+    Resume {
+        /// the checkpoint to continue from.
+        checkpoint: PathBuf,
+
+        /// the number of steps to take. runs until the simulation is finished
+        /// if omitted.
+        #[arg(short, long)]
+        steps: Option<usize>,
+
+        /// where to save the checkpoint afterwards, as `.json` or `.bin`.
+        /// unfinished simulations are saved back to the checkpoint if
+        /// omitted.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// generates a random puzzle input. the same seed and size always
+    /// generate the same input.
+    Generate {
+        /// the day to generate an input for.
+        #[arg(short, long)]
+        day: u8,
+
+        /// seed for the random number generator.
+        #[arg(short, long, default_value_t = 0)]
+        seed: u64,
+
+        /// size of the input, e.g. the number of elves for day 1. defaults to
+        /// the size of a typical puzzle input.
+        #[arg(long)]
+        size: Option<usize>,
+
+        /// file to write the input to. prints it to stdout if omitted.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// runs all days and compares their answers with the answers file.
+    Verify {
+        /// only verifies this day.
+        #[arg(short, long)]
+        day: Option<u8>,
+
+        /// directory containing the puzzle inputs.
+        #[arg(long, default_value = "input/2022")]
+        input_dir: PathBuf,
+
+        /// file with the known answers.
+        #[arg(long, default_value = "answers.toml")]
+        answers: PathBuf,
+    },
+    /// runs all days and records their answers in the answers file.
+    Record {
+        /// only records this day.
+        #[arg(short, long)]
+        day: Option<u8>,
+
+        /// directory containing the puzzle inputs.
+        #[arg(long, default_value = "input/2022")]
+        input_dir: PathBuf,
+
+        /// file with the known answers.
+        #[arg(long, default_value = "answers.toml")]
+        answers: PathBuf,
+
+        /// replaces known answers that differ.
+        #[arg(long)]
+        overwrite: bool,
+    },
+    /// downloads puzzle inputs into the input directory.
+    Fetch {
+        /// the day to download. downloads all implemented days if omitted.
+        #[arg(short, long)]
+        day: Option<u8>,
+
+        /// downloads inputs again even if they are cached.
+        #[arg(long)]
+        refresh: bool,
+
+        /// directory the inputs are cached in.
+        #[arg(long, default_value = "input/2022")]
+        input_dir: PathBuf,
+
+        #[command(flatten)]
+        server: ServerArgs,
+    },
+    /// submits an answer.
+    Submit {
+        /// the day to submit an answer for.
+        #[arg(short, long)]
+        day: u8,
+
+        /// the part to submit an answer for.
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+
+        /// the answer to submit. if omitted, the part is run on the input of
+        /// the day, which is downloaded if necessary.
+        #[arg(short, long)]
+        answer: Option<String>,
+
+        /// waits until rate limits are over instead of giving up.
+        #[arg(short, long)]
+        wait: bool,
+
+        /// directory the inputs are cached in.
+        #[arg(long, default_value = "input/2022")]
+        input_dir: PathBuf,
+
+        /// file the submissions are stored in.
+        #[arg(long, default_value = "submissions.toml")]
+        history: PathBuf,
+
+        #[command(flatten)]
+        server: ServerArgs,
+    },
+    /// creates a new day from `src/day_template.rs` and registers it.
+    NewDay {
+        /// the day to create.
+        #[arg(short, long)]
+        day: u8,
+
+        /// the source directory of the crate.
+        #[arg(long, default_value = "src")]
+        src_dir: PathBuf,
+
+        /// directory that gets an empty input file for the day.
+        #[arg(long, default_value = "input/2022")]
+        input_dir: PathBuf,
+
+        /// file that gets an empty table for the answers of the day.
+        #[arg(long, default_value = "answers.toml")]
+        answers: PathBuf,
+    },
+    /// extracts the examples and their answers from a saved puzzle page, and
+    /// writes them as a fixture for the tests.
+    ExtractExamples {
+        /// the day of the puzzle.
+        #[arg(short, long)]
+        day: u8,
+
+        /// the puzzle page, saved from the browser.
+        page: PathBuf,
+
+        /// directory that gets the fixture.
+        #[arg(long, default_value = "fixtures")]
+        fixtures_dir: PathBuf,
+
+        /// replaces an existing fixture.
+        #[arg(long)]
+        overwrite: bool,
+    },
+    /// lists all implemented days.
+    List,
+}
+
+#[derive(Debug, clap::Args)]
+struct ServerArgs {
+    /// the session cookie of your account.
+    #[arg(long, env = "AOC_SESSION", hide_env_values = true)]
+    session: String,
+
+    /// the server to talk to.
+    #[arg(long, env = "AOC_BASE_URL", default_value = client::DEFAULT_BASE_URL)]
+    base_url: String,
+}
+
+#[cfg(any(feature = "day9", feature = "day10", feature = "day14"))]
+#[derive(Copy, Clone, Debug, ValueEnum)]
+enum FrameFormat {
+    Txt,
+    Ppm,
+    Png,
+}
+
+#[cfg(any(feature = "day9", feature = "day10", feature = "day14"))]
+impl From<FrameFormat> for ImageFormat {
+    fn from(format: FrameFormat) -> Self {
+        match format {
+            FrameFormat::Txt => ImageFormat::Ascii,
+            FrameFormat::Ppm => ImageFormat::Ppm,
+            FrameFormat::Png => ImageFormat::Png,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+enum ReportFormat {
+    Table,
+    Json,
+    Markdown,
+    Junit,
+}
+
+#[cfg(any(feature = "day9", feature = "day10", feature = "day14"))]
+/// where and how `render` writes its output.
+struct RenderOptions {
+    output: Option<PathBuf>,
+    frame_format: ImageFormat,
+    scale: usize,
+    delay: u16,
+}
+
+#[cfg(any(feature = "day9", feature = "day10", feature = "day14"))]
+impl RenderOptions {
+    fn frame_sink(&self) -> Result<Box<dyn FrameSink>, RenderError> {
+        let sink: Box<dyn FrameSink> = match &self.output {
+            None => Box::new(TextFrames(io::stdout().lock())),
+            Some(path) if path.extension().is_none() => {
+                Box::new(FrameDirectory::create(path, self.frame_format, self.scale)?)
+            }
+            Some(path) if path.extension() == Some("gif".as_ref()) => {
+                Box::new(GifAnimation::create(path, self.scale, self.delay)?)
+            }
+            Some(path) => {
+                return Err(RenderError::UnknownFormat(path.display().to_string()));
+            }
+        };
+        Ok(sink)
+    }
+
+    fn write_frame(&self, frame: &Frame) -> Result<(), RenderError> {
+        match &self.output {
+            None => {
+                print!("{}", frame);
+                Ok(())
+            }
+            Some(path) => frame.save(path, self.scale),
+        }
+    }
+}
+
+fn read_input(path: &Path) -> io::Result<String> {
+    let mut input = if path == Path::new("-") {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        input
+    }
+    else {
+        fs::read_to_string(path)?
+    };
+
+    // cargo-aoc strips trailing newlines, and the generators rely on that.
+    // `\r\n` is trimmed too, like the lines in between and `stream` do.
+    input.truncate(input.trim_end_matches(['\r', '\n']).len());
+
+    Ok(input)
+}
+
+fn default_input_path(input_dir: &Path, day: u8) -> PathBuf {
+    input_dir.join(format!("day{}.txt", day))
+}
+
+fn print_day_run(day_run: &DayRun) {
+    for part_run in &day_run.parts {
+        println!(
+            "day {} - {}: {}",
+            day_run.day, part_run.part, part_run.answer
+        );
+    }
+}
+
+/// reads the input and runs the requested parts on it. returns the hash of
+/// the input together with the answers.
+fn solve_day(
+    solver: &dyn Solver,
+    input_path: &Path,
+    parts: &[Part],
+) -> Result<(String, DayRun), String> {
+    let input = read_input(input_path).map_err(|e| {
+        format!(
+            "failed to read input for day {} from {}: {}",
+            solver.day(),
+            input_path.display(),
+            e
+        )
+    })?;
+
+    let day_run = solver.run(&input, parts).map_err(|e| {
+        match std::error::Error::source(&e) {
+            Some(source) => format!("{}: {}", e, source),
+            None => e.to_string(),
+        }
+    })?;
+
+    Ok((input::hash(&input), day_run))
+}
+
+fn run_day(solver: &dyn Solver, input_path: &Path, parts: &[Part]) -> Result<(), String> {
+    let (_, day_run) = solve_day(solver, input_path, parts)?;
+    print_day_run(&day_run);
+    Ok(())
+}
+
+/// runs a day on its input while it's read, and prints the answers so far to
+/// stderr after every `progress` lines.
+fn stream_day(
+    mut stream: Box<dyn StreamSolver>,
+    input_path: &Path,
+    parts: &[Part],
+    progress: Option<usize>,
+) -> Result<(), String> {
+    let day = stream.day();
+    let read_error = |e: StreamError| {
+        format!(
+            "failed to stream input for day {} from {}: {}",
+            day,
+            input_path.display(),
+            error_chain(&e)
+        )
+    };
+
+    let mut reader: Box<dyn BufRead> = if input_path == Path::new("-") {
+        Box::new(io::stdin().lock())
+    }
+    else {
+        let file = fs::File::open(input_path).map_err(|e| read_error(e.into()))?;
+        Box::new(BufReader::with_capacity(1 << 16, file))
+    };
+
+    while !stream.read(&mut reader, progress).map_err(read_error)? {
+        // multi-line answers like the screen of day 10 would flood the log
+        let answers = parts
+            .iter()
+            .map(|part| {
+                match stream.answer(*part) {
+                    Ok(answer) if !answer.contains('\n') => format!("{}: {}", part, answer),
+                    Ok(_) => format!("{}: ...", part),
+                    Err(e) => format!("{}: {}", part, e),
+                }
+            })
+            .collect::<Vec<_>>();
+        eprintln!("line {}: {}", stream.lines(), answers.join(", "));
+    }
+
+    for part in parts {
+        let answer = stream
+            .answer(*part)
+            .map_err(|e| format!("day {} {}: {}", day, part, e))?;
+        println!("day {} - {}: {}", day, part, answer);
+    }
+
+    Ok(())
+}
+
+/// runs both parts of every day on `threads` threads. days whose input can't
+/// be read are reported as failed.
+fn run_all(input_dir: &Path, threads: Option<usize>) -> Result<Report, String> {
+    let solvers = solution::solvers();
+    let mut inputs = vec![];
+    let mut failures = vec![];
+
+    for solver in &solvers {
+        let input_path = default_input_path(input_dir, solver.day());
+        match read_input(&input_path) {
+            Ok(input) => inputs.push((solver.as_ref(), input)),
+            Err(e) => {
+                failures.push((
+                    solver.day(),
+                    format!("failed to read input from {}: {}", input_path.display(), e),
+                ))
+            }
+        }
+    }
+
+    let jobs = inputs
+        .iter()
+        .flat_map(|(solver, input)| {
+            Part::ALL.iter().map(move |part| {
+                Job {
+                    solver: *solver,
+                    part: *part,
+                    input,
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let mut report =
+        Report::run(&jobs, threads).map_err(|e| format!("failed to start the threads: {}", e))?;
+    for (day, error) in failures {
+        for part in Part::ALL {
+            report.add_failure(day, part, error.clone());
+        }
+    }
+
+    Ok(report)
+}
+
+/// returns the solver for `day`, or all solvers if it's `None`.
+fn select_solvers(day: Option<u8>) -> Result<Vec<Box<dyn Solver>>, RunError> {
+    match day {
+        Some(day) => Ok(vec![solution::solver(day)?]),
+        None => Ok(solution::solvers()),
+    }
+}
+
+/// number of answers per verdict.
+#[derive(Debug, Default)]
+struct VerifySummary {
+    passed: usize,
+    failed: usize,
+    missing: usize,
+    errors: usize,
+    skipped: usize,
+}
+
+/// compares the answers of `solvers` with the answers file and prints a
+/// verdict for every part. days without an input file are skipped.
+fn verify(solvers: &[Box<dyn Solver>], input_dir: &Path, answers: &Answers) -> VerifySummary {
+    let mut summary = VerifySummary::default();
+
+    for solver in solvers {
+        let input_path = default_input_path(input_dir, solver.day());
+        if !input_path.exists() {
+            println!("day {}: no input", solver.day());
+            summary.skipped += 1;
+            continue;
+        }
+
+        let (input_hash, day_run) = match solve_day(solver.as_ref(), &input_path, &Part::ALL) {
+            Ok(result) => result,
+            Err(e) => {
+                println!("day {}: ERROR", solver.day());
+                println!("    {}", e);
+                summary.errors += 1;
+                continue;
+            }
+        };
+
+        for part_run in &day_run.parts {
+            let verdict = answers.check(day_run.day, &input_hash, part_run.part, &part_run.answer);
+            println!("day {} - {}: {}", day_run.day, part_run.part, verdict);
+
+            match verdict {
+                Verdict::Pass => summary.passed += 1,
+                Verdict::Fail { expected } => {
+                    for line in answers::diff(&expected, &part_run.answer).lines() {
+                        println!("    {}", line);
+                    }
+                    summary.failed += 1;
+                }
+                Verdict::Missing => {
+                    println!("    answer: {}", part_run.answer);
+                    summary.missing += 1;
+                }
+            }
+        }
+    }
+
+    summary
+}
+
+/// runs `solvers` and stores their answers. answers that differ from already
+/// known ones are only replaced if `overwrite` is set, and days without an
+/// input file are skipped.
+///
+/// returns whether all days ran and no known answer was kept.
+fn record(
+    solvers: &[Box<dyn Solver>],
+    input_dir: &Path,
+    answers: &mut Answers,
+    overwrite: bool,
+) -> bool {
+    let mut success = true;
+
+    for solver in solvers {
+        let input_path = default_input_path(input_dir, solver.day());
+        if !input_path.exists() {
+            println!("day {}: no input", solver.day());
+            continue;
+        }
+
+        let (input_hash, day_run) = match solve_day(solver.as_ref(), &input_path, &Part::ALL) {
+            Ok(result) => result,
+            Err(e) => {
+                eprintln!("error: {}", e);
+                success = false;
+                continue;
+            }
+        };
+
+        for part_run in day_run.parts {
+            let prefix = format!("day {} - {}", day_run.day, part_run.part);
+
+            match answers.check(day_run.day, &input_hash, part_run.part, &part_run.answer) {
+                Verdict::Pass => println!("{}: unchanged", prefix),
+                Verdict::Fail { expected } if !overwrite => {
+                    println!(
+                        "{}: kept the known answer, use --overwrite to replace it",
+                        prefix
+                    );
+                    for line in answers::diff(&expected, &part_run.answer).lines() {
+                        println!("    {}", line);
+                    }
+                    success = false;
+                }
+                verdict => {
+                    let action = if verdict == Verdict::Missing {
+                        "recorded"
+                    }
+                    else {
+                        "replaced"
+                    };
+                    println!("{}: {} {}", prefix, action, part_run.answer);
+                    answers.insert(day_run.day, &input_hash, part_run.part, part_run.answer);
+                }
+            }
+        }
+    }
+
+    success
+}
+
+#[cfg(any(feature = "day9", feature = "day10", feature = "day14"))]
+#[cfg_attr(not(any(feature = "day9", feature = "day14")), allow(unused_variables))]
+fn render_day(
+    day: u8,
+    part: u8,
+    input_path: &Path,
+    animate: bool,
+    options: &RenderOptions,
+) -> Result<(), String> {
+    let input = read_input(input_path).map_err(|e| {
+        format!(
+            "failed to read input for day {} from {}: {}",
+            day,
+            input_path.display(),
+            e
+        )
+    })?;
+    let parse_error = |e: aoc2022::Error| format!("failed to parse input for day {}: {}", day, e);
+
+    let result = match day {
+        #[cfg(feature = "day9")]
+        9 => {
+            let movements = day9::day9_input(&input).map_err(parse_error)?;
+            let length = if part == 1 { 2 } else { 10 };
+            if animate {
+                options
+                    .frame_sink()
+                    .and_then(|mut sink| day9::animate_rope(length, &movements, sink.as_mut()))
+            }
+            else {
+                options.write_frame(&day9::simulate_rope_state(length, &movements).render())
+            }
+        }
+        #[cfg(feature = "day10")]
+        10 => {
+            let program = day10::day10_input(&input).map_err(parse_error)?;
+            if animate {
+                options
+                    .frame_sink()
+                    .and_then(|mut sink| day10::animate_screen(&program, sink.as_mut()))
+            }
+            else {
+                options.write_frame(&day10::run_screen(&program).render())
+            }
+        }
+        #[cfg(feature = "day14")]
+        14 => {
+            let rock_paths = day14::day14_input(&input).map_err(parse_error)?;
+            let floor = part == 2;
+            if animate {
+                options
+                    .frame_sink()
+                    .and_then(|mut sink| day14::animate_sand(&rock_paths, floor, sink.as_mut()))
+            }
+            else {
+                let mut sandbox = day14::Sandbox::from_rock_paths(&rock_paths, floor);
+                sandbox.fill();
+                options.write_frame(&sandbox.render())
+            }
+        }
+        _ => return Err(format!("day {} can't be rendered", day)),
+    };
+
+    result.map_err(|e| {
+        match std::error::Error::source(&e) {
+            Some(source) => format!("failed to render day {}: {}: {}", day, e, source),
+            None => format!("failed to render day {}: {}", day, e),
+        }
+    })
+}
+
+#[cfg(all(
+    feature = "tui",
+    any(feature = "day9", feature = "day10", feature = "day14")
+))]
+#[cfg_attr(not(any(feature = "day9", feature = "day14")), allow(unused_variables))]
+fn tui_day(day: u8, part: u8, input_path: &Path) -> Result<(), String> {
+    let input = read_input(input_path).map_err(|e| {
+        format!(
+            "failed to read input for day {} from {}: {}",
+            day,
+            input_path.display(),
+            e
+        )
+    })?;
+    let parse_error = |e: aoc2022::Error| format!("failed to parse input for day {}: {}", day, e);
+    let title = format!(" day {} - part {} ", day, part);
+
+    let result = match day {
+        #[cfg(feature = "day9")]
+        9 => {
+            let movements = day9::day9_input(&input).map_err(parse_error)?;
+            let length = if part == 1 { 2 } else { 10 };
+            tui::run(title, day9::RopeSimulation::new(length, movements))
+        }
+        #[cfg(feature = "day10")]
+        10 => {
+            let program = day10::day10_input(&input).map_err(parse_error)?;
+            tui::run(title, day10::CpuSimulation::new(program))
+        }
+        #[cfg(feature = "day14")]
+        14 => {
+            let rock_paths = day14::day14_input(&input).map_err(parse_error)?;
+            tui::run(
+                title,
+                day14::Sandbox::from_rock_paths(&rock_paths, part == 2),
+            )
+        }
+        _ => return Err(format!("day {} can't be played", day)),
+    };
+
+    result.map_err(|e| format!("failed to run the terminal: {}", e))
+}
+
+#[cfg(any(
+    feature = "day5",
+    feature = "day9",
+    feature = "day10",
+    feature = "day11",
+    feature = "day14"
+))]
+/// takes up to `steps` steps of the simulation in `checkpoint`, or runs it
+/// until it's finished, and saves it to `output` if given. unfinished
+/// simulations are always saved, to `output` or back to `path`.
+fn resume(
+    mut checkpoint: Checkpoint,
+    path: &Path,
+    steps: Option<usize>,
+    output: Option<&Path>,
+) -> Result<(), String> {
+    let prefix = format!("day {} - {}", checkpoint.day(), checkpoint.part());
+
+    checkpoint
+        .run(steps)
+        .map_err(|e| format!("failed to simulate {}: {}", prefix, error_chain(&e)))?;
+
+    let output = match (output, checkpoint.answer()) {
+        (Some(output), _) => Some(output),
+        (None, None) => Some(path),
+        (None, Some(_)) => None,
+    };
+    if let Some(output) = output {
+        checkpoint.save(output).map_err(|e| {
+            format!(
+                "failed to save checkpoint to {}: {}",
+                output.display(),
+                error_chain(&e)
+            )
+        })?;
+        println!(
+            "{}: saved after {} steps to {}",
+            prefix,
+            checkpoint.steps(),
+            output.display()
+        );
+    }
+
+    if let Some(answer) = checkpoint.answer() {
+        println!("{}: {}", prefix, answer);
+    }
+
+    Ok(())
+}
+
+fn extract_examples(
+    day: u8,
+    page: &Path,
+    fixtures_dir: &Path,
+    overwrite: bool,
+) -> Result<(), String> {
+    let html = fs::read_to_string(page)
+        .map_err(|e| format!("failed to read page from {}: {}", page.display(), e))?;
+    let fixture = Fixture::extract(&html);
+    if fixture.examples.is_empty() {
+        return Err(format!("found no examples in {}", page.display()));
+    }
+
+    let path = Fixture::path(fixtures_dir, day);
+    if path.exists() && !overwrite {
+        return Err(format!(
+            "{} already exists, use --overwrite to replace it",
+            path.display()
+        ));
+    }
+    fixture.save(&path).map_err(|e| {
+        format!(
+            "failed to save fixture to {}: {}",
+            path.display(),
+            error_chain(&e)
+        )
+    })?;
+
+    for (i, example) in fixture.examples.iter().enumerate() {
+        let answers = Part::ALL
+            .iter()
+            .filter_map(|part| Some(format!("{}: {}", part, example.answer(*part)?)))
+            .collect::<Vec<_>>();
+        println!(
+            "example {}: {} lines, {}",
+            i + 1,
+            example.input.lines().count(),
+            if answers.is_empty() {
+                "no answers".to_owned()
+            }
+            else {
+                answers.join(", ")
+            }
+        );
+    }
+    println!("saved to {}", path.display());
+
+    Ok(())
+}
+
+fn fetch(client: &Client, solvers: &[Box<dyn Solver>], refresh: bool) -> bool {
+    let mut success = true;
+
+    for solver in solvers {
+        match client.fetch_input(solver.day(), refresh) {
+            Ok(_) => {
+                println!(
+                    "day {}: {}",
+                    solver.day(),
+                    client.input_path(solver.day()).display()
+                )
+            }
+            Err(e) => {
+                eprintln!(
+                    "error: failed to fetch input for day {}: {}",
+                    solver.day(),
+                    error_chain(&e)
+                );
+                success = false;
+            }
+        }
+    }
+
+    success
+}
+
+/// submits an answer, or the answer of the solver if `answer` is `None`.
+/// returns whether the part is solved.
+fn submit(
+    client: &Client,
+    day: u8,
+    part: Part,
+    answer: Option<String>,
+    wait: bool,
+) -> Result<bool, String> {
+    let answer = match answer {
+        Some(answer) => answer,
+        None => {
+            let solver = solution::solver(day).map_err(|e| e.to_string())?;
+            let input = client.fetch_input(day, false).map_err(|e| {
+                format!("failed to fetch input for day {}: {}", day, error_chain(&e))
+            })?;
+            let day_run = solver
+                .run(input.trim_end_matches('\n'), &[part])
+                .map_err(|e| error_chain(&e))?;
+            let answer = day_run.parts[0].answer.clone();
+            if answer.contains('\n') {
+                return Err(
+                    "the answer has to be read from the screen, pass it with --answer".to_owned(),
+                );
+            }
+            answer
+        }
+    };
+
+    let prefix = format!("day {} - {}", day, part);
+    let result = client
+        .submit(day, part, &answer, wait)
+        .map_err(|e| format!("failed to submit {}: {}", answer, error_chain(&e)))?;
+
+    match result {
+        SubmitResult::Submitted(submission) => {
+            println!(
+                "{}: {} is {}",
+                prefix, submission.answer, submission.outcome
+            );
+            println!("    {}", submission.message);
+            Ok(submission.outcome == SubmitOutcome::Correct)
+        }
+        SubmitResult::Known(submission) if submission.outcome == SubmitOutcome::Correct => {
+            println!("{}: already solved with {}", prefix, submission.answer);
+            Ok(submission.answer == answer)
+        }
+        SubmitResult::Known(submission) => {
+            println!(
+                "{}: {} was already submitted and is {}",
+                prefix, submission.answer, submission.outcome
+            );
+            Ok(false)
+        }
+        SubmitResult::Wait(wait_time) => {
+            println!(
+                "{}: have to wait {}s before submitting again, use --wait to wait",
+                prefix,
+                wait_time.as_secs()
+            );
+            Ok(false)
+        }
+    }
+}
+
+/// sends log events to stderr, so that only answers end up on stdout.
+fn init_logging(verbose: u8, log: Option<&str>) -> Result<(), String> {
+    let level = match verbose {
+        0 => "warn",
+        1 => "info",
+        2 => "debug",
+        _ => "trace",
+    };
+    let directives = match log {
+        Some(log) => format!("{},{}", level, log),
+        None => level.to_owned(),
+    };
+    let filter =
+        EnvFilter::try_new(directives).map_err(|e| format!("invalid log filter: {}", e))?;
+
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(io::stderr)
+        .with_ansi(io::stderr().is_terminal())
+        .init();
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+
+    if let Err(e) = init_logging(args.verbose, args.log.as_deref()) {
+        eprintln!("error: {}", e);
+        return ExitCode::FAILURE;
+    }
+
+    match args.command {
+        Command::Run {
+            day,
+            part,
+            input,
+            input_dir,
+        } => {
+            let solver = match solution::solver(day) {
+                Ok(solver) => solver,
+                Err(e) => {
+                    eprintln!("error: {}", e);
+                    return ExitCode::FAILURE;
+                }
+            };
+            let parts = match part.and_then(Part::from_number) {
+                Some(part) => vec![part],
+                None => Part::ALL.to_vec(),
+            };
+            let input = input.unwrap_or_else(|| default_input_path(&input_dir, day));
+
+            if let Err(e) = run_day(solver.as_ref(), &input, &parts) {
+                eprintln!("error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+        Command::Stream {
+            day,
+            part,
+            input,
+            input_dir,
+            progress,
+        } => {
+            let stream = match stream::solver(day) {
+                Ok(stream) => stream,
+                Err(e) => {
+                    eprintln!("error: {}", e);
+                    return ExitCode::FAILURE;
+                }
+            };
+            let parts = match part.and_then(Part::from_number) {
+                Some(part) => vec![part],
+                None => Part::ALL.to_vec(),
+            };
+            let input = input.unwrap_or_else(|| default_input_path(&input_dir, day));
+
+            if let Err(e) = stream_day(stream, &input, &parts, progress.filter(|n| *n > 0)) {
+                eprintln!("error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+        Command::RunAll {
+            input_dir,
+            jobs,
+            format,
+            output,
+        } => {
+            let report = match run_all(&input_dir, jobs) {
+                Ok(report) => report,
+                Err(e) => {
+                    eprintln!("error: {}", e);
+                    return ExitCode::FAILURE;
+                }
+            };
+
+            let text = match format {
+                ReportFormat::Table => format!("{}\n", report),
+                ReportFormat::Json => {
+                    let json =
+                        serde_json::to_string_pretty(&report).expect("reports are valid JSON");
+                    format!("{}\n", json)
+                }
+                ReportFormat::Markdown => report.markdown(),
+                ReportFormat::Junit => report.junit(),
+            };
+            match output {
+                Some(output) => {
+                    if let Err(e) = fs::write(&output, text) {
+                        eprintln!(
+                            "error: failed to write report to {}: {}",
+                            output.display(),
+                            e
+                        );
+                        return ExitCode::FAILURE;
+                    }
+                }
+                None => print!("{}", text),
+            }
+
+            if !report.all_solved() {
+                return ExitCode::FAILURE;
+            }
+        }
+        #[cfg(any(feature = "day9", feature = "day10", feature = "day14"))]
+        Command::Render {
+            day,
+            part,
+            input,
+            input_dir,
+            output,
+            animate,
+            frame_format,
+            scale,
+            delay,
+        } => {
+            let input = input.unwrap_or_else(|| default_input_path(&input_dir, day));
+            let options = RenderOptions {
+                output,
+                frame_format: frame_format.into(),
+                scale,
+                delay,
+            };
+
+            if let Err(e) = render_day(day, part, &input, animate, &options) {
+                eprintln!("error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+        #[cfg(all(
+            feature = "tui",
+            any(feature = "day9", feature = "day10", feature = "day14")
+        ))]
+        Command::Tui {
+            day,
+            part,
+            input,
+            input_dir,
+        } => {
+            let input = input.unwrap_or_else(|| default_input_path(&input_dir, day));
+            if let Err(e) = tui_day(day, part, &input) {
+                eprintln!("error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+        #[cfg(any(
+            feature = "day5",
+            feature = "day9",
+            feature = "day10",
+            feature = "day11",
+            feature = "day14"
+        ))]
+        Command::Checkpoint {
+            day,
+            part,
+            input,
+            input_dir,
+            steps,
+            output,
+        } => {
+            let part = Part::from_number(part).expect("part is checked by clap");
+            let input_path = input.unwrap_or_else(|| default_input_path(&input_dir, day));
+            let input = match read_input(&input_path) {
+                Ok(input) => input,
+                Err(e) => {
+                    eprintln!(
+                        "error: failed to read input for day {} from {}: {}",
+                        day,
+                        input_path.display(),
+                        e
+                    );
+                    return ExitCode::FAILURE;
+                }
+            };
+            let checkpoint = match Checkpoint::start(day, part, &input) {
+                Ok(checkpoint) => checkpoint,
+                Err(e) => {
+                    eprintln!("error: {}", error_chain(&e));
+                    return ExitCode::FAILURE;
+                }
+            };
+
+            if let Err(e) = resume(checkpoint, &output, Some(steps), Some(&output)) {
+                eprintln!("error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+        #[cfg(any(
+            feature = "day5",
+            feature = "day9",
+            feature = "day10",
+            feature = "day11",
+            feature = "day14"
+        ))]
+        Command::Resume {
+            checkpoint: path,
+            steps,
+            output,
+        } => {
+            let checkpoint = match Checkpoint::load(&path) {
+                Ok(checkpoint) => checkpoint,
+                Err(e) => {
+                    eprintln!(
+                        "error: failed to load checkpoint from {}: {}",
+                        path.display(),
+                        error_chain(&e)
+                    );
+                    return ExitCode::FAILURE;
+                }
+            };
+
+            if let Err(e) = resume(checkpoint, &path, steps, output.as_deref()) {
+                eprintln!("error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+        Command::Generate {
+            day,
+            seed,
+            size,
+            output,
+        } => {
+            let input = match generate::generate(day, seed, size) {
+                Ok(input) => input,
+                Err(e) => {
+                    eprintln!("error: {}", e);
+                    return ExitCode::FAILURE;
+                }
+            };
+
+            let result = match &output {
+                Some(path) => fs::write(path, format!("{}\n", input)),
+                None => writeln!(io::stdout(), "{}", input),
+            };
+            if let Err(e) = result {
+                eprintln!("error: failed to write input: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+        Command::Verify {
+            day,
+            input_dir,
+            answers,
+        } => {
+            let solvers = match select_solvers(day) {
+                Ok(solvers) => solvers,
+                Err(e) => {
+                    eprintln!("error: {}", e);
+                    return ExitCode::FAILURE;
+                }
+            };
+            let answers = match Answers::load(&answers) {
+                Ok(answers) => answers,
+                Err(e) => {
+                    eprintln!("error: failed to load {}: {}", answers.display(), e);
+                    return ExitCode::FAILURE;
+                }
+            };
+
+            let summary = verify(&solvers, &input_dir, &answers);
+            println!(
+                "{} passed, {} failed, {} missing, {} errors, {} days without input",
+                summary.passed, summary.failed, summary.missing, summary.errors, summary.skipped
+            );
+
+            if summary.failed > 0 || summary.errors > 0 {
+                return ExitCode::FAILURE;
+            }
+        }
+        Command::Record {
+            day,
+            input_dir,
+            answers: answers_path,
+            overwrite,
+        } => {
+            let solvers = match select_solvers(day) {
+                Ok(solvers) => solvers,
+                Err(e) => {
+                    eprintln!("error: {}", e);
+                    return ExitCode::FAILURE;
+                }
+            };
+            let mut answers = match Answers::load(&answers_path) {
+                Ok(answers) => answers,
+                Err(e) => {
+                    eprintln!("error: failed to load {}: {}", answers_path.display(), e);
+                    return ExitCode::FAILURE;
+                }
+            };
+
+            let success = record(&solvers, &input_dir, &mut answers, overwrite);
+
+            if let Err(e) = answers.save(&answers_path) {
+                eprintln!("error: failed to save {}: {}", answers_path.display(), e);
+                return ExitCode::FAILURE;
+            }
+            if !success {
+                return ExitCode::FAILURE;
+            }
+        }
+        Command::Fetch {
+            day,
+            refresh,
+            input_dir,
+            server,
+        } => {
+            let solvers = match select_solvers(day) {
+                Ok(solvers) => solvers,
+                Err(e) => {
+                    eprintln!("error: {}", e);
+                    return ExitCode::FAILURE;
+                }
+            };
+            let client = Client::new(&server.base_url, &server.session, input_dir, "");
+
+            if !fetch(&client, &solvers, refresh) {
+                return ExitCode::FAILURE;
+            }
+        }
+        Command::Submit {
+            day,
+            part,
+            answer,
+            wait,
+            input_dir,
+            history,
+            server,
+        } => {
+            let part = Part::from_number(part).expect("part is checked by clap");
+            let client = Client::new(&server.base_url, &server.session, input_dir, history);
+
+            match submit(&client, day, part, answer, wait) {
+                Ok(true) => {}
+                Ok(false) => return ExitCode::FAILURE,
+                Err(e) => {
+                    eprintln!("error: {}", e);
+                    return ExitCode::FAILURE;
+                }
+            }
+        }
+        Command::NewDay {
+            day,
+            src_dir,
+            input_dir,
+            answers,
+        } => {
+            let scaffold = Scaffold {
+                src_dir,
+                input_dir,
+                answers_path: answers,
+            };
+
+            match scaffold.new_day(day) {
+                Ok(new_day) => {
+                    for path in new_day.created {
+                        println!("created {}", path.display());
+                    }
+                    for path in new_day.updated {
+                        println!("updated {}", path.display());
+                    }
+                }
+                Err(e) => {
+                    eprintln!("error: {}", error_chain(&e));
+                    return ExitCode::FAILURE;
+                }
+            }
+        }
+        Command::ExtractExamples {
+            day,
+            page,
+            fixtures_dir,
+            overwrite,
+        } => {
+            if let Err(e) = extract_examples(day, &page, &fixtures_dir, overwrite) {
+                eprintln!("error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+        Command::List => {
+            for solver in solution::solvers() {
+                println!("day {}", solver.day());
+            }
+        }
+    }
+
+    ExitCode::SUCCESS
+}
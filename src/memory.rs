@@ -0,0 +1,268 @@
+//! counts the allocations of the generators and solvers.
+//!
+//! with the `alloc-profile` feature, the binary installs [`CountingAllocator`]
+//! as its global allocator, and [`measure`] reports how often a closure
+//! allocated, how many bytes it allocated in total and how many of them were
+//! alive at most. without the feature, [`measure`] only runs the closure.
+//!
+//! the counters are per thread, so days running in parallel don't see each
+//! other's allocations. allocations on other threads that a closure spawns
+//! aren't counted.
+
+use std::fmt;
+
+use serde::Serialize;
+
+/// the allocations of a measured closure.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct AllocStats {
+    /// the number of allocations, including reallocations.
+    pub allocations: u64,
+    /// the bytes requested by all allocations. a reallocation counts with its
+    /// new size.
+    pub bytes: u64,
+    /// the most bytes that were allocated at the same time, not counting
+    /// memory that was allocated before the closure started.
+    pub peak_bytes: u64,
+}
+
+impl fmt::Display for AllocStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} allocations, {} allocated, {} peak",
+            self.allocations,
+            Bytes(self.bytes),
+            Bytes(self.peak_bytes)
+        )
+    }
+}
+
+/// formats a number of bytes with a binary prefix, e.g. `1.50KiB`.
+pub struct Bytes(pub u64);
+
+impl fmt::Display for Bytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+        if self.0 < 1024 {
+            return write!(f, "{}B", self.0);
+        }
+
+        let mut value = self.0 as f64 / 1024.0;
+        let mut unit = 0;
+        while value >= 1024.0 && unit < UNITS.len() - 1 {
+            value /= 1024.0;
+            unit += 1;
+        }
+        write!(f, "{:.2}{}", value, UNITS[unit])
+    }
+}
+
+/// runs `f` and returns its allocations, or `None` if they aren't counted.
+#[cfg(feature = "alloc-profile")]
+pub fn measure<R>(f: impl FnOnce() -> R) -> (R, Option<AllocStats>) {
+    let outer = counting::take();
+    let result = f();
+    let inner = counting::restore(outer);
+
+    let stats = counting::is_installed().then(|| {
+        AllocStats {
+            allocations: inner.allocations,
+            bytes: inner.bytes,
+            peak_bytes: inner.peak.max(0) as u64,
+        }
+    });
+    (result, stats)
+}
+
+/// runs `f` and returns its allocations, or `None` if they aren't counted.
+#[cfg(not(feature = "alloc-profile"))]
+pub fn measure<R>(f: impl FnOnce() -> R) -> (R, Option<AllocStats>) {
+    (f(), None)
+}
+
+#[cfg(feature = "alloc-profile")]
+pub use counting::CountingAllocator;
+
+#[cfg(feature = "alloc-profile")]
+mod counting {
+    use std::{
+        alloc::{
+            GlobalAlloc,
+            Layout,
+            System,
+        },
+        cell::Cell,
+        sync::atomic::{
+            AtomicBool,
+            Ordering,
+        },
+    };
+
+    static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+    /// the allocations of a thread since the current measurement started.
+    #[derive(Clone, Copy, Default)]
+    pub struct Counters {
+        pub allocations: u64,
+        pub bytes: u64,
+        /// the bytes allocated minus the bytes freed. memory that was
+        /// allocated before and freed during the measurement makes this
+        /// negative.
+        pub current: i64,
+        pub peak: i64,
+    }
+
+    thread_local! {
+        static COUNTERS: Cell<Counters> = const {
+            Cell::new(Counters {
+                allocations: 0,
+                bytes: 0,
+                current: 0,
+                peak: 0,
+            })
+        };
+    }
+
+    /// a global allocator that counts the allocations of every thread and
+    /// passes them on to the system allocator.
+    pub struct CountingAllocator;
+
+    fn update(f: impl FnOnce(&mut Counters)) {
+        // the counters are gone while a thread shuts down
+        let _ = COUNTERS.try_with(|counters| {
+            let mut value = counters.get();
+            f(&mut value);
+            counters.set(value);
+        });
+    }
+
+    fn allocated(bytes: usize) {
+        INSTALLED.store(true, Ordering::Relaxed);
+        update(|counters| {
+            counters.allocations += 1;
+            counters.bytes += bytes as u64;
+            counters.current += bytes as i64;
+            counters.peak = counters.peak.max(counters.current);
+        });
+    }
+
+    fn freed(bytes: usize) {
+        update(|counters| counters.current -= bytes as i64);
+    }
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let ptr = unsafe { System.alloc(layout) };
+            if !ptr.is_null() {
+                allocated(layout.size());
+            }
+            ptr
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            let ptr = unsafe { System.alloc_zeroed(layout) };
+            if !ptr.is_null() {
+                allocated(layout.size());
+            }
+            ptr
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            unsafe { System.dealloc(ptr, layout) };
+            freed(layout.size());
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            let new_ptr = unsafe { System.realloc(ptr, layout, new_size) };
+            if !new_ptr.is_null() {
+                freed(layout.size());
+                allocated(new_size);
+            }
+            new_ptr
+        }
+    }
+
+    /// whether the counting allocator is the global allocator.
+    pub fn is_installed() -> bool {
+        INSTALLED.load(Ordering::Relaxed)
+    }
+
+    /// starts a new measurement, and returns the counters of the measurement
+    /// it interrupts.
+    pub fn take() -> Counters {
+        COUNTERS.with(|counters| counters.replace(Counters::default()))
+    }
+
+    /// ends the current measurement, and continues `outer` with the
+    /// allocations it made. returns the counters of the ended measurement.
+    pub fn restore(outer: Counters) -> Counters {
+        COUNTERS.with(|counters| {
+            let inner = counters.get();
+            counters.set(Counters {
+                allocations: outer.allocations + inner.allocations,
+                bytes: outer.bytes + inner.bytes,
+                current: outer.current + inner.current,
+                peak: outer.peak.max(outer.current + inner.peak),
+            });
+            inner
+        })
+    }
+}
+
+/// the tests of the library run with the counting allocator, so that
+/// [`measure`] can be tested.
+#[cfg(all(test, feature = "alloc-profile"))]
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bytes() {
+        assert_eq!(Bytes(0).to_string(), "0B");
+        assert_eq!(Bytes(1023).to_string(), "1023B");
+        assert_eq!(Bytes(1536).to_string(), "1.50KiB");
+        assert_eq!(Bytes(3 << 30).to_string(), "3.00GiB");
+    }
+
+    #[cfg(feature = "alloc-profile")]
+    #[test]
+    fn measure_allocations() {
+        let (_, stats) = measure(|| {
+            let mut outer = Vec::<u8>::with_capacity(1000);
+            let (_, inner) = measure(|| {
+                let inner = vec![0u8; 4000];
+                drop(inner);
+            });
+            assert_eq!(
+                inner,
+                Some(AllocStats {
+                    allocations: 1,
+                    bytes: 4000,
+                    peak_bytes: 4000,
+                })
+            );
+            outer.push(1);
+            drop(outer);
+        });
+
+        assert_eq!(
+            stats,
+            Some(AllocStats {
+                allocations: 2,
+                bytes: 5000,
+                peak_bytes: 5000,
+            })
+        );
+    }
+
+    #[cfg(not(feature = "alloc-profile"))]
+    #[test]
+    fn measure_without_allocator() {
+        assert_eq!(measure(|| vec![1, 2, 3].len()), (3, None));
+    }
+}
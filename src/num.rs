@@ -0,0 +1,107 @@
+//! numbers for solvers that work with any integer type.
+//!
+//! the days that only add up or compare numbers from the input are generic
+//! over [`Number`], so the same code runs on `u64`, `i128` or `BigInt` for
+//! inputs that are larger than usual. arithmetic is checked, and results that
+//! don't fit into the chosen type are reported as [`Error::Overflow`] instead
+//! of wrapping around in release builds.
+//!
+//! days that work with positions use
+//! [`Coordinate`](crate::geometry::Coordinate) instead, which is limited to
+//! the primitive integers.
+
+use std::{
+    any,
+    fmt,
+    str::FromStr,
+};
+
+use num_traits::{
+    CheckedAdd,
+    CheckedMul,
+    CheckedSub,
+    One,
+    Zero,
+};
+
+use crate::error::Error;
+
+/// an integer type that the generic solvers can compute with.
+pub trait Number:
+    Clone
+    + Ord
+    + fmt::Debug
+    + fmt::Display
+    + FromStr
+    + Zero
+    + One
+    + CheckedAdd
+    + CheckedSub
+    + CheckedMul
+{
+}
+
+impl<T> Number for T where
+    T: Clone
+        + Ord
+        + fmt::Debug
+        + fmt::Display
+        + FromStr
+        + Zero
+        + One
+        + CheckedAdd
+        + CheckedSub
+        + CheckedMul
+{
+}
+
+/// returns the name of `T` without its path, e.g. `u32` or `BigInt`.
+pub fn type_name<T>() -> &'static str {
+    let name = any::type_name::<T>();
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+/// creates an error for a `what` that doesn't fit into `T`.
+pub fn overflow<T>(what: impl fmt::Display) -> Error {
+    Error::Overflow(format!("{} doesn't fit into {}", what, type_name::<T>()))
+}
+
+/// adds up `values`, or returns an error if the sum doesn't fit into `T`.
+///
+/// `what` describes the sum in the error, e.g. "the calories of elf 3".
+pub fn checked_sum<'a, T: Number + 'a>(
+    values: impl IntoIterator<Item = &'a T>,
+    what: impl fmt::Display,
+) -> Result<T, Error> {
+    values
+        .into_iter()
+        .try_fold(T::zero(), |sum, value| sum.checked_add(value))
+        .ok_or_else(|| overflow::<T>(what))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sum() {
+        assert_eq!(checked_sum(&[100u8, 155], "sum").unwrap(), 255);
+        assert_eq!(checked_sum(&[] as &[u8], "sum").unwrap(), 0);
+
+        let error = checked_sum(&[100u8, 156], "the sum").unwrap_err();
+        assert_eq!(error.to_string(), "overflow: the sum doesn't fit into u8");
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn bigint() {
+        use num_bigint::BigInt;
+
+        let values = [BigInt::from(u128::MAX), BigInt::from(u128::MAX)];
+        assert_eq!(
+            checked_sum(&values, "sum").unwrap(),
+            BigInt::from(u128::MAX) * 2
+        );
+        assert_eq!(type_name::<BigInt>(), "BigInt");
+    }
+}
@@ -0,0 +1,479 @@
+//! python bindings, built with [maturin](https://www.maturin.rs) from
+//! `pyproject.toml`.
+//!
+//! the extension module `aoc2022` has a submodule for every enabled day, with
+//! the generator as `parse` and the parts as `part1` and `part2`. `parse`
+//! returns an opaque `Input`, except for the days whose inputs are worth
+//! exploring on their own:
+//!
+//! - `day10.Cpu` runs instructions one at a time
+//! - `day11.Monkeys` plays rounds of keep away
+//! - `day12.HeightMap` has the elevations and the paths through them
+//! - `day15.Sensors` has the positions the sensors cover
+//!
+//! positions are `(x, y)` tuples, and errors of the generators and solvers
+//! are raised as `aoc2022.AocError`, which is a `ValueError`.
+
+#[cfg(feature = "day12")]
+use pyo3::exceptions::PyIndexError;
+use pyo3::{
+    create_exception,
+    exceptions::PyValueError,
+    prelude::*,
+};
+
+#[cfg(any(feature = "day12", feature = "day15"))]
+use crate::geometry::Point2;
+#[cfg(any(
+    feature = "day1",
+    feature = "day2",
+    feature = "day3",
+    feature = "day4",
+    feature = "day5",
+    feature = "day6",
+    feature = "day7",
+    feature = "day8",
+    feature = "day9",
+    feature = "day10",
+    feature = "day11",
+    feature = "day12",
+    feature = "day13",
+    feature = "day14",
+    feature = "day15"
+))]
+use crate::solution::Solution;
+use crate::{
+    error::{
+        error_chain,
+        Error,
+    },
+    runner::Part,
+    solution,
+};
+
+create_exception!(
+    aoc2022,
+    AocError,
+    PyValueError,
+    "an error of a generator or solver."
+);
+
+impl From<Error> for PyErr {
+    fn from(error: Error) -> Self {
+        AocError::new_err(error.to_string())
+    }
+}
+
+/// converts a position to a python tuple.
+#[cfg(any(feature = "day12", feature = "day15"))]
+fn xy<T>(position: Point2<T>) -> (T, T) {
+    (position.x, position.y)
+}
+
+/// adds `module` as a submodule of `parent`, so that it can be imported as
+/// `aoc2022.<name>` too.
+#[cfg(any(
+    feature = "day1",
+    feature = "day2",
+    feature = "day3",
+    feature = "day4",
+    feature = "day5",
+    feature = "day6",
+    feature = "day7",
+    feature = "day8",
+    feature = "day9",
+    feature = "day10",
+    feature = "day11",
+    feature = "day12",
+    feature = "day13",
+    feature = "day14",
+    feature = "day15"
+))]
+fn add_submodule(parent: &Bound<'_, PyModule>, module: &Bound<'_, PyModule>) -> PyResult<()> {
+    parent.add_submodule(module)?;
+    parent
+        .py()
+        .import("sys")?
+        .getattr("modules")?
+        .set_item(format!("aoc2022.{}", module.name()?), module)
+}
+
+/// creates the submodule of a day, named `$name`. the parsed input is wrapped
+/// in `$input`, or in an opaque `Input` if it's omitted, and `$class`es are
+/// added to the submodule.
+#[cfg(any(
+    feature = "day1",
+    feature = "day2",
+    feature = "day3",
+    feature = "day4",
+    feature = "day5",
+    feature = "day6",
+    feature = "day7",
+    feature = "day8",
+    feature = "day9",
+    feature = "day10",
+    feature = "day11",
+    feature = "day12",
+    feature = "day13",
+    feature = "day14",
+    feature = "day15"
+))]
+macro_rules! day_module {
+    ($module:ident, $name:tt, $solution:ty $(, class: $class:ty)*) => {
+        mod $module {
+            use super::*;
+
+            /// the parsed puzzle input.
+            #[pyclass(module = $name, unsendable)]
+            pub struct Input(<$solution as Solution>::Input);
+
+            day_module!(@functions $solution, Input $(, $class)*);
+        }
+    };
+    ($module:ident, $name:tt, $solution:ty, input: $input:ident $(, class: $class:ty)*) => {
+        mod $module {
+            use super::*;
+
+            day_module!(@functions $solution, $input $(, $class)*);
+        }
+    };
+    (@functions $solution:ty, $input:ident $(, $class:ty)*) => {
+        /// parses the puzzle input.
+        #[pyfunction]
+        fn parse(input: &str) -> PyResult<$input> {
+            Ok($input(<$solution as Solution>::parse(input)?))
+        }
+
+        #[pyfunction]
+        fn part1(input: &$input) -> PyResult<<$solution as Solution>::Part1> {
+            Ok(<$solution as Solution>::part1(&input.0)?)
+        }
+
+        #[pyfunction]
+        fn part2(input: &$input) -> PyResult<<$solution as Solution>::Part2> {
+            Ok(<$solution as Solution>::part2(&input.0)?)
+        }
+
+        pub fn register(parent: &Bound<'_, PyModule>) -> PyResult<()> {
+            let module = PyModule::new(parent.py(), &format!("day{}", <$solution as Solution>::DAY))?;
+            module.add_class::<$input>()?;
+            $(module.add_class::<$class>()?;)*
+            module.add_function(wrap_pyfunction!(parse, &module)?)?;
+            module.add_function(wrap_pyfunction!(part1, &module)?)?;
+            module.add_function(wrap_pyfunction!(part2, &module)?)?;
+            add_submodule(parent, &module)
+        }
+    };
+}
+
+#[cfg(feature = "day1")]
+day_module!(day1, "aoc2022.day1", crate::day1::Day1);
+#[cfg(feature = "day2")]
+day_module!(day2, "aoc2022.day2", crate::day2::Day2);
+#[cfg(feature = "day3")]
+day_module!(day3, "aoc2022.day3", crate::day3::Day3);
+#[cfg(feature = "day4")]
+day_module!(day4, "aoc2022.day4", crate::day4::Day4);
+#[cfg(feature = "day5")]
+day_module!(day5, "aoc2022.day5", crate::day5::Day5);
+#[cfg(feature = "day6")]
+day_module!(day6, "aoc2022.day6", crate::day6::Day6);
+#[cfg(feature = "day7")]
+day_module!(day7, "aoc2022.day7", crate::day7::Day7);
+#[cfg(feature = "day8")]
+day_module!(day8, "aoc2022.day8", crate::day8::Day8);
+#[cfg(feature = "day9")]
+day_module!(day9, "aoc2022.day9", crate::day9::Day9);
+#[cfg(feature = "day10")]
+day_module!(day10, "aoc2022.day10", crate::day10::Day10, class: Cpu);
+#[cfg(feature = "day11")]
+day_module!(day11, "aoc2022.day11", crate::day11::Day11, input: Monkeys);
+#[cfg(feature = "day12")]
+day_module!(day12, "aoc2022.day12", crate::day12::Day12, input: HeightMap);
+#[cfg(feature = "day13")]
+day_module!(day13, "aoc2022.day13", crate::day13::Day13);
+#[cfg(feature = "day14")]
+day_module!(day14, "aoc2022.day14", crate::day14::Day14);
+#[cfg(feature = "day15")]
+day_module!(day15, "aoc2022.day15", crate::day15::Day15, input: Sensors);
+
+/// the cpu of day 10, running one instruction at a time.
+#[cfg(feature = "day10")]
+#[pyclass(module = "aoc2022.day10")]
+#[derive(Default)]
+pub struct Cpu(crate::day10::Cpu);
+
+#[cfg(feature = "day10")]
+#[pymethods]
+impl Cpu {
+    #[new]
+    fn new() -> Self {
+        Self::default()
+    }
+
+    /// runs an instruction like `addx 3` or `noop`.
+    fn execute(&mut self, instruction: &str) -> PyResult<()> {
+        self.0
+            .run_instruction(instruction.parse::<crate::day10::Instruction>()?);
+        Ok(())
+    }
+
+    /// runs every line of `program`.
+    fn run(&mut self, program: &str) -> PyResult<()> {
+        for instruction in crate::day10::day10_input(program)? {
+            self.0.run_instruction(instruction);
+        }
+        Ok(())
+    }
+
+    /// the cycle of the next instruction.
+    #[getter]
+    fn cycle(&self) -> u64 {
+        self.0.cycle()
+    }
+
+    #[getter]
+    fn x(&self) -> i64 {
+        self.0.x_register()
+    }
+
+    /// the sum of the signal strengths sampled so far.
+    #[getter]
+    fn signal_strength(&self) -> i64 {
+        self.0.signal_strength()
+    }
+
+    /// the screen, with `#` for lit pixels and `.` for dark ones.
+    #[getter]
+    fn screen(&self) -> String {
+        self.0.frame_buffer().to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Cpu(cycle={}, x={})", self.0.cycle(), self.0.x_register())
+    }
+}
+
+/// the monkeys of day 11. `part1` and `part2` start from the current round.
+#[cfg(feature = "day11")]
+#[pyclass(module = "aoc2022.day11")]
+pub struct Monkeys(crate::day11::Monkeys);
+
+#[cfg(feature = "day11")]
+#[pymethods]
+impl Monkeys {
+    fn __len__(&self) -> usize {
+        self.0.monkeys().len()
+    }
+
+    /// plays a round. with `relief`, worry levels are divided by 3 after every
+    /// inspection, as in part 1.
+    #[pyo3(signature = (relief = true))]
+    fn round(&mut self, relief: bool) {
+        self.0.round(relief);
+    }
+
+    /// the worry levels of the items of every monkey.
+    #[getter]
+    fn items(&self) -> Vec<Vec<num_bigint::BigInt>> {
+        self.0
+            .monkeys()
+            .iter()
+            .map(|monkey| monkey.items().to_vec())
+            .collect()
+    }
+
+    /// how often every monkey inspected an item.
+    #[getter]
+    fn inspect_counts(&self) -> Vec<usize> {
+        self.0
+            .monkeys()
+            .iter()
+            .map(|monkey| monkey.inspect_count())
+            .collect()
+    }
+
+    fn monkey_business(&self) -> usize {
+        self.0.monkey_business()
+    }
+}
+
+/// the height map of day 12.
+#[cfg(feature = "day12")]
+#[pyclass(module = "aoc2022.day12", frozen)]
+pub struct HeightMap(crate::day12::HeightMap);
+
+#[cfg(feature = "day12")]
+impl HeightMap {
+    fn position(&self, (x, y): (usize, usize)) -> PyResult<Point2<usize>> {
+        let position = Point2::new(x, y);
+        if self.0.elevations().contains(position) {
+            Ok(position)
+        }
+        else {
+            Err(PyIndexError::new_err(format!(
+                "({}, {}) is outside of the height map",
+                x, y
+            )))
+        }
+    }
+}
+
+#[cfg(feature = "day12")]
+#[pymethods]
+impl HeightMap {
+    #[getter]
+    fn width(&self) -> usize {
+        self.0.elevations().width()
+    }
+
+    #[getter]
+    fn height(&self) -> usize {
+        self.0.elevations().height()
+    }
+
+    #[getter]
+    fn start(&self) -> (usize, usize) {
+        xy(self.0.start_position())
+    }
+
+    #[getter]
+    fn best_signal(&self) -> (usize, usize) {
+        xy(self.0.best_signal())
+    }
+
+    /// the elevation at `position`, from 0 for `a` to 25 for `z`.
+    fn elevation(&self, position: (usize, usize)) -> PyResult<i32> {
+        Ok(self.0.get_height(self.position(position)?))
+    }
+
+    /// the positions from which `position` can be reached.
+    fn neighbors(&self, position: (usize, usize)) -> PyResult<Vec<(usize, usize)>> {
+        Ok(self.0.neighbors(self.position(position)?).map(xy).collect())
+    }
+
+    /// the shortest path from the start to the best signal, or `None`.
+    fn shortest_path_to_best_signal(&self) -> Option<Vec<(usize, usize)>> {
+        let path = self.0.shortest_path_to_best_signal()?;
+        Some(path.into_iter().map(xy).collect())
+    }
+
+    /// the shortest path from any lowest position to the best signal, or
+    /// `None`.
+    fn shortest_path_from_lowest_elevation(&self) -> Option<Vec<(usize, usize)>> {
+        let path = self.0.shortest_path_from_lowest_elevation()?;
+        Some(path.into_iter().map(xy).collect())
+    }
+}
+
+/// the sensors of day 15.
+#[cfg(feature = "day15")]
+#[pyclass(module = "aoc2022.day15", frozen)]
+pub struct Sensors(Vec<crate::day15::Sensor>);
+
+#[cfg(feature = "day15")]
+#[pymethods]
+impl Sensors {
+    fn __len__(&self) -> usize {
+        self.0.len()
+    }
+
+    /// the position and the closest beacon of every sensor.
+    #[getter]
+    fn sensors(&self) -> Vec<((i64, i64), (i64, i64))> {
+        self.0
+            .iter()
+            .map(|sensor| (xy(sensor.position()), xy(sensor.closest_beacon())))
+            .collect()
+    }
+
+    /// the x positions in row `y` that a sensor covers, as sorted `range`s.
+    fn covered_positions_for_row<'py>(
+        &self,
+        py: Python<'py>,
+        y: i64,
+    ) -> PyResult<Vec<Bound<'py, PyAny>>> {
+        let range = py.import("builtins")?.getattr("range")?;
+        crate::day15::Sensors::new(&self.0)
+            .covered_positions_for_row(y)
+            .iter()
+            .map(|covered| range.call1((*covered.start(), i128::from(*covered.end()) + 1)))
+            .collect()
+    }
+
+    /// the number of positions in row `y` where there can't be a beacon.
+    fn num_covered_positions_for_row(&self, y: i64) -> PyResult<i64> {
+        Ok(crate::day15::Sensors::new(&self.0).num_covered_positions_for_row(y)?)
+    }
+
+    /// the only position with x and y from 0 to `max_xy` that no sensor
+    /// covers.
+    fn find_distress_signal(&self, max_xy: i64) -> PyResult<(i64, i64)> {
+        Ok(xy(
+            crate::day15::Sensors::new(&self.0).find_distress_signal(max_xy)?
+        ))
+    }
+}
+
+/// the enabled days.
+#[pyfunction]
+fn days() -> Vec<u32> {
+    // a `Vec<u8>` would be converted to `bytes`
+    solution::solvers()
+        .iter()
+        .map(|solver| u32::from(solver.day()))
+        .collect()
+}
+
+/// parses `input` and returns the answer of a part as a string, like the
+/// command line does.
+#[pyfunction]
+fn solve(day: u8, input: &str, part: u8) -> PyResult<String> {
+    let part = Part::from_number(part)
+        .ok_or_else(|| PyValueError::new_err(format!("there is no part {}", part)))?;
+    let mut day_run = solution::solver(day)
+        .and_then(|solver| solver.run(input.trim_end_matches('\n'), &[part]))
+        .map_err(|e| AocError::new_err(error_chain(&e)))?;
+    Ok(day_run.parts.remove(0).answer)
+}
+
+#[pymodule]
+#[pyo3(name = "aoc2022")]
+fn aoc2022_module(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add("AocError", module.py().get_type::<AocError>())?;
+    module.add_function(wrap_pyfunction!(days, module)?)?;
+    module.add_function(wrap_pyfunction!(solve, module)?)?;
+
+    #[cfg(feature = "day1")]
+    day1::register(module)?;
+    #[cfg(feature = "day2")]
+    day2::register(module)?;
+    #[cfg(feature = "day3")]
+    day3::register(module)?;
+    #[cfg(feature = "day4")]
+    day4::register(module)?;
+    #[cfg(feature = "day5")]
+    day5::register(module)?;
+    #[cfg(feature = "day6")]
+    day6::register(module)?;
+    #[cfg(feature = "day7")]
+    day7::register(module)?;
+    #[cfg(feature = "day8")]
+    day8::register(module)?;
+    #[cfg(feature = "day9")]
+    day9::register(module)?;
+    #[cfg(feature = "day10")]
+    day10::register(module)?;
+    #[cfg(feature = "day11")]
+    day11::register(module)?;
+    #[cfg(feature = "day12")]
+    day12::register(module)?;
+    #[cfg(feature = "day13")]
+    day13::register(module)?;
+    #[cfg(feature = "day14")]
+    day14::register(module)?;
+    #[cfg(feature = "day15")]
+    day15::register(module)?;
+
+    Ok(())
+}
@@ -0,0 +1,43 @@
+//! naive implementations of every day, used to check the actual solutions.
+//!
+//! these are written to be obviously correct rather than fast: they follow
+//! the puzzle text step by step, enumerate every cell, never reduce numbers
+//! and share no code with the solutions, not even the parsers. they expect
+//! valid puzzle inputs and panic on anything else. some of them are only
+//! practical for small inputs, which is noted on the function.
+//!
+//! the tests of every day generate small random inputs with proptest and check
+//! that the solution gives the same answers as the reference. failing inputs
+//! are shrunk, and proptest saves their seeds in `proptest-regressions/`, so
+//! that they are tried first on every later run.
+
+#[cfg(feature = "day1")]
+pub mod day1;
+#[cfg(feature = "day10")]
+pub mod day10;
+#[cfg(feature = "day11")]
+pub mod day11;
+#[cfg(feature = "day12")]
+pub mod day12;
+#[cfg(feature = "day13")]
+pub mod day13;
+#[cfg(feature = "day14")]
+pub mod day14;
+#[cfg(feature = "day15")]
+pub mod day15;
+#[cfg(feature = "day2")]
+pub mod day2;
+#[cfg(feature = "day3")]
+pub mod day3;
+#[cfg(feature = "day4")]
+pub mod day4;
+#[cfg(feature = "day5")]
+pub mod day5;
+#[cfg(feature = "day6")]
+pub mod day6;
+#[cfg(feature = "day7")]
+pub mod day7;
+#[cfg(feature = "day8")]
+pub mod day8;
+#[cfg(feature = "day9")]
+pub mod day9;
@@ -0,0 +1,74 @@
+/// returns the total calories carried by every elf.
+fn elves(input: &str) -> Vec<u64> {
+    input
+        .split("\n\n")
+        .map(|elf| elf.lines().map(|line| line.parse::<u64>().unwrap()).sum())
+        .collect()
+}
+
+pub fn part1(input: &str) -> u64 {
+    elves(input).into_iter().max().unwrap()
+}
+
+/// returns `None` if there are less than 3 elves.
+pub fn part2(input: &str) -> Option<u64> {
+    let mut elves = elves(input);
+    elves.sort();
+    elves.reverse();
+
+    (elves.len() >= 3).then(|| elves[0] + elves[1] + elves[2])
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use proptest::{
+        collection::vec,
+        prelude::*,
+    };
+
+    use super::*;
+    use crate::{
+        day1::Day1,
+        solution::Solution,
+    };
+
+    const EXAMPLE: &str = "1000
+2000
+3000
+
+4000
+
+5000
+6000
+
+7000
+8000
+9000
+
+10000";
+
+    fn elves_input() -> impl Strategy<Value = String> {
+        vec(vec(0..100_000u32, 1..6), 1..10).prop_map(|elves| {
+            elves
+                .iter()
+                .map(|calories| calories.iter().join("\n"))
+                .join("\n\n")
+        })
+    }
+
+    #[test]
+    fn example() {
+        assert_eq!(part1(EXAMPLE), 24000);
+        assert_eq!(part2(EXAMPLE), Some(45000));
+    }
+
+    proptest! {
+        #[test]
+        fn same_answers(input in elves_input()) {
+            let calories = Day1::parse(&input).unwrap();
+            prop_assert_eq!(Day1::part1(&calories).unwrap(), part1(&input));
+            prop_assert_eq!(Day1::part2(&calories).ok(), part2(&input));
+        }
+    }
+}
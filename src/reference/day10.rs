@@ -0,0 +1,92 @@
+/// returns the value of the X register during every cycle, starting with the
+/// first one.
+fn register_values(input: &str) -> Vec<i64> {
+    let mut x = 1;
+    let mut values = vec![];
+
+    for line in input.lines() {
+        if line == "noop" {
+            values.push(x);
+        }
+        else {
+            let operand = line.strip_prefix("addx ").unwrap().parse::<i64>().unwrap();
+            values.push(x);
+            values.push(x);
+            x += operand;
+        }
+    }
+
+    values
+}
+
+pub fn part1(input: &str) -> i64 {
+    let values = register_values(input);
+
+    [20, 60, 100, 140, 180, 220]
+        .into_iter()
+        .filter_map(|cycle| Some(cycle as i64 * values.get(cycle - 1)?))
+        .sum()
+}
+
+/// returns the 40x6 screen with a newline after every row. pixels after the
+/// end of the program stay dark.
+pub fn part2(input: &str) -> String {
+    let values = register_values(input);
+    let mut screen = String::new();
+
+    for row in 0..6 {
+        for column in 0..40 {
+            let sprite = values.get(row * 40 + column);
+            let lit = sprite.is_some_and(|sprite| (sprite - column as i64).abs() <= 1);
+            screen.push(if lit { '#' } else { '.' });
+        }
+        screen.push('\n');
+    }
+
+    screen
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::{
+        collection::vec,
+        prelude::*,
+    };
+
+    use super::*;
+    use crate::{
+        day10::Day10,
+        solution::Solution,
+    };
+
+    /// programs that take at most the 240 cycles of the screen, just like the
+    /// real ones.
+    fn program_input() -> impl Strategy<Value = String> {
+        let instruction = prop_oneof![
+            Just("noop".to_owned()),
+            (-12..=12i64).prop_map(|operand| format!("addx {}", operand)),
+        ];
+        vec(instruction, 1..=120).prop_map(|program| program.join("\n"))
+    }
+
+    #[test]
+    fn example() {
+        // the sprite follows the beam until the program ends after 21 cycles.
+        let input = format!("noop{}", "\naddx 2".repeat(10));
+        assert_eq!(part1(&input), 20 * 19);
+        assert_eq!(
+            part2(&input),
+            format!("{}{}\n", "#".repeat(21), ".".repeat(19))
+                + &format!("{}\n", ".".repeat(40)).repeat(5)
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn same_answers(input in program_input()) {
+            let program = Day10::parse(&input).unwrap();
+            prop_assert_eq!(Day10::part1(&program).unwrap(), part1(&input));
+            prop_assert_eq!(Day10::part2(&program).unwrap(), format!("\n{}", part2(&input)));
+        }
+    }
+}
@@ -0,0 +1,229 @@
+use num_bigint::BigUint;
+use num_traits::Zero;
+
+struct Monkey {
+    items: Vec<BigUint>,
+    /// the left operand, operator and right operand of the operation.
+    operation: [String; 3],
+    divisible_by: BigUint,
+    if_true: usize,
+    if_false: usize,
+    inspected: usize,
+}
+
+impl Monkey {
+    fn inspect(&self, old: &BigUint) -> BigUint {
+        let operand = |operand: &str| {
+            if operand == "old" {
+                old.clone()
+            }
+            else {
+                operand.parse().unwrap()
+            }
+        };
+        let left = operand(&self.operation[0]);
+        let right = operand(&self.operation[2]);
+
+        match self.operation[1].as_str() {
+            "+" => left + right,
+            "*" => left * right,
+            operator => panic!("invalid operator {}", operator),
+        }
+    }
+}
+
+fn parse(input: &str) -> Vec<Monkey> {
+    input
+        .split("\n\n")
+        .map(|monkey| {
+            let lines = monkey.lines().collect::<Vec<_>>();
+            let after = |line: &str, prefix: &str| line.strip_prefix(prefix).unwrap().to_owned();
+
+            let items = after(lines[1], "  Starting items: ")
+                .split(", ")
+                .map(|item| item.parse().unwrap())
+                .collect();
+            let operation = after(lines[2], "  Operation: new = ")
+                .split(' ')
+                .map(str::to_owned)
+                .collect::<Vec<_>>();
+
+            Monkey {
+                items,
+                operation: operation.try_into().unwrap(),
+                divisible_by: after(lines[3], "  Test: divisible by ").parse().unwrap(),
+                if_true: after(lines[4], "    If true: throw to monkey ")
+                    .parse()
+                    .unwrap(),
+                if_false: after(lines[5], "    If false: throw to monkey ")
+                    .parse()
+                    .unwrap(),
+                inspected: 0,
+            }
+        })
+        .collect()
+}
+
+/// returns the product of the two highest numbers of inspected items after
+/// `rounds`. with `relief`, worry levels are divided by 3 after every
+/// inspection.
+///
+/// worry levels are never reduced otherwise, so this is only practical for a
+/// few rounds, or if worry levels never get squared.
+pub fn monkey_business(input: &str, rounds: usize, relief: bool) -> usize {
+    let mut monkeys = parse(input);
+
+    for _ in 0..rounds {
+        for i in 0..monkeys.len() {
+            let items = std::mem::take(&mut monkeys[i].items);
+            monkeys[i].inspected += items.len();
+
+            for item in items {
+                let monkey = &monkeys[i];
+                let mut worry_level = monkey.inspect(&item);
+                if relief {
+                    worry_level /= 3u32;
+                }
+                let target = if (&worry_level % &monkey.divisible_by).is_zero() {
+                    monkey.if_true
+                }
+                else {
+                    monkey.if_false
+                };
+                monkeys[target].items.push(worry_level);
+            }
+        }
+    }
+
+    let mut inspected = monkeys
+        .iter()
+        .map(|monkey| monkey.inspected)
+        .collect::<Vec<_>>();
+    inspected.sort();
+    inspected.reverse();
+    inspected[0] * inspected[1]
+}
+
+pub fn part1(input: &str) -> usize {
+    monkey_business(input, 20, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use proptest::{
+        collection::vec,
+        prelude::*,
+    };
+
+    use super::*;
+    use crate::{
+        day11::{
+            day11_input,
+            Day11,
+        },
+        solution::Solution,
+    };
+
+    const EXAMPLE: &str = "Monkey 0:
+  Starting items: 79, 98
+  Operation: new = old * 19
+  Test: divisible by 23
+    If true: throw to monkey 2
+    If false: throw to monkey 3
+
+Monkey 1:
+  Starting items: 54, 65, 75, 74
+  Operation: new = old + 6
+  Test: divisible by 19
+    If true: throw to monkey 2
+    If false: throw to monkey 0
+
+Monkey 2:
+  Starting items: 79, 60, 97
+  Operation: new = old * old
+  Test: divisible by 13
+    If true: throw to monkey 1
+    If false: throw to monkey 3
+
+Monkey 3:
+  Starting items: 74
+  Operation: new = old + 3
+  Test: divisible by 17
+    If true: throw to monkey 0
+    If false: throw to monkey 1";
+
+    fn operation() -> impl Strategy<Value = String> {
+        prop_oneof![
+            (1..20u32).prop_map(|factor| format!("old * {}", factor)),
+            (1..9u32).prop_map(|summand| format!("old + {}", summand)),
+        ]
+    }
+
+    /// only monkey 0 squares worry levels, and no monkey throws items back to
+    /// it, so that the worry levels stay small enough without reducing them.
+    ///
+    /// unlike in the real inputs, the divisors don't have to be prime.
+    fn monkeys_input() -> impl Strategy<Value = String> {
+        let monkey = (
+            vec(1..100u32, 1..5),
+            operation(),
+            2..24u32,
+            any::<usize>(),
+            any::<usize>(),
+        );
+        let squares = prop_oneof![Just("old * old".to_owned()), operation()];
+
+        (vec(monkey, 3..6), squares).prop_map(|(monkeys, squares)| {
+            let num_monkeys = monkeys.len();
+            monkeys
+                .into_iter()
+                .enumerate()
+                .map(|(i, (items, operation, divisor, if_true, if_false))| {
+                    let targets = (1..num_monkeys).filter(|j| *j != i).collect::<Vec<_>>();
+                    let operation = if i == 0 { &squares } else { &operation };
+
+                    format!(
+                        "Monkey {}:
+  Starting items: {}
+  Operation: new = {}
+  Test: divisible by {}
+    If true: throw to monkey {}
+    If false: throw to monkey {}",
+                        i,
+                        items.iter().join(", "),
+                        operation,
+                        divisor,
+                        targets[if_true % targets.len()],
+                        targets[if_false % targets.len()]
+                    )
+                })
+                .join("\n\n")
+        })
+    }
+
+    #[test]
+    fn example() {
+        assert_eq!(part1(EXAMPLE), 10605);
+        assert_eq!(monkey_business(EXAMPLE, 20, false), 99 * 103);
+    }
+
+    proptest! {
+        #[test]
+        fn same_answers_part1(input in monkeys_input()) {
+            let monkeys = Day11::parse(&input).unwrap();
+            prop_assert_eq!(Day11::part1(&monkeys).unwrap(), part1(&input));
+        }
+
+        /// checks that reducing the worry levels modulo the product of all
+        /// divisors doesn't change where items are thrown.
+        #[test]
+        fn same_monkey_business_without_relief(input in monkeys_input(), rounds in 1..40usize) {
+            let mut monkeys = day11_input(&input).unwrap();
+            for _ in 0..rounds {
+                monkeys.round(false);
+            }
+            prop_assert_eq!(monkeys.monkey_business(), monkey_business(&input, rounds, false));
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use std::collections::{
+    HashMap,
+    VecDeque,
+};
+
+type Position = (usize, usize);
+
+/// returns the elevation of every square, and the start and end position.
+fn parse(input: &str) -> (Vec<Vec<u8>>, Position, Position) {
+    let mut start = None;
+    let mut end = None;
+
+    let elevations = input
+        .lines()
+        .enumerate()
+        .map(|(y, line)| {
+            line.chars()
+                .enumerate()
+                .map(|(x, c)| {
+                    let c = match c {
+                        'S' => {
+                            start = Some((x, y));
+                            'a'
+                        }
+                        'E' => {
+                            end = Some((x, y));
+                            'z'
+                        }
+                        c => c,
+                    };
+                    c as u8 - b'a'
+                })
+                .collect()
+        })
+        .collect();
+
+    (elevations, start.unwrap(), end.unwrap())
+}
+
+/// returns the fewest steps from `start` to `end`, using a breadth-first
+/// search.
+fn fewest_steps(elevations: &[Vec<u8>], start: Position, end: Position) -> Option<usize> {
+    let mut steps = HashMap::from([(start, 0)]);
+    let mut queue = VecDeque::from([start]);
+
+    while let Some((x, y)) = queue.pop_front() {
+        let steps_here = steps[&(x, y)];
+        if (x, y) == end {
+            return Some(steps_here);
+        }
+
+        let neighbors = [
+            (x, y.wrapping_sub(1)),
+            (x + 1, y),
+            (x, y + 1),
+            (x.wrapping_sub(1), y),
+        ];
+        for (nx, ny) in neighbors {
+            let Some(elevation) = elevations.get(ny).and_then(|row| row.get(nx))
+            else {
+                continue;
+            };
+            // at most one higher
+            if *elevation <= elevations[y][x] + 1 && !steps.contains_key(&(nx, ny)) {
+                steps.insert((nx, ny), steps_here + 1);
+                queue.push_back((nx, ny));
+            }
+        }
+    }
+
+    None
+}
+
+pub fn part1(input: &str) -> Option<usize> {
+    let (elevations, start, end) = parse(input);
+    fewest_steps(&elevations, start, end)
+}
+
+/// tries every square at elevation `a`.
+pub fn part2(input: &str) -> Option<usize> {
+    let (elevations, _, end) = parse(input);
+
+    let mut fewest = None;
+    for y in 0..elevations.len() {
+        for x in 0..elevations[y].len() {
+            if elevations[y][x] == 0 {
+                if let Some(steps) = fewest_steps(&elevations, (x, y), end) {
+                    fewest = Some(fewest.map_or(steps, |fewest: usize| fewest.min(steps)));
+                }
+            }
+        }
+    }
+
+    fewest
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use proptest::{
+        collection::vec,
+        prelude::*,
+    };
+
+    use super::*;
+    use crate::{
+        day12::Day12,
+        solution::Solution,
+    };
+
+    const EXAMPLE: &str = "Sabqponm
+abcryxxl
+accszExk
+acctuvwj
+abdefghi";
+
+    /// the squares form a path that winds through the map row by row. the
+    /// elevation rises by one per square over the last 26 squares of the path,
+    /// up to the best signal at its end, and the start is somewhere before
+    /// that. random dips make some squares impossible to climb out of.
+    fn height_map_input() -> impl Strategy<Value = String> {
+        // at least two squares for the start and the end
+        (1..12usize, 2..12usize).prop_flat_map(|(width, height)| {
+            let length = width * height;
+            let dip = prop_oneof![59 => Just(0u8), 1 => 1..4u8];
+            (vec(dip, length), 0..length.saturating_sub(26).max(1)).prop_map(
+                move |(dips, start)| {
+                    let mut rows = vec![vec![' '; width]; height];
+                    for (i, dip) in dips.into_iter().enumerate() {
+                        let y = i / width;
+                        let x = if y % 2 == 0 {
+                            i % width
+                        }
+                        else {
+                            width - 1 - i % width
+                        };
+                        let to_end = (length - 1 - i).min(25) as u8;
+                        rows[y][x] = if i == start {
+                            'S'
+                        }
+                        else if i == length - 1 {
+                            'E'
+                        }
+                        else {
+                            (b'a' + (25 - to_end).saturating_sub(dip)) as char
+                        };
+                    }
+
+                    rows.iter()
+                        .map(|row| row.iter().collect::<String>())
+                        .join("\n")
+                },
+            )
+        })
+    }
+
+    #[test]
+    fn example() {
+        assert_eq!(part1(EXAMPLE), Some(31));
+        assert_eq!(part2(EXAMPLE), Some(29));
+    }
+
+    proptest! {
+        #[test]
+        fn same_answers(input in height_map_input()) {
+            let height_map = Day12::parse(&input).unwrap();
+            prop_assert_eq!(Day12::part1(&height_map).ok(), part1(&input));
+            prop_assert_eq!(Day12::part2(&height_map).ok(), part2(&input));
+        }
+    }
+}
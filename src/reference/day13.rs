@@ -0,0 +1,179 @@
+use std::{
+    cmp::Ordering,
+    iter::Peekable,
+    str::Chars,
+};
+
+#[derive(Clone, Debug)]
+enum Packet {
+    Integer(u32),
+    List(Vec<Packet>),
+}
+
+fn parse_packet(chars: &mut Peekable<Chars>) -> Packet {
+    if chars.next_if_eq(&'[').is_some() {
+        let mut list = vec![];
+        while chars.next_if_eq(&']').is_none() {
+            chars.next_if_eq(&',');
+            list.push(parse_packet(chars));
+        }
+        Packet::List(list)
+    }
+    else {
+        let mut digits = String::new();
+        while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+            digits.push(digit);
+        }
+        Packet::Integer(digits.parse().unwrap())
+    }
+}
+
+fn parse(packet: &str) -> Packet {
+    parse_packet(&mut packet.chars().peekable())
+}
+
+/// compares two packets, following the rules of the puzzle one by one.
+fn compare(left: &Packet, right: &Packet) -> Ordering {
+    match (left, right) {
+        (Packet::Integer(left), Packet::Integer(right)) => left.cmp(right),
+        (Packet::List(left), Packet::List(right)) => {
+            for i in 0..left.len().min(right.len()) {
+                let ordering = compare(&left[i], &right[i]);
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            // the list that runs out of items first comes first
+            left.len().cmp(&right.len())
+        }
+        (Packet::Integer(left), right) => {
+            compare(&Packet::List(vec![Packet::Integer(*left)]), right)
+        }
+        (left, Packet::Integer(right)) => {
+            compare(left, &Packet::List(vec![Packet::Integer(*right)]))
+        }
+    }
+}
+
+pub fn part1(input: &str) -> usize {
+    let mut sum = 0;
+
+    for (i, pair) in input.split("\n\n").enumerate() {
+        let (left, right) = pair.split_once('\n').unwrap();
+        if compare(&parse(left), &parse(right)) == Ordering::Less {
+            sum += i + 1;
+        }
+    }
+
+    sum
+}
+
+/// instead of sorting the packets, this counts the packets that come before
+/// each divider packet.
+pub fn part2(input: &str) -> usize {
+    let packets = input
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(parse)
+        .collect::<Vec<_>>();
+    let before = |divider: &str| {
+        let divider = parse(divider);
+        packets
+            .iter()
+            .filter(|packet| compare(packet, &divider) == Ordering::Less)
+            .count()
+    };
+
+    // [[2]] also comes before [[6]]
+    (before("[[2]]") + 1) * (before("[[6]]") + 2)
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use proptest::{
+        collection::vec,
+        prelude::*,
+    };
+
+    use super::*;
+    use crate::{
+        day13::Day13,
+        solution::Solution,
+    };
+
+    const EXAMPLE: &str = "[1,1,3,1,1]
+[1,1,5,1,1]
+
+[[1],[2,3,4]]
+[[1],4]
+
+[9]
+[[8,7,6]]
+
+[[4,4],4,4]
+[[4,4],4,4,4]
+
+[7,7,7,7]
+[7,7,7]
+
+[]
+[3]
+
+[[[]]]
+[[]]
+
+[1,[2,[3,[4,[5,6,7]]]],8,9]
+[1,[2,[3,[4,[5,6,0]]]],8,9]";
+
+    fn format(packet: &Packet) -> String {
+        match packet {
+            Packet::Integer(integer) => integer.to_string(),
+            Packet::List(list) => format!("[{}]", list.iter().map(format).join(",")),
+        }
+    }
+
+    /// packets that are equal to a divider packet could be sorted on either
+    /// side of it, so they are left out.
+    fn packet() -> impl Strategy<Value = Packet> {
+        let value = (0..11u32)
+            .prop_map(Packet::Integer)
+            .prop_recursive(3, 16, 4, |value| vec(value, 0..4).prop_map(Packet::List));
+        vec(value, 0..4)
+            .prop_map(Packet::List)
+            .prop_filter("equal to a divider packet", |packet| {
+                ["[[2]]", "[[6]]"]
+                    .iter()
+                    .all(|divider| compare(packet, &parse(divider)) != Ordering::Equal)
+            })
+    }
+
+    /// equal packets are in neither the right nor the wrong order, so the
+    /// packets of a pair always differ.
+    fn pairs_input() -> impl Strategy<Value = String> {
+        let pair = (packet(), packet()).prop_filter("equal packets", |(left, right)| {
+            compare(left, right) != Ordering::Equal
+        });
+        vec(pair, 1..8).prop_map(|pairs| {
+            pairs
+                .iter()
+                .map(|(left, right)| format!("{}\n{}", format(left), format(right)))
+                .join("\n\n")
+        })
+    }
+
+    #[test]
+    fn example() {
+        assert_eq!(part1(EXAMPLE), 13);
+        assert_eq!(part2(EXAMPLE), 140);
+    }
+
+    proptest! {
+        #[test]
+        fn same_answers(input in pairs_input()) {
+            let packet_pairs = Day13::parse(&input).unwrap();
+            prop_assert_eq!(Day13::part1(&packet_pairs).unwrap(), part1(&input));
+            prop_assert_eq!(Day13::part2(&packet_pairs).unwrap(), part2(&input));
+        }
+    }
+}
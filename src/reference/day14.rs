@@ -0,0 +1,142 @@
+use std::collections::HashSet;
+
+/// returns the position of every unit of rock.
+fn rock(input: &str) -> HashSet<(i32, i32)> {
+    let mut rock = HashSet::new();
+
+    for line in input.lines() {
+        let points = line
+            .split(" -> ")
+            .map(|point| {
+                let (x, y) = point.split_once(',').unwrap();
+                (x.parse::<i32>().unwrap(), y.parse::<i32>().unwrap())
+            })
+            .collect::<Vec<_>>();
+
+        for segment in points.windows(2) {
+            let (a, b) = (segment[0], segment[1]);
+            for x in a.0.min(b.0)..=a.0.max(b.0) {
+                for y in a.1.min(b.1)..=a.1.max(b.1) {
+                    rock.insert((x, y));
+                }
+            }
+        }
+    }
+
+    rock
+}
+
+/// drops sand from the source until a grain falls below all rock or the source
+/// is blocked, and returns how many grains came to rest. with a `floor`, the
+/// sand lands on an endless floor 2 units below the lowest rock instead.
+fn pour_sand(input: &str, floor: bool) -> usize {
+    let mut blocked = rock(input);
+    let lowest_rock = blocked.iter().map(|(_, y)| *y).max().unwrap();
+    let floor_y = lowest_rock + 2;
+    let mut grains = 0;
+
+    while !blocked.contains(&(500, 0)) {
+        let (mut x, mut y) = (500, 0);
+
+        loop {
+            if !floor && y > lowest_rock {
+                return grains;
+            }
+
+            let next = [(x, y + 1), (x - 1, y + 1), (x + 1, y + 1)]
+                .into_iter()
+                .find(|position| (!floor || position.1 < floor_y) && !blocked.contains(position));
+            match next {
+                Some(position) => (x, y) = position,
+                None => break,
+            }
+        }
+
+        blocked.insert((x, y));
+        grains += 1;
+    }
+
+    grains
+}
+
+pub fn part1(input: &str) -> usize {
+    pour_sand(input, false)
+}
+
+pub fn part2(input: &str) -> usize {
+    pour_sand(input, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use proptest::{
+        collection::vec,
+        prelude::*,
+    };
+
+    use super::*;
+    use crate::{
+        day14::Day14,
+        solution::Solution,
+    };
+
+    const EXAMPLE: &str = "498,4 -> 498,6 -> 496,6
+503,4 -> 502,4 -> 502,9 -> 494,9";
+
+    const MAX_Y: i32 = 12;
+
+    /// a path that alternates between horizontal and vertical lines, and stays
+    /// between y=1 and `MAX_Y`.
+    fn path() -> impl Strategy<Value = String> {
+        let step = prop_oneof![-4..0i32, 1..5i32];
+        (494..507i32, 1..=MAX_Y, any::<bool>(), vec(step, 1..5)).prop_map(
+            |(mut x, mut y, mut horizontal, steps)| {
+                let mut points = vec![(x, y)];
+                for step in steps {
+                    if horizontal {
+                        x += step;
+                    }
+                    else {
+                        let next_y = (y + step).clamp(1, MAX_Y);
+                        y = if next_y != y {
+                            next_y
+                        }
+                        else if y < MAX_Y {
+                            y + 1
+                        }
+                        else {
+                            y - 1
+                        };
+                    }
+                    points.push((x, y));
+                    horizontal = !horizontal;
+                }
+
+                points
+                    .iter()
+                    .map(|(x, y)| format!("{},{}", x, y))
+                    .join(" -> ")
+            },
+        )
+    }
+
+    fn rock_input() -> impl Strategy<Value = String> {
+        vec(path(), 1..6).prop_map(|paths| paths.join("\n"))
+    }
+
+    #[test]
+    fn example() {
+        assert_eq!(part1(EXAMPLE), 24);
+        assert_eq!(part2(EXAMPLE), 93);
+    }
+
+    proptest! {
+        #[test]
+        fn same_answers(input in rock_input()) {
+            let rock_paths = Day14::parse(&input).unwrap();
+            prop_assert_eq!(Day14::part1(&rock_paths).unwrap(), part1(&input));
+            prop_assert_eq!(Day14::part2(&rock_paths).unwrap(), part2(&input));
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use std::collections::HashSet;
+
+type Position = (i64, i64);
+
+/// returns the position of every sensor and its closest beacon.
+fn parse(input: &str) -> Vec<(Position, Position)> {
+    input
+        .lines()
+        .map(|line| {
+            let numbers = line
+                .split(|c: char| !c.is_ascii_digit() && c != '-')
+                .filter(|number| !number.is_empty())
+                .map(|number| number.parse().unwrap())
+                .collect::<Vec<_>>();
+            ((numbers[0], numbers[1]), (numbers[2], numbers[3]))
+        })
+        .collect()
+}
+
+fn distance(a: Position, b: Position) -> i64 {
+    (a.0 - b.0).abs() + (a.1 - b.1).abs()
+}
+
+/// returns whether any sensor is at least as close to `position` as to its
+/// closest beacon.
+fn is_covered(sensors: &[(Position, Position)], position: Position) -> bool {
+    sensors
+        .iter()
+        .any(|(sensor, beacon)| distance(*sensor, position) <= distance(*sensor, *beacon))
+}
+
+/// checks every position in row `y` that any sensor could cover.
+pub fn positions_without_beacon(input: &str, y: i64) -> i64 {
+    let sensors = parse(input);
+    let beacons = sensors
+        .iter()
+        .map(|(_, beacon)| *beacon)
+        .collect::<HashSet<_>>();
+    let min_x = sensors
+        .iter()
+        .map(|(sensor, beacon)| sensor.0 - distance(*sensor, *beacon))
+        .min()
+        .unwrap();
+    let max_x = sensors
+        .iter()
+        .map(|(sensor, beacon)| sensor.0 + distance(*sensor, *beacon))
+        .max()
+        .unwrap();
+
+    (min_x..=max_x)
+        .filter(|x| is_covered(&sensors, (*x, y)) && !beacons.contains(&(*x, y)))
+        .count() as i64
+}
+
+/// returns all positions with both coordinates in `0..=max_xy` that no sensor
+/// covers.
+fn uncovered_positions(sensors: &[(Position, Position)], max_xy: i64) -> Vec<Position> {
+    (0..=max_xy)
+        .flat_map(|y| (0..=max_xy).map(move |x| (x, y)))
+        .filter(|position| !is_covered(sensors, *position))
+        .collect()
+}
+
+/// checks every position with both coordinates in `0..=max_xy`, and returns
+/// `None` unless exactly one of them isn't covered.
+pub fn tuning_frequency(input: &str, max_xy: i64) -> Option<i64> {
+    match uncovered_positions(&parse(input), max_xy)[..] {
+        [(x, y)] => Some(x * 4000000 + y),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use proptest::{
+        collection::vec,
+        prelude::*,
+    };
+
+    use super::*;
+    use crate::{
+        day15::{
+            day15_input,
+            Sensors,
+        },
+        geometry::Point2,
+    };
+
+    const EXAMPLE: &str = "Sensor at x=2, y=18: closest beacon is at x=-2, y=15
+Sensor at x=9, y=16: closest beacon is at x=10, y=16
+Sensor at x=13, y=2: closest beacon is at x=15, y=3
+Sensor at x=12, y=14: closest beacon is at x=10, y=16
+Sensor at x=10, y=20: closest beacon is at x=10, y=16
+Sensor at x=14, y=17: closest beacon is at x=10, y=16
+Sensor at x=8, y=7: closest beacon is at x=2, y=10
+Sensor at x=2, y=0: closest beacon is at x=2, y=10
+Sensor at x=0, y=11: closest beacon is at x=2, y=10
+Sensor at x=20, y=14: closest beacon is at x=25, y=17
+Sensor at x=17, y=20: closest beacon is at x=21, y=22
+Sensor at x=16, y=7: closest beacon is at x=15, y=3
+Sensor at x=14, y=3: closest beacon is at x=15, y=3
+Sensor at x=20, y=1: closest beacon is at x=15, y=3";
+
+    /// sensors around a small search area, with beacons far enough away that
+    /// often only a few positions are left uncovered.
+    fn sensors_input() -> impl Strategy<Value = String> {
+        vec((-4..16i64, -4..16i64, -8..=8i64, -8..=8i64), 1..10).prop_map(|sensors| {
+            sensors
+                .iter()
+                .map(|(x, y, dx, dy)| {
+                    format!(
+                        "Sensor at x={}, y={}: closest beacon is at x={}, y={}",
+                        x,
+                        y,
+                        x + dx,
+                        y + dy
+                    )
+                })
+                .join("\n")
+        })
+    }
+
+    #[test]
+    fn example() {
+        assert_eq!(positions_without_beacon(EXAMPLE, 10), 26);
+        assert_eq!(tuning_frequency(EXAMPLE, 20), Some(56000011));
+    }
+
+    proptest! {
+        #[test]
+        fn same_positions_without_beacon(input in sensors_input(), y in -12..28i64) {
+            let sensors = day15_input(&input).unwrap();
+            prop_assert_eq!(
+                Sensors::new(&sensors).num_covered_positions_for_row(y).unwrap(),
+                positions_without_beacon(&input, y)
+            );
+        }
+
+        #[test]
+        fn same_distress_signal(input in sensors_input(), max_xy in 0..12i64) {
+            let sensors = day15_input(&input).unwrap();
+            let distress_signal = Sensors::new(&sensors).find_distress_signal(max_xy);
+
+            match uncovered_positions(&parse(&input), max_xy)[..] {
+                [] => prop_assert!(distress_signal.is_err()),
+                [(x, y)] => prop_assert_eq!(distress_signal.ok(), Some(Point2::new(x, y))),
+                // not a valid input, any of the positions or an error will do.
+                _ => {}
+            }
+        }
+    }
+}
@@ -0,0 +1,70 @@
+/// the scores of every possible line for both parts, worked out by hand from
+/// the puzzle text.
+const SCORES: [(&str, u64, u64); 9] = [
+    // rock vs. rock is a draw; losing against rock takes scissors
+    ("A X", 1 + 3, 3),
+    // paper beats rock; drawing against rock takes rock
+    ("A Y", 2 + 6, 1 + 3),
+    // scissors lose against rock; winning against rock takes paper
+    ("A Z", 3, 2 + 6),
+    // rock loses against paper; losing against paper takes rock
+    ("B X", 1, 1),
+    // paper vs. paper is a draw; drawing against paper takes paper
+    ("B Y", 2 + 3, 2 + 3),
+    // scissors beat paper; winning against paper takes scissors
+    ("B Z", 3 + 6, 3 + 6),
+    // rock beats scissors; losing against scissors takes paper
+    ("C X", 1 + 6, 2),
+    // paper loses against scissors; drawing against scissors takes scissors
+    ("C Y", 2, 3 + 3),
+    // scissors vs. scissors is a draw; winning against scissors takes rock
+    ("C Z", 3 + 3, 1 + 6),
+];
+
+fn score(line: &str) -> (u64, u64) {
+    let (_, part1, part2) = SCORES.iter().find(|(round, ..)| *round == line).unwrap();
+    (*part1, *part2)
+}
+
+pub fn part1(input: &str) -> u64 {
+    input.lines().map(|line| score(line).0).sum()
+}
+
+pub fn part2(input: &str) -> u64 {
+    input.lines().map(|line| score(line).1).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::{
+        collection::vec,
+        prelude::*,
+        sample::select,
+    };
+
+    use super::*;
+    use crate::{
+        day2::Day2,
+        solution::Solution,
+    };
+
+    fn rounds_input() -> impl Strategy<Value = String> {
+        let rounds = SCORES.map(|(round, ..)| round).to_vec();
+        vec(select(rounds), 1..20).prop_map(|rounds| rounds.join("\n"))
+    }
+
+    #[test]
+    fn example() {
+        assert_eq!(part1("A Y\nB X\nC Z"), 15);
+        assert_eq!(part2("A Y\nB X\nC Z"), 12);
+    }
+
+    proptest! {
+        #[test]
+        fn same_answers(input in rounds_input()) {
+            let rounds = Day2::parse(&input).unwrap();
+            prop_assert_eq!(Day2::part1(&rounds).unwrap(), part1(&input));
+            prop_assert_eq!(Day2::part2(&rounds).unwrap(), part2(&input));
+        }
+    }
+}
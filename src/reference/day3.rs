@@ -0,0 +1,141 @@
+const ITEMS: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+fn priority(item: char) -> u64 {
+    ITEMS.find(item).unwrap() as u64 + 1
+}
+
+pub fn part1(input: &str) -> u64 {
+    input
+        .lines()
+        .map(|rucksack| {
+            let (first, second) = rucksack.split_at(rucksack.len() / 2);
+            let item = first.chars().find(|item| second.contains(*item)).unwrap();
+            priority(item)
+        })
+        .sum()
+}
+
+pub fn part2(input: &str) -> u64 {
+    let rucksacks = input.lines().collect::<Vec<_>>();
+
+    rucksacks
+        .chunks(3)
+        .map(|group| {
+            let badge = group[0]
+                .chars()
+                .find(|item| group[1].contains(*item) && group[2].contains(*item))
+                .unwrap();
+            priority(badge)
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use proptest::{
+        collection::vec,
+        prelude::*,
+    };
+
+    use super::*;
+    use crate::{
+        day3::Day3,
+        solution::Solution,
+    };
+
+    const EXAMPLE: &str = "vJrwpWtwJgWrhcsFMMfFFhFp
+jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+PmmdzqPrVvPwwTWBwg
+wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+ttgJtRGJQctTZtZT
+CrZsJsPPZsGzwwsLwLmpwMDw";
+
+    /// a rucksack that picks its items from a pool of 18 items, one of which is
+    /// the badge of its group.
+    ///
+    /// the first 8 items after taking out `in_both` go into the first
+    /// compartment and the other 9 into the second one, so that only `in_both`
+    /// is in both.
+    #[derive(Clone, Debug)]
+    struct Rucksack {
+        in_both: usize,
+        first: Vec<usize>,
+        second: Vec<usize>,
+    }
+
+    impl Rucksack {
+        fn items(&self, pool: &[char], badge: char) -> String {
+            let mut items = pool.to_vec();
+            items.push(badge);
+            let in_both = items.remove(self.in_both);
+            let (first_items, second_items) = items.split_at(8);
+
+            let mut first = vec![in_both];
+            first.extend(self.first.iter().map(|i| first_items[*i]));
+            let mut second = vec![in_both];
+            second.extend(self.second.iter().map(|i| second_items[*i]));
+
+            // the badge has to be in every rucksack of the group.
+            if in_both != badge {
+                if first_items.contains(&badge) {
+                    first[1] = badge;
+                }
+                else {
+                    second[1] = badge;
+                }
+            }
+
+            first.into_iter().chain(second).collect()
+        }
+    }
+
+    fn rucksack() -> impl Strategy<Value = Rucksack> {
+        (0..18usize, 1..8usize).prop_flat_map(|(in_both, length)| {
+            (vec(0..8usize, length), vec(0..9usize, length)).prop_map(move |(first, second)| {
+                Rucksack {
+                    in_both,
+                    first,
+                    second,
+                }
+            })
+        })
+    }
+
+    /// every group shares a badge, and apart from that every rucksack uses its
+    /// own pool of items.
+    fn group() -> impl Strategy<Value = String> {
+        let items = ITEMS.chars().collect::<Vec<_>>();
+        (
+            Just(items).prop_shuffle(),
+            [rucksack(), rucksack(), rucksack()],
+        )
+            .prop_map(|(items, rucksacks)| {
+                let badge = items[0];
+                rucksacks
+                    .iter()
+                    .zip(items[1..].chunks(17))
+                    .map(|(rucksack, pool)| rucksack.items(pool, badge))
+                    .join("\n")
+            })
+    }
+
+    fn rucksacks_input() -> impl Strategy<Value = String> {
+        vec(group(), 1..5).prop_map(|groups| groups.join("\n"))
+    }
+
+    #[test]
+    fn example() {
+        assert_eq!(part1(EXAMPLE), 157);
+        assert_eq!(part2(EXAMPLE), 70);
+    }
+
+    proptest! {
+        #[test]
+        fn same_answers(input in rucksacks_input()) {
+            let rucksacks = Day3::parse(&input).unwrap();
+            prop_assert_eq!(Day3::part1(&rucksacks).unwrap(), part1(&input));
+            prop_assert_eq!(Day3::part2(&rucksacks).unwrap(), part2(&input));
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use std::collections::HashSet;
+
+/// returns the sections of both elves of every pair.
+fn pairs(input: &str) -> Vec<(HashSet<u64>, HashSet<u64>)> {
+    let sections = |range: &str| {
+        let (first, last) = range.split_once('-').unwrap();
+        (first.parse().unwrap()..=last.parse().unwrap()).collect()
+    };
+
+    input
+        .lines()
+        .map(|line| {
+            let (first, second) = line.split_once(',').unwrap();
+            (sections(first), sections(second))
+        })
+        .collect()
+}
+
+pub fn part1(input: &str) -> usize {
+    pairs(input)
+        .iter()
+        .filter(|(first, second)| first.is_subset(second) || second.is_subset(first))
+        .count()
+}
+
+pub fn part2(input: &str) -> usize {
+    pairs(input)
+        .iter()
+        .filter(|(first, second)| !first.is_disjoint(second))
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use proptest::{
+        collection::vec,
+        prelude::*,
+    };
+
+    use super::*;
+    use crate::{
+        day4::Day4,
+        solution::Solution,
+    };
+
+    const EXAMPLE: &str = "2-4,6-8
+2-3,4-5
+5-7,7-9
+2-8,3-7
+6-6,4-6
+2-6,4-8";
+
+    /// few sections, so that ranges often start or end at the same section.
+    fn range() -> impl Strategy<Value = String> {
+        (1..12u64, 1..12u64).prop_map(|(a, b)| format!("{}-{}", a.min(b), a.max(b)))
+    }
+
+    fn pairs_input() -> impl Strategy<Value = String> {
+        vec((range(), range()), 1..20).prop_map(|pairs| {
+            pairs
+                .iter()
+                .map(|(first, second)| format!("{},{}", first, second))
+                .join("\n")
+        })
+    }
+
+    #[test]
+    fn example() {
+        assert_eq!(part1(EXAMPLE), 2);
+        assert_eq!(part2(EXAMPLE), 4);
+    }
+
+    proptest! {
+        #[test]
+        fn same_answers(input in pairs_input()) {
+            let assignments = Day4::parse(&input).unwrap();
+            prop_assert_eq!(Day4::part1(&assignments).unwrap(), part1(&input));
+            prop_assert_eq!(Day4::part2(&assignments).unwrap(), part2(&input));
+        }
+    }
+}
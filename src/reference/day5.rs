@@ -0,0 +1,169 @@
+struct Move {
+    count: usize,
+    from: usize,
+    to: usize,
+}
+
+/// returns the stacks, bottom crate first, and the moves with 0-based stack
+/// indices.
+fn parse(input: &str) -> (Vec<Vec<char>>, Vec<Move>) {
+    let (drawing, moves) = input.split_once("\n\n").unwrap();
+
+    // the crates are drawn right above the stack numbers.
+    let mut rows = drawing.lines().rev();
+    let numbers = rows.next().unwrap();
+    let rows = rows
+        .map(|row| row.chars().collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let stacks = numbers
+        .char_indices()
+        .filter(|(_, c)| c.is_ascii_digit())
+        .map(|(column, _)| {
+            rows.iter()
+                .filter_map(|row| row.get(column))
+                .filter(|c| c.is_ascii_uppercase())
+                .copied()
+                .collect()
+        })
+        .collect();
+
+    let moves = moves
+        .lines()
+        .map(|line| {
+            let words = line.split(' ').collect::<Vec<_>>();
+            Move {
+                count: words[1].parse().unwrap(),
+                from: words[3].parse::<usize>().unwrap() - 1,
+                to: words[5].parse::<usize>().unwrap() - 1,
+            }
+        })
+        .collect();
+
+    (stacks, moves)
+}
+
+fn top_crates(stacks: &[Vec<char>]) -> String {
+    stacks.iter().filter_map(|stack| stack.last()).collect()
+}
+
+/// the CrateMover 9000 moves one crate at a time.
+pub fn part1(input: &str) -> String {
+    let (mut stacks, moves) = parse(input);
+
+    for Move { count, from, to } in moves {
+        for _ in 0..count {
+            let crate_id = stacks[from].pop().unwrap();
+            stacks[to].push(crate_id);
+        }
+    }
+
+    top_crates(&stacks)
+}
+
+/// the CrateMover 9001 moves all crates at once.
+pub fn part2(input: &str) -> String {
+    let (mut stacks, moves) = parse(input);
+
+    for Move { count, from, to } in moves {
+        let rest = stacks[from].len() - count;
+        let crates = stacks[from].drain(rest..).collect::<Vec<_>>();
+        stacks[to].extend(crates);
+    }
+
+    top_crates(&stacks)
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use proptest::{
+        collection::vec,
+        prelude::*,
+    };
+
+    use super::*;
+    use crate::{
+        day5::Day5,
+        solution::Solution,
+    };
+
+    const EXAMPLE: &str = "    [D]
+[N] [C]
+[Z] [M] [P]
+ 1   2   3
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2";
+
+    fn drawing(stacks: &[Vec<char>]) -> Vec<String> {
+        let height = stacks.iter().map(Vec::len).max().unwrap();
+        let mut lines = (0..height)
+            .rev()
+            .map(|level| {
+                stacks
+                    .iter()
+                    .map(|stack| {
+                        stack
+                            .get(level)
+                            .map_or_else(|| "   ".to_owned(), |c| format!("[{}]", c))
+                    })
+                    .join(" ")
+                    .trim_end()
+                    .to_owned()
+            })
+            .collect::<Vec<_>>();
+        lines.push(
+            (1..=stacks.len())
+                .map(|i| format!(" {} ", i))
+                .join(" ")
+                .trim_end()
+                .to_owned(),
+        );
+        lines
+    }
+
+    /// every stack starts with at least one crate. the moves are chosen by
+    /// index among the stacks that still have crates, so they are always
+    /// possible. crates may be moved onto the stack they came from.
+    fn stacks_input() -> impl Strategy<Value = String> {
+        let stacks = vec(vec(proptest::char::range('A', 'Z'), 1..5), 1..5);
+        let moves = vec((0..5usize, 0..5usize, 0..5usize), 1..12);
+
+        (stacks, moves).prop_map(|(stacks, moves)| {
+            let mut lines = drawing(&stacks);
+            lines.push(String::new());
+
+            let mut sizes = stacks.iter().map(Vec::len).collect::<Vec<_>>();
+            for (from, to, count) in moves {
+                let sources = (0..sizes.len())
+                    .filter(|i| sizes[*i] > 0)
+                    .collect::<Vec<_>>();
+                let from = sources[from % sources.len()];
+                let to = to % sizes.len();
+                let count = 1 + count % sizes[from];
+                sizes[from] -= count;
+                sizes[to] += count;
+                lines.push(format!("move {} from {} to {}", count, from + 1, to + 1));
+            }
+
+            lines.join("\n")
+        })
+    }
+
+    #[test]
+    fn example() {
+        assert_eq!(part1(EXAMPLE), "CMZ");
+        assert_eq!(part2(EXAMPLE), "MCD");
+    }
+
+    proptest! {
+        #[test]
+        fn same_answers(input in stacks_input()) {
+            let stacks = Day5::parse(&input).unwrap();
+            prop_assert_eq!(Day5::part1(&stacks).unwrap(), part1(&input));
+            prop_assert_eq!(Day5::part2(&stacks).unwrap(), part2(&input));
+        }
+    }
+}
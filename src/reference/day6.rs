@@ -0,0 +1,53 @@
+use std::collections::HashSet;
+
+/// returns the number of characters up to the end of the first `length`
+/// characters that are all different.
+fn find_marker(signal: &str, length: usize) -> Option<usize> {
+    let signal = signal.chars().collect::<Vec<_>>();
+
+    signal
+        .windows(length)
+        .position(|window| window.iter().collect::<HashSet<_>>().len() == length)
+        .map(|start| start + length)
+}
+
+pub fn part1(input: &str) -> Option<usize> {
+    find_marker(input, 4)
+}
+
+pub fn part2(input: &str) -> Option<usize> {
+    find_marker(input, 14)
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::{
+        day6::Day6,
+        solution::Solution,
+    };
+
+    #[test]
+    fn example() {
+        assert_eq!(part1("mjqjpqmgbljsphdztnvjfqwrcgsmlb"), Some(7));
+        assert_eq!(part2("mjqjpqmgbljsphdztnvjfqwrcgsmlb"), Some(19));
+    }
+
+    proptest! {
+        // few letters, so that markers are neither too easy nor impossible to
+        // find.
+        #[test]
+        fn same_answers_part1(input in "[a-e]{0,30}") {
+            let signal = Day6::parse(&input).unwrap();
+            prop_assert_eq!(Day6::part1(&signal).ok(), part1(&input));
+        }
+
+        #[test]
+        fn same_answers_part2(input in "[a-p]{0,60}") {
+            let signal = Day6::parse(&input).unwrap();
+            prop_assert_eq!(Day6::part2(&signal).ok(), part2(&input));
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+
+/// returns the total size of every directory, keyed by its path. the root
+/// directory has an empty path.
+fn directory_sizes(input: &str) -> HashMap<Vec<String>, u64> {
+    let mut path: Vec<String> = vec![];
+    let mut sizes = HashMap::new();
+    sizes.insert(vec![], 0);
+
+    for line in input.lines() {
+        if let Some(directory) = line.strip_prefix("$ cd ") {
+            match directory {
+                "/" => path.clear(),
+                ".." => {
+                    path.pop();
+                }
+                name => path.push(name.to_owned()),
+            }
+            sizes.entry(path.clone()).or_insert(0);
+        }
+        else if let Some(name) = line.strip_prefix("dir ") {
+            let mut directory = path.clone();
+            directory.push(name.to_owned());
+            sizes.entry(directory).or_insert(0);
+        }
+        else if line != "$ ls" {
+            // a file counts towards the current directory and all of its
+            // parents.
+            let (size, _) = line.split_once(' ').unwrap();
+            let size = size.parse::<u64>().unwrap();
+            for depth in 0..=path.len() {
+                *sizes.entry(path[..depth].to_vec()).or_insert(0) += size;
+            }
+        }
+    }
+
+    sizes
+}
+
+pub fn part1(input: &str) -> u64 {
+    directory_sizes(input)
+        .values()
+        .filter(|size| **size <= 100000)
+        .sum()
+}
+
+/// returns `None` if the files don't fit on the disk, or if there is enough
+/// free space already.
+pub fn part2(input: &str) -> Option<u64> {
+    let sizes = directory_sizes(input);
+    let free_space = 70000000u64.checked_sub(sizes[&vec![]])?;
+    if free_space >= 30000000 {
+        return None;
+    }
+    let need_to_free = 30000000 - free_space;
+
+    sizes
+        .values()
+        .filter(|size| **size >= need_to_free)
+        .min()
+        .copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::{
+        collection::vec,
+        prelude::*,
+    };
+
+    use super::*;
+    use crate::{
+        day7::Day7,
+        solution::Solution,
+    };
+
+    const EXAMPLE: &str = "$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd a
+$ ls
+dir e
+29116 f
+2557 g
+62596 h.lst
+$ cd e
+$ ls
+584 i
+$ cd ..
+$ cd ..
+$ cd d
+$ ls
+4060174 j
+8033020 d.log
+5626152 d.ext
+7214296 k";
+
+    #[derive(Clone, Debug, Default)]
+    struct Directory {
+        children: Vec<usize>,
+        files: Vec<(usize, u64)>,
+    }
+
+    /// lists `directory` and then visits all of its children, like the
+    /// terminal output in the puzzle.
+    fn visit(directories: &[Directory], directory: usize, lines: &mut Vec<String>) {
+        let Directory { children, files } = &directories[directory];
+
+        lines.push("$ ls".to_owned());
+        for child in children {
+            lines.push(format!("dir d{}", child));
+        }
+        for (file, size) in files {
+            lines.push(format!("{} f{}.txt", size, file));
+        }
+
+        for child in children {
+            lines.push(format!("$ cd d{}", child));
+            visit(directories, *child, lines);
+            lines.push("$ cd ..".to_owned());
+        }
+    }
+
+    /// the small files decide part 1. the large ones are multiples of 5000000,
+    /// so that part 2 often has a directory of exactly the size that has to be
+    /// freed.
+    fn file_size() -> impl Strategy<Value = u64> {
+        prop_oneof![1..150_000u64, (1..=8u64).prop_map(|n| n * 5_000_000)]
+    }
+
+    fn terminal_input() -> impl Strategy<Value = String> {
+        let parents = vec(any::<usize>(), 0..8);
+        let files = vec((any::<usize>(), file_size()), 1..12);
+
+        (parents, files).prop_map(|(parents, files)| {
+            // directory 0 is the root, every other directory picks one of the
+            // directories before it as its parent.
+            let num_directories = parents.len() + 1;
+            let mut directories = vec![Directory::default(); num_directories];
+            for (i, parent) in parents.into_iter().enumerate() {
+                directories[parent % (i + 1)].children.push(i + 1);
+            }
+            for (i, (directory, size)) in files.into_iter().enumerate() {
+                directories[directory % num_directories]
+                    .files
+                    .push((i, size));
+            }
+
+            let mut lines = vec!["$ cd /".to_owned()];
+            visit(&directories, 0, &mut lines);
+            lines.join("\n")
+        })
+    }
+
+    #[test]
+    fn example() {
+        assert_eq!(part1(EXAMPLE), 95437);
+        assert_eq!(part2(EXAMPLE), Some(24933642));
+    }
+
+    proptest! {
+        #[test]
+        fn same_answers(input in terminal_input()) {
+            let fs = Day7::parse(&input).unwrap();
+            prop_assert_eq!(Day7::part1(&fs).unwrap(), part1(&input));
+            prop_assert_eq!(Day7::part2(&fs).ok(), part2(&input));
+        }
+    }
+}
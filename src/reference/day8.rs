@@ -0,0 +1,105 @@
+fn parse(input: &str) -> Vec<Vec<u32>> {
+    input
+        .lines()
+        .map(|line| line.chars().map(|c| c.to_digit(10).unwrap()).collect())
+        .collect()
+}
+
+/// returns the trees between the tree at `x`, `y` and the edge, looking up,
+/// right, down and left. the nearest tree comes first.
+fn lines_of_sight(trees: &[Vec<u32>], x: usize, y: usize) -> [Vec<u32>; 4] {
+    let row = &trees[y];
+    let column = trees.iter().map(|row| row[x]).collect::<Vec<_>>();
+
+    [
+        column[..y].iter().rev().copied().collect(),
+        row[x + 1..].to_vec(),
+        column[y + 1..].to_vec(),
+        row[..x].iter().rev().copied().collect(),
+    ]
+}
+
+pub fn part1(input: &str) -> usize {
+    let trees = parse(input);
+    let mut visible = 0;
+
+    for y in 0..trees.len() {
+        for x in 0..trees[y].len() {
+            let height = trees[y][x];
+            if lines_of_sight(&trees, x, y)
+                .iter()
+                .any(|line| line.iter().all(|other| *other < height))
+            {
+                visible += 1;
+            }
+        }
+    }
+
+    visible
+}
+
+pub fn part2(input: &str) -> usize {
+    let trees = parse(input);
+    let mut best = 0;
+
+    for y in 0..trees.len() {
+        for x in 0..trees[y].len() {
+            let height = trees[y][x];
+            // the view ends at the first tree that is at least as tall.
+            let score = lines_of_sight(&trees, x, y)
+                .iter()
+                .map(|line| {
+                    line.iter()
+                        .position(|other| *other >= height)
+                        .map_or(line.len(), |i| i + 1)
+                })
+                .product();
+            best = best.max(score);
+        }
+    }
+
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use proptest::{
+        collection::vec,
+        prelude::*,
+    };
+
+    use super::*;
+    use crate::{
+        day8::Day8,
+        solution::Solution,
+    };
+
+    const EXAMPLE: &str = "30373
+25512
+65332
+33549
+35390";
+
+    fn forest_input() -> impl Strategy<Value = String> {
+        (1..8usize, 1..8usize).prop_flat_map(|(width, height)| {
+            vec(vec(0..10u32, width), height)
+                .prop_map(|rows| rows.iter().map(|row| row.iter().join("")).join("\n"))
+        })
+    }
+
+    #[test]
+    fn example() {
+        assert_eq!(part1(EXAMPLE), 21);
+        assert_eq!(part2(EXAMPLE), 8);
+    }
+
+    proptest! {
+        #[test]
+        fn same_answers(input in forest_input()) {
+            let forest = Day8::parse(&input).unwrap();
+            prop_assert_eq!(Day8::part1(&forest).unwrap(), part1(&input));
+            prop_assert_eq!(Day8::part2(&forest).unwrap(), part2(&input));
+        }
+    }
+}
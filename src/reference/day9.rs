@@ -0,0 +1,110 @@
+use std::collections::HashSet;
+
+/// returns where `tail` moves after `head` moved, following the rules of the
+/// puzzle one by one.
+fn follow(head: (i32, i32), tail: (i32, i32)) -> (i32, i32) {
+    let dx = head.0 - tail.0;
+    let dy = head.1 - tail.1;
+
+    if dx.abs() <= 1 && dy.abs() <= 1 {
+        // touching or overlapping, so the tail stays where it is
+        tail
+    }
+    else if dy == 0 {
+        // two steps directly left or right
+        (tail.0 + dx.signum(), tail.1)
+    }
+    else if dx == 0 {
+        // two steps directly up or down
+        (tail.0, tail.1 + dy.signum())
+    }
+    else {
+        // not touching and not in the same row or column, so the tail moves
+        // one step diagonally
+        (tail.0 + dx.signum(), tail.1 + dy.signum())
+    }
+}
+
+/// returns the number of positions the last of `length` knots visits.
+fn tail_positions(input: &str, length: usize) -> usize {
+    let mut knots = vec![(0, 0); length];
+    let mut visited = HashSet::from([(0, 0)]);
+
+    for line in input.lines() {
+        let (direction, steps) = line.split_once(' ').unwrap();
+        let (dx, dy) = match direction {
+            "U" => (0, -1),
+            "D" => (0, 1),
+            "L" => (-1, 0),
+            "R" => (1, 0),
+            _ => panic!("invalid direction {}", direction),
+        };
+
+        for _ in 0..steps.parse::<u32>().unwrap() {
+            knots[0] = (knots[0].0 + dx, knots[0].1 + dy);
+            for i in 1..length {
+                knots[i] = follow(knots[i - 1], knots[i]);
+            }
+            visited.insert(knots[length - 1]);
+        }
+    }
+
+    visited.len()
+}
+
+pub fn part1(input: &str) -> usize {
+    tail_positions(input, 2)
+}
+
+pub fn part2(input: &str) -> usize {
+    tail_positions(input, 10)
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use proptest::{
+        collection::vec,
+        prelude::*,
+        sample::select,
+    };
+
+    use super::*;
+    use crate::{
+        day9::Day9,
+        solution::Solution,
+    };
+
+    const EXAMPLE: &str = "R 4
+U 4
+L 3
+D 1
+R 4
+D 1
+L 5
+R 2";
+
+    fn movements_input() -> impl Strategy<Value = String> {
+        vec((select(vec!["U", "D", "L", "R"]), 1..8u32), 1..25).prop_map(|movements| {
+            movements
+                .iter()
+                .map(|(direction, steps)| format!("{} {}", direction, steps))
+                .join("\n")
+        })
+    }
+
+    #[test]
+    fn example() {
+        assert_eq!(part1(EXAMPLE), 13);
+        assert_eq!(part2(EXAMPLE), 1);
+    }
+
+    proptest! {
+        #[test]
+        fn same_answers(input in movements_input()) {
+            let movements = Day9::parse(&input).unwrap();
+            prop_assert_eq!(Day9::part1(&movements).unwrap(), part1(&input));
+            prop_assert_eq!(Day9::part2(&movements).unwrap(), part2(&input));
+        }
+    }
+}
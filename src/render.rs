@@ -0,0 +1,621 @@
+//! rendering of puzzle states as text, images and animations.
+//!
+//! types that can be visualized implement [`Render`], which draws them into a
+//! [`Frame`]. a frame can then be printed as text, or written as a PPM or PNG
+//! image. sequences of frames (e.g. one per rope step) are pushed into a
+//! [`FrameSink`], which either collects them, writes them to a directory or
+//! encodes them as an animated GIF.
+//!
+//! the text output is always available, since day 10 reads its answer from
+//! it. the images and animations need the `render` feature.
+//!
+//! an [`Animation`] attaches to a [`Simulation`](crate::simulation::Simulation)
+//! and pushes a frame per step.
+
+use std::{
+    fmt,
+    io::{
+        self,
+        Write,
+    },
+    ops::ControlFlow,
+};
+#[cfg(feature = "render")]
+use std::{
+    fs::{
+        self,
+        File,
+    },
+    io::BufWriter,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+use thiserror::Error;
+
+use crate::{
+    geometry::{
+        BoundingBox,
+        Point2,
+    },
+    simulation::Observer,
+};
+
+#[derive(Debug, Error)]
+pub enum RenderError {
+    #[error("io error")]
+    Io(#[from] io::Error),
+    #[cfg(feature = "render")]
+    #[error("failed to encode png")]
+    Png(#[from] png::EncodingError),
+    #[cfg(feature = "render")]
+    #[error("failed to encode gif")]
+    Gif(#[from] gif::EncodingError),
+    #[error("frame is {width}x{height}, but the animation is {expected_width}x{expected_height}")]
+    FrameSize {
+        width: usize,
+        height: usize,
+        expected_width: usize,
+        expected_height: usize,
+    },
+    #[error("frame is too large: {width}x{height}")]
+    FrameTooLarge { width: usize, height: usize },
+    #[error("frame has more than 256 colors")]
+    TooManyColors,
+    #[error("unknown image format: {0}")]
+    UnknownFormat(String),
+}
+
+/// a 24-bit color.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Self = Rgb(0, 0, 0);
+}
+
+/// a single cell of a [`Frame`]. it's shown as `glyph` in text output and as
+/// a square of `color` in images.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Cell {
+    pub glyph: char,
+    pub color: Rgb,
+}
+
+impl Cell {
+    pub const fn new(glyph: char, color: Rgb) -> Self {
+        Self { glyph, color }
+    }
+}
+
+/// a rectangular grid of cells. row 0 is at the top.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Frame {
+    width: usize,
+    height: usize,
+    cells: Vec<Cell>,
+}
+
+impl Frame {
+    pub fn new(width: usize, height: usize, background: Cell) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![background; width * height],
+        }
+    }
+
+    /// returns a frame with a cell for every point of `area`.
+    pub fn for_area(area: BoundingBox<i32>, background: Cell) -> Self {
+        Self::new(area.width() as usize, area.height() as usize, background)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<Cell> {
+        (x < self.width && y < self.height).then(|| self.cells[y * self.width + x])
+    }
+
+    /// sets the cell at `(x, y)`. cells outside of the frame are ignored.
+    pub fn set(&mut self, x: usize, y: usize, cell: Cell) {
+        if x < self.width && y < self.height {
+            self.cells[y * self.width + x] = cell;
+        }
+    }
+
+    /// sets the cell of `point` in a frame created by [`Frame::for_area`].
+    /// points outside of `area` are ignored.
+    pub fn set_point(&mut self, area: BoundingBox<i32>, point: Point2<i32>, cell: Cell) {
+        if area.contains(point) {
+            let offset = point - area.min;
+            self.set(offset.x as usize, offset.y as usize, cell);
+        }
+    }
+
+    /// returns the frame as text, one line per row.
+    pub fn to_ascii(&self) -> String {
+        self.to_string()
+    }
+}
+
+#[cfg(feature = "render")]
+impl Frame {
+    /// writes the frame as a binary PPM (P6) image. every cell becomes a
+    /// `scale` x `scale` square.
+    pub fn write_ppm<W: Write>(&self, mut writer: W, scale: usize) -> Result<(), RenderError> {
+        let (width, height) = self.image_size(scale)?;
+        write!(writer, "P6\n{} {}\n255\n", width, height)?;
+        writer.write_all(&self.rgb_pixels(scale))?;
+        Ok(())
+    }
+
+    /// writes the frame as a PNG image. every cell becomes a `scale` x `scale`
+    /// square.
+    pub fn write_png<W: Write>(&self, writer: W, scale: usize) -> Result<(), RenderError> {
+        let (width, height) = self.image_size(scale)?;
+        let mut encoder = png::Encoder::new(writer, width, height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.rgb_pixels(scale))?;
+        writer.finish()?;
+        Ok(())
+    }
+
+    /// writes the frame in the given format.
+    pub fn write<W: Write>(
+        &self,
+        mut writer: W,
+        format: ImageFormat,
+        scale: usize,
+    ) -> Result<(), RenderError> {
+        match format {
+            ImageFormat::Ascii => writer.write_all(self.to_ascii().as_bytes())?,
+            ImageFormat::Ppm => self.write_ppm(writer, scale)?,
+            ImageFormat::Png => self.write_png(writer, scale)?,
+        }
+        Ok(())
+    }
+
+    /// writes the frame to a file. the format is derived from the file
+    /// extension.
+    pub fn save(&self, path: &Path, scale: usize) -> Result<(), RenderError> {
+        let format = ImageFormat::from_path(path)?;
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write(&mut writer, format, scale)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    fn image_size(&self, scale: usize) -> Result<(u32, u32), RenderError> {
+        let too_large = || {
+            RenderError::FrameTooLarge {
+                width: self.width,
+                height: self.height,
+            }
+        };
+        let width = self.width.checked_mul(scale).ok_or_else(too_large)?;
+        let height = self.height.checked_mul(scale).ok_or_else(too_large)?;
+        Ok((
+            width.try_into().map_err(|_| too_large())?,
+            height.try_into().map_err(|_| too_large())?,
+        ))
+    }
+
+    /// returns the scaled image as RGB bytes.
+    fn rgb_pixels(&self, scale: usize) -> Vec<u8> {
+        self.scaled_pixels(scale, 3, |cell, pixels| {
+            pixels.extend_from_slice(&[cell.color.0, cell.color.1, cell.color.2])
+        })
+    }
+
+    /// returns the scaled image, with every pixel produced by `pixel`.
+    fn scaled_pixels(
+        &self,
+        scale: usize,
+        bytes_per_pixel: usize,
+        mut pixel: impl FnMut(&Cell, &mut Vec<u8>),
+    ) -> Vec<u8> {
+        let mut pixels = Vec::with_capacity(self.cells.len() * scale * scale * bytes_per_pixel);
+
+        for row in self.cells.chunks(self.width.max(1)) {
+            let row_start = pixels.len();
+            for cell in row {
+                for _ in 0..scale {
+                    pixel(cell, &mut pixels);
+                }
+            }
+            let row_end = pixels.len();
+            for _ in 1..scale {
+                pixels.extend_from_within(row_start..row_end);
+            }
+        }
+
+        pixels
+    }
+}
+
+impl fmt::Display for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.cells.chunks(self.width.max(1)) {
+            for cell in row {
+                write!(f, "{}", cell.glyph)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// something that can be drawn into a [`Frame`].
+pub trait Render {
+    /// returns the area that contains everything worth showing.
+    fn bounds(&self) -> BoundingBox<i32>;
+
+    /// draws `area` into a new frame.
+    ///
+    /// animations use this to render every step with the same size.
+    fn render_area(&self, area: BoundingBox<i32>) -> Frame;
+
+    /// draws everything inside of [`Render::bounds`].
+    fn render(&self) -> Frame {
+        self.render_area(self.bounds())
+    }
+}
+
+#[cfg(feature = "render")]
+/// file formats for single frames.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ImageFormat {
+    Ascii,
+    Ppm,
+    Png,
+}
+
+#[cfg(feature = "render")]
+impl ImageFormat {
+    /// derives the format from the extension of `path`.
+    pub fn from_path(path: &Path) -> Result<Self, RenderError> {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or_default();
+        match extension {
+            "txt" => Ok(Self::Ascii),
+            "ppm" => Ok(Self::Ppm),
+            "png" => Ok(Self::Png),
+            _ => Err(RenderError::UnknownFormat(path.display().to_string())),
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Ascii => "txt",
+            Self::Ppm => "ppm",
+            Self::Png => "png",
+        }
+    }
+}
+
+/// receives the frames of an animation.
+pub trait FrameSink {
+    fn push_frame(&mut self, frame: &Frame) -> Result<(), RenderError>;
+}
+
+impl FrameSink for Vec<Frame> {
+    fn push_frame(&mut self, frame: &Frame) -> Result<(), RenderError> {
+        self.push(frame.clone());
+        Ok(())
+    }
+}
+
+/// writes frames as text, separated by empty lines.
+pub struct TextFrames<W: Write>(pub W);
+
+impl<W: Write> FrameSink for TextFrames<W> {
+    fn push_frame(&mut self, frame: &Frame) -> Result<(), RenderError> {
+        writeln!(self.0, "{}", frame)?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "render")]
+/// writes every frame to its own numbered file (`frame_00000.png`, ...) in a
+/// directory.
+pub struct FrameDirectory {
+    path: PathBuf,
+    format: ImageFormat,
+    scale: usize,
+    num_frames: usize,
+}
+
+#[cfg(feature = "render")]
+impl FrameDirectory {
+    /// creates the directory if it doesn't exist yet.
+    pub fn create(
+        path: impl Into<PathBuf>,
+        format: ImageFormat,
+        scale: usize,
+    ) -> Result<Self, RenderError> {
+        let path = path.into();
+        fs::create_dir_all(&path)?;
+        Ok(Self {
+            path,
+            format,
+            scale,
+            num_frames: 0,
+        })
+    }
+
+    pub fn num_frames(&self) -> usize {
+        self.num_frames
+    }
+}
+
+#[cfg(feature = "render")]
+impl FrameSink for FrameDirectory {
+    fn push_frame(&mut self, frame: &Frame) -> Result<(), RenderError> {
+        let path = self.path.join(format!(
+            "frame_{:05}.{}",
+            self.num_frames,
+            self.format.extension()
+        ));
+        let mut writer = BufWriter::new(File::create(path)?);
+        frame.write(&mut writer, self.format, self.scale)?;
+        writer.flush()?;
+        self.num_frames += 1;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "render")]
+/// encodes frames as an animated GIF that loops forever.
+///
+/// all frames must have the same size as the first one. the GIF is completed
+/// when the animation is dropped or [`GifAnimation::finish`] is called.
+pub struct GifAnimation<W: Write> {
+    /// the writer until the first frame is pushed, because the encoder needs
+    /// to know the size of the image.
+    writer: Option<W>,
+    encoder: Option<gif::Encoder<W>>,
+    scale: usize,
+    delay: u16,
+    size: (usize, usize),
+}
+
+#[cfg(feature = "render")]
+impl<W: Write> GifAnimation<W> {
+    /// `delay` is the time between frames in hundredths of a second.
+    pub fn new(writer: W, scale: usize, delay: u16) -> Self {
+        Self {
+            writer: Some(writer),
+            encoder: None,
+            scale,
+            delay,
+            size: (0, 0),
+        }
+    }
+
+    /// writes the end of the GIF and returns the writer.
+    ///
+    /// returns `None` if no frame was pushed, in which case nothing was
+    /// written.
+    pub fn finish(mut self) -> Result<Option<W>, RenderError> {
+        match self.encoder.take() {
+            Some(encoder) => Ok(Some(encoder.into_inner()?)),
+            None => Ok(self.writer.take()),
+        }
+    }
+}
+
+#[cfg(feature = "render")]
+impl GifAnimation<BufWriter<File>> {
+    pub fn create(path: &Path, scale: usize, delay: u16) -> Result<Self, RenderError> {
+        Ok(Self::new(BufWriter::new(File::create(path)?), scale, delay))
+    }
+}
+
+#[cfg(feature = "render")]
+impl<W: Write> FrameSink for GifAnimation<W> {
+    fn push_frame(&mut self, frame: &Frame) -> Result<(), RenderError> {
+        let (width, height) = frame.image_size(self.scale)?;
+        let (Ok(width), Ok(height)) = (u16::try_from(width), u16::try_from(height))
+        else {
+            return Err(RenderError::FrameTooLarge {
+                width: frame.width,
+                height: frame.height,
+            });
+        };
+
+        if let Some(writer) = self.writer.take() {
+            let mut encoder = gif::Encoder::new(writer, width, height, &[])?;
+            encoder.set_repeat(gif::Repeat::Infinite)?;
+            self.encoder = Some(encoder);
+            self.size = (frame.width, frame.height);
+        }
+        else if self.size != (frame.width, frame.height) {
+            return Err(RenderError::FrameSize {
+                width: frame.width,
+                height: frame.height,
+                expected_width: self.size.0,
+                expected_height: self.size.1,
+            });
+        }
+
+        // the renders only use a handful of colors, so every frame gets its own
+        // exact palette instead of being quantized.
+        let mut palette: Vec<Rgb> = vec![];
+        for cell in &frame.cells {
+            if !palette.contains(&cell.color) {
+                if palette.len() == 256 {
+                    return Err(RenderError::TooManyColors);
+                }
+                palette.push(cell.color);
+            }
+        }
+        let pixels = frame.scaled_pixels(self.scale, 1, |cell, pixels| {
+            let index = palette.iter().position(|color| *color == cell.color);
+            pixels.push(index.unwrap() as u8);
+        });
+        let palette: Vec<u8> = palette
+            .iter()
+            .flat_map(|color| [color.0, color.1, color.2])
+            .collect();
+
+        let mut gif_frame = gif::Frame::from_palette_pixels(width, height, pixels, palette, None);
+        gif_frame.delay = self.delay;
+
+        self.encoder
+            .as_mut()
+            .expect("encoder is created for the first frame")
+            .write_frame(&gif_frame)?;
+
+        Ok(())
+    }
+}
+
+/// an observer that pushes a frame of the simulation into a sink before the
+/// first and after every step. every frame shows the same area, so that they
+/// all have the same size.
+///
+/// the first error of the sink stops the simulation, and is returned by
+/// [`Animation::finish`].
+pub struct Animation<'a> {
+    sink: &'a mut dyn FrameSink,
+    area: BoundingBox<i32>,
+    result: Result<(), RenderError>,
+}
+
+impl<'a> Animation<'a> {
+    pub fn new(sink: &'a mut dyn FrameSink, area: BoundingBox<i32>) -> Self {
+        Self {
+            sink,
+            area,
+            result: Ok(()),
+        }
+    }
+
+    /// returns the first error of the sink.
+    pub fn finish(self) -> Result<(), RenderError> {
+        self.result
+    }
+
+    fn push_frame(&mut self, state: &dyn Render) -> ControlFlow<()> {
+        self.result = self.sink.push_frame(&state.render_area(self.area));
+        if self.result.is_ok() {
+            ControlFlow::Continue(())
+        }
+        else {
+            ControlFlow::Break(())
+        }
+    }
+}
+
+impl<'a, S: Render> Observer<S> for Animation<'a> {
+    fn on_start(&mut self, simulation: &S) -> ControlFlow<()> {
+        self.push_frame(simulation)
+    }
+
+    fn on_step(&mut self, simulation: &S) -> ControlFlow<()> {
+        self.push_frame(simulation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOT: Cell = Cell::new('.', Rgb::BLACK);
+    const HASH: Cell = Cell::new('#', Rgb(255, 255, 255));
+
+    fn checkerboard() -> Frame {
+        let mut frame = Frame::new(3, 2, DOT);
+        frame.set(0, 0, HASH);
+        frame.set(2, 0, HASH);
+        frame.set(1, 1, HASH);
+        frame
+    }
+
+    #[test]
+    fn ascii() {
+        assert_eq!(checkerboard().to_ascii(), "#.#\n.#.\n");
+    }
+
+    #[test]
+    fn set_outside_is_ignored() {
+        let mut frame = checkerboard();
+        frame.set(3, 0, DOT);
+        frame.set(0, 2, DOT);
+        assert_eq!(frame, checkerboard());
+        assert_eq!(frame.get(3, 0), None);
+    }
+
+    #[cfg(feature = "render")]
+    #[test]
+    fn ppm() {
+        let mut ppm = vec![];
+        checkerboard().write_ppm(&mut ppm, 3).unwrap();
+
+        let header = b"P6\n9 6\n255\n";
+        assert_eq!(&ppm[..header.len()], header);
+
+        let pixels = &ppm[header.len()..];
+        let row_length = 9 * 3;
+        assert_eq!(pixels.len(), 6 * row_length);
+        // the first three rows are the same, and every cell is 3 pixels wide.
+        assert_eq!(&pixels[..row_length], &pixels[row_length..2 * row_length]);
+        assert_eq!(
+            &pixels[..row_length],
+            &pixels[2 * row_length..3 * row_length]
+        );
+        assert_ne!(
+            &pixels[..row_length],
+            &pixels[3 * row_length..4 * row_length]
+        );
+        assert_eq!(
+            &pixels[..12],
+            &[255, 255, 255, 255, 255, 255, 255, 255, 255, 0, 0, 0]
+        );
+    }
+
+    #[cfg(feature = "render")]
+    #[test]
+    fn png_signature() {
+        let mut png = vec![];
+        checkerboard().write_png(&mut png, 1).unwrap();
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+    }
+
+    #[cfg(feature = "render")]
+    #[test]
+    fn gif_rejects_different_frame_sizes() {
+        let mut gif = GifAnimation::new(vec![], 1, 10);
+        gif.push_frame(&checkerboard()).unwrap();
+        gif.push_frame(&checkerboard()).unwrap();
+
+        let error = gif.push_frame(&Frame::new(2, 2, DOT)).unwrap_err();
+        assert!(matches!(error, RenderError::FrameSize { .. }));
+
+        let gif = gif.finish().unwrap().unwrap();
+        assert_eq!(&gif[..6], b"GIF89a");
+        assert_eq!(gif.last(), Some(&0x3b));
+    }
+
+    #[cfg(feature = "render")]
+    #[test]
+    fn image_format_from_path() {
+        assert_eq!(
+            ImageFormat::from_path(Path::new("out/frame.png")).unwrap(),
+            ImageFormat::Png
+        );
+        assert!(ImageFormat::from_path(Path::new("frame.jpg")).is_err());
+    }
+}
@@ -0,0 +1,88 @@
+use std::{
+    fmt::{
+        self,
+        Display,
+    },
+    time::Duration,
+};
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use thiserror::Error;
+
+use crate::{
+    error,
+    memory::AllocStats,
+};
+
+#[derive(Debug, Error)]
+pub enum RunError {
+    #[error("day {0} is not implemented")]
+    UnknownDay(u8),
+    #[error("generator for day {day} failed")]
+    Generator {
+        day: u8,
+        #[source]
+        source: error::Error,
+    },
+    #[error("{part} of day {day} failed")]
+    Runner {
+        day: u8,
+        part: Part,
+        #[source]
+        source: error::Error,
+    },
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Part {
+    Part1,
+    Part2,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::Part1, Part::Part2];
+
+    pub fn from_number(number: u8) -> Option<Self> {
+        match number {
+            1 => Some(Part::Part1),
+            2 => Some(Part::Part2),
+            _ => None,
+        }
+    }
+
+    pub fn number(self) -> u8 {
+        match self {
+            Part::Part1 => 1,
+            Part::Part2 => 2,
+        }
+    }
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "part {}", self.number())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PartRun {
+    pub part: Part,
+    pub answer: String,
+    pub runner_time: Duration,
+    /// the allocations of the part, if the `alloc-profile` feature counts
+    /// them.
+    pub runner_allocs: Option<AllocStats>,
+}
+
+#[derive(Clone, Debug)]
+pub struct DayRun {
+    pub day: u8,
+    pub generator_time: Duration,
+    /// the allocations of the generator, if the `alloc-profile` feature counts
+    /// them.
+    pub generator_allocs: Option<AllocStats>,
+    pub parts: Vec<PartRun>,
+}
@@ -0,0 +1,381 @@
+//! creates new days from `src/day_template.rs`.
+//!
+//! the template spells the day as `dayX`, `DayX` and a bare `X`, e.g.
+//! `dayX_part1` or `const DAY: u8 = X;`. besides writing the module, the day
+//! is registered in `src/lib.rs` and in the solver registry in
+//! `src/solution.rs`, behind its `day<N>` feature, and placeholders are created
+//! for its input and answers.
+
+use std::{
+    fs::{
+        self,
+        OpenOptions,
+    },
+    io::{
+        self,
+        Write,
+    },
+    path::PathBuf,
+};
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use thiserror::Error;
+
+use crate::answers::{
+    Answers,
+    AnswersError,
+};
+
+lazy_static! {
+    static ref DAY_NUMBER_REGEX: Regex = r"\bX\b".parse().unwrap();
+    static ref MODULE_REGEX: Regex = r"^pub mod (\w+);$".parse().unwrap();
+    static ref IMPORT_REGEX: Regex = r"^use crate::(day\d+)::Day\d+;$".parse().unwrap();
+    static ref SOLVER_REGEX: Regex = r"^        Box::new\(Day(\d+)\),$".parse().unwrap();
+}
+
+#[derive(Debug, Error)]
+pub enum ScaffoldError {
+    #[error("io error")]
+    Io(#[from] io::Error),
+    #[error("days range from 1 to 25, got {0}")]
+    InvalidDay(u8),
+    #[error("day {0} already exists")]
+    DayExists(u8),
+    #[error("can't find where to register the day in {}", .0.display())]
+    NoRegistry(PathBuf),
+    #[error("failed to update answers")]
+    Answers(#[from] AnswersError),
+}
+
+/// where new days are created.
+#[derive(Clone, Debug)]
+pub struct Scaffold {
+    /// the `src` directory of the crate, which has to contain the template.
+    pub src_dir: PathBuf,
+    /// the directory that gets the placeholder input.
+    pub input_dir: PathBuf,
+    /// the answers file that gets the placeholder table.
+    pub answers_path: PathBuf,
+}
+
+/// files touched by [`Scaffold::new_day`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NewDay {
+    pub created: Vec<PathBuf>,
+    pub updated: Vec<PathBuf>,
+}
+
+impl Scaffold {
+    /// creates `src/day<N>.rs` from the template and registers it.
+    ///
+    /// nothing is written if the module or its registration already exists.
+    /// an existing input file or answers table is kept as it is.
+    pub fn new_day(&self, day: u8) -> Result<NewDay, ScaffoldError> {
+        if !(1..=25).contains(&day) {
+            return Err(ScaffoldError::InvalidDay(day));
+        }
+
+        let module_path = self.src_dir.join(format!("day{}.rs", day));
+        let lib_path = self.src_dir.join("lib.rs");
+        let solution_path = self.src_dir.join("solution.rs");
+
+        let feature = format!("#[cfg(feature = \"day{}\")]", day);
+        let module_line = format!("pub mod day{};", day);
+        let lib = fs::read_to_string(&lib_path)?;
+        if module_path.exists() || lib.lines().any(|line| line == module_line) {
+            return Err(ScaffoldError::DayExists(day));
+        }
+
+        // prepare all changes first, so that a missing registry doesn't leave
+        // a half registered day behind.
+        let template = fs::read_to_string(self.src_dir.join("day_template.rs"))?;
+        let module = instantiate(&template, day);
+        let lib = insert_line(
+            &lib,
+            &MODULE_REGEX,
+            &format!("{}\n{}", feature, module_line),
+            str::to_owned,
+        )
+        .ok_or_else(|| ScaffoldError::NoRegistry(lib_path.clone()))?;
+        let solution = fs::read_to_string(&solution_path)?;
+        let solution = insert_line(
+            &solution,
+            &IMPORT_REGEX,
+            &format!("{}\nuse crate::day{1}::Day{1};", feature, day),
+            str::to_owned,
+        )
+        .and_then(|solution| {
+            insert_line(
+                &solution,
+                &SOLVER_REGEX,
+                &format!("        {}\n        Box::new(Day{}),", feature, day),
+                solver_day,
+            )
+        })
+        .ok_or_else(|| ScaffoldError::NoRegistry(solution_path.clone()))?;
+
+        let mut new_day = NewDay::default();
+
+        OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&module_path)?
+            .write_all(module.as_bytes())?;
+        new_day.created.push(module_path);
+        fs::write(&lib_path, lib)?;
+        new_day.updated.push(lib_path);
+        fs::write(&solution_path, solution)?;
+        new_day.updated.push(solution_path);
+
+        let input_path = self.input_dir.join(format!("day{}.txt", day));
+        if !input_path.exists() {
+            fs::create_dir_all(&self.input_dir)?;
+            fs::write(&input_path, "")?;
+            new_day.created.push(input_path);
+        }
+
+        let answers_existed = self.answers_path.exists();
+        let mut answers = Answers::load(&self.answers_path)?;
+        if answers.add_day(day) {
+            answers.save(&self.answers_path)?;
+            if answers_existed {
+                new_day.updated.push(self.answers_path.clone());
+            }
+            else {
+                new_day.created.push(self.answers_path.clone());
+            }
+        }
+
+        Ok(new_day)
+    }
+}
+
+/// replaces the day placeholders in the template.
+fn instantiate(template: &str, day: u8) -> String {
+    let module = template
+        .replace("dayX", &format!("day{}", day))
+        .replace("DayX", &format!("Day{}", day));
+    DAY_NUMBER_REGEX
+        .replace_all(&module, day.to_string())
+        .into_owned()
+}
+
+fn solver_day(day: &str) -> u8 {
+    day.parse().unwrap()
+}
+
+/// inserts `line` into the lines of `source` that match `entry`, in front of
+/// the first one that sorts after it and its attributes. lines are sorted by
+/// `key` of the first capture group of `entry`. `line` can start with
+/// attributes of its own, the last line is the entry. returns `None` if no
+/// line matches.
+fn insert_line<K: Ord>(
+    source: &str,
+    entry: &Regex,
+    line: &str,
+    key: impl Fn(&str) -> K,
+) -> Option<String> {
+    let lines = source.split_inclusive('\n').collect::<Vec<_>>();
+    let sort_key = |line: &str| Some(key(entry.captures(line.trim_end())?.get(1)?.as_str()));
+    let entries = (0..lines.len())
+        .filter_map(|i| Some((i, sort_key(lines[i])?)))
+        .collect::<Vec<_>>();
+    let last = entries.last()?.0;
+
+    let new_key =
+        sort_key(line.lines().last().unwrap_or_default()).expect("new line matches the entries");
+    let mut index = entries
+        .into_iter()
+        .find(|(_, key)| *key > new_key)
+        .map_or(last + 1, |(i, _)| i);
+    while index > 0 && lines[index - 1].trim_start().starts_with("#[") {
+        index -= 1;
+    }
+
+    let mut result = lines[..index].concat();
+    result.push_str(line);
+    result.push('\n');
+    result.push_str(&lines[index..].concat());
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    const LIB: &str = "pub mod answers;
+#[cfg(feature = \"day1\")]
+pub mod day1;
+#[cfg(feature = \"day2\")]
+pub mod day2;
+#[cfg(feature = \"day9\")]
+pub mod day9;
+pub mod error;
+
+aoc_lib! { year = 2022 }
+";
+
+    const SOLUTION: &str = "#[cfg(feature = \"day1\")]
+use crate::day1::Day1;
+#[cfg(feature = \"day2\")]
+use crate::day2::Day2;
+#[cfg(feature = \"day9\")]
+use crate::day9::Day9;
+use crate::error::Error;
+
+pub fn solvers() -> Vec<Box<dyn Solver>> {
+    vec![
+        #[cfg(feature = \"day1\")]
+        Box::new(Day1),
+        #[cfg(feature = \"day2\")]
+        Box::new(Day2),
+        #[cfg(feature = \"day9\")]
+        Box::new(Day9),
+    ]
+}
+";
+
+    /// returns a scaffold for a new temporary directory with a minimal crate.
+    fn new_scaffold(name: &str) -> (Scaffold, PathBuf) {
+        let dir =
+            std::env::temp_dir().join(format!("aoc2022-scaffold-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let src_dir = dir.join("src");
+        fs::create_dir_all(&src_dir).unwrap();
+        fs::write(src_dir.join("lib.rs"), LIB).unwrap();
+        fs::write(src_dir.join("solution.rs"), SOLUTION).unwrap();
+        fs::write(
+            src_dir.join("day_template.rs"),
+            include_str!("day_template.rs"),
+        )
+        .unwrap();
+
+        let scaffold = Scaffold {
+            src_dir,
+            input_dir: dir.join("input"),
+            answers_path: dir.join("answers.toml"),
+        };
+        (scaffold, dir)
+    }
+
+    fn read(path: impl AsRef<Path>) -> String {
+        fs::read_to_string(path).unwrap()
+    }
+
+    #[test]
+    fn new_day() {
+        let (scaffold, dir) = new_scaffold("new-day");
+
+        let new_day = scaffold.new_day(16).unwrap();
+        assert_eq!(
+            new_day,
+            NewDay {
+                created: vec![
+                    dir.join("src/day16.rs"),
+                    dir.join("input/day16.txt"),
+                    dir.join("answers.toml"),
+                ],
+                updated: vec![dir.join("src/lib.rs"), dir.join("src/solution.rs")],
+            }
+        );
+
+        let module = read(dir.join("src/day16.rs"));
+        assert!(module.contains("#[aoc_generator(day16)]\npub fn day16_input("));
+        assert!(module.contains("#[aoc(day16, part2)]\npub fn day16_part2("));
+        assert!(module.contains("impl Solution for Day16 {\n    const DAY: u8 = 16;"));
+        assert!(!module.contains("dayX") && !module.contains("DayX"));
+
+        assert_eq!(
+            read(dir.join("src/lib.rs")),
+            LIB.replace(
+                "#[cfg(feature = \"day2\")]\npub mod day2;",
+                "#[cfg(feature = \"day16\")]\npub mod day16;\n#[cfg(feature = \"day2\")]\npub mod day2;"
+            )
+        );
+        assert_eq!(
+            read(dir.join("src/solution.rs")),
+            SOLUTION
+                .replace(
+                    "#[cfg(feature = \"day2\")]\nuse crate::day2::Day2;",
+                    "#[cfg(feature = \"day16\")]\nuse crate::day16::Day16;\n#[cfg(feature = \"day2\")]\nuse crate::day2::Day2;"
+                )
+                .replace(
+                    "        Box::new(Day9),",
+                    "        Box::new(Day9),\n        #[cfg(feature = \"day16\")]\n        Box::new(Day16),"
+                )
+        );
+        assert_eq!(read(dir.join("input/day16.txt")), "");
+        assert_eq!(read(dir.join("answers.toml")), "[day16]\n");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn keeps_existing_input_and_answers() {
+        let (scaffold, dir) = new_scaffold("keep");
+        fs::create_dir_all(&scaffold.input_dir).unwrap();
+        fs::write(dir.join("input/day3.txt"), "vJrwpWtwJgWrhcsFMMfFFhFp").unwrap();
+        fs::write(dir.join("answers.toml"), "[day3.ab12]\npart1 = \"157\"\n").unwrap();
+
+        let new_day = scaffold.new_day(3).unwrap();
+        assert_eq!(new_day.created, vec![dir.join("src/day3.rs")]);
+        assert_eq!(read(dir.join("input/day3.txt")), "vJrwpWtwJgWrhcsFMMfFFhFp");
+        assert_eq!(
+            read(dir.join("answers.toml")),
+            "[day3.ab12]\npart1 = \"157\"\n"
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn refuses_existing_day() {
+        let (scaffold, dir) = new_scaffold("existing");
+        fs::write(dir.join("src/day2.rs"), "// solved").unwrap();
+
+        assert!(matches!(
+            scaffold.new_day(2),
+            Err(ScaffoldError::DayExists(2))
+        ));
+        assert_eq!(read(dir.join("src/day2.rs")), "// solved");
+        assert_eq!(read(dir.join("src/lib.rs")), LIB);
+
+        // registered, but the module is missing
+        assert!(matches!(
+            scaffold.new_day(9),
+            Err(ScaffoldError::DayExists(9))
+        ));
+        assert!(!dir.join("src/day9.rs").exists());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn missing_registry() {
+        let (scaffold, dir) = new_scaffold("registry");
+        fs::write(dir.join("src/solution.rs"), "").unwrap();
+
+        assert!(matches!(
+            scaffold.new_day(4),
+            Err(ScaffoldError::NoRegistry(_))
+        ));
+        assert!(!dir.join("src/day4.rs").exists());
+        assert_eq!(read(dir.join("src/lib.rs")), LIB);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn invalid_day() {
+        let (scaffold, dir) = new_scaffold("invalid");
+        assert!(matches!(
+            scaffold.new_day(26),
+            Err(ScaffoldError::InvalidDay(26))
+        ));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
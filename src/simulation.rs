@@ -0,0 +1,240 @@
+//! a common interface for the puzzles that are simulated step by step.
+//!
+//! a [`Simulation`] only knows how to take its next step. running it, stopping
+//! it at an interesting state and watching it along the way is the same for
+//! all of them: an [`Observer`] is told about the state before the first and
+//! after every step, which is enough to render animations, log progress or
+//! collect statistics without touching the loops of the simulations.
+
+use std::ops::ControlFlow;
+
+pub trait Simulation {
+    /// takes the next step. returns `false` without changing anything if the
+    /// simulation is finished.
+    fn step(&mut self) -> bool;
+
+    /// the number of steps taken so far.
+    fn steps(&self) -> usize;
+
+    fn is_finished(&self) -> bool;
+
+    /// takes steps until `predicate` holds or the simulation is finished, and
+    /// returns the number of steps taken. `predicate` is checked before every
+    /// step, so nothing happens if it already holds.
+    fn run_until(&mut self, predicate: impl FnMut(&Self) -> bool) -> usize
+    where
+        Self: Sized,
+    {
+        self.run_until_with(&mut (), predicate)
+    }
+
+    /// runs until the simulation is finished, and returns the number of steps
+    /// taken.
+    fn run(&mut self) -> usize
+    where
+        Self: Sized,
+    {
+        self.run_until(|_| false)
+    }
+
+    /// like [`Simulation::run_until`], but tells `observer` about every step.
+    /// stops early if the observer breaks.
+    fn run_until_with(
+        &mut self,
+        observer: &mut dyn Observer<Self>,
+        mut predicate: impl FnMut(&Self) -> bool,
+    ) -> usize
+    where
+        Self: Sized,
+    {
+        let mut steps = 0;
+
+        if observer.on_start(self).is_continue() {
+            while !predicate(self) && self.step() {
+                steps += 1;
+                if observer.on_step(self).is_break() {
+                    break;
+                }
+            }
+        }
+
+        observer.on_finish(self);
+        steps
+    }
+
+    /// like [`Simulation::run`], but tells `observer` about every step.
+    fn run_with(&mut self, observer: &mut dyn Observer<Self>) -> usize
+    where
+        Self: Sized,
+    {
+        self.run_until_with(observer, |_| false)
+    }
+}
+
+/// watches a simulation while it runs.
+///
+/// closures taking the simulation are observers that are called after every
+/// step. several observers can be attached at once by collecting them in a
+/// `Vec`.
+pub trait Observer<S: ?Sized> {
+    /// called with the state before the first step of a run.
+    fn on_start(&mut self, _simulation: &S) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// called after every step.
+    fn on_step(&mut self, simulation: &S) -> ControlFlow<()>;
+
+    /// called once the run ended, whether the simulation is finished or not.
+    fn on_finish(&mut self, _simulation: &S) {}
+}
+
+/// doesn't observe anything.
+impl<S: ?Sized> Observer<S> for () {
+    fn on_step(&mut self, _simulation: &S) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+}
+
+impl<S: ?Sized, F: FnMut(&S)> Observer<S> for F {
+    fn on_step(&mut self, simulation: &S) -> ControlFlow<()> {
+        self(simulation);
+        ControlFlow::Continue(())
+    }
+}
+
+/// tells every observer, in order. the run stops if any of them breaks, but
+/// the others still see the step that made it break.
+impl<S: ?Sized> Observer<S> for Vec<Box<dyn Observer<S> + '_>> {
+    fn on_start(&mut self, simulation: &S) -> ControlFlow<()> {
+        let mut flow = ControlFlow::Continue(());
+        for observer in self {
+            if observer.on_start(simulation).is_break() {
+                flow = ControlFlow::Break(());
+            }
+        }
+        flow
+    }
+
+    fn on_step(&mut self, simulation: &S) -> ControlFlow<()> {
+        let mut flow = ControlFlow::Continue(());
+        for observer in self {
+            if observer.on_step(simulation).is_break() {
+                flow = ControlFlow::Break(());
+            }
+        }
+        flow
+    }
+
+    fn on_finish(&mut self, simulation: &S) {
+        for observer in self {
+            observer.on_finish(simulation);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// counts up to `limit`.
+    struct Counter {
+        value: usize,
+        limit: usize,
+    }
+
+    impl Simulation for Counter {
+        fn step(&mut self) -> bool {
+            if self.is_finished() {
+                return false;
+            }
+            self.value += 1;
+            true
+        }
+
+        fn steps(&self) -> usize {
+            self.value
+        }
+
+        fn is_finished(&self) -> bool {
+            self.value >= self.limit
+        }
+    }
+
+    fn counter(limit: usize) -> Counter {
+        Counter { value: 0, limit }
+    }
+
+    /// records everything it's told, and breaks at `stop_at`.
+    #[derive(Default)]
+    struct Recorder {
+        events: Vec<String>,
+        stop_at: Option<usize>,
+    }
+
+    impl Observer<Counter> for Recorder {
+        fn on_start(&mut self, counter: &Counter) -> ControlFlow<()> {
+            self.events.push(format!("start {}", counter.value));
+            ControlFlow::Continue(())
+        }
+
+        fn on_step(&mut self, counter: &Counter) -> ControlFlow<()> {
+            self.events.push(format!("step {}", counter.value));
+            if self.stop_at == Some(counter.value) {
+                ControlFlow::Break(())
+            }
+            else {
+                ControlFlow::Continue(())
+            }
+        }
+
+        fn on_finish(&mut self, counter: &Counter) {
+            self.events.push(format!("finish {}", counter.value));
+        }
+    }
+
+    #[test]
+    fn run() {
+        let mut counter = counter(5);
+        assert_eq!(counter.run(), 5);
+        assert!(counter.is_finished());
+        assert!(!counter.step());
+        assert_eq!(counter.run(), 0);
+    }
+
+    #[test]
+    fn run_until() {
+        let mut counter = counter(5);
+        assert_eq!(counter.run_until(|counter| counter.steps() == 2), 2);
+        assert_eq!(counter.run_until(|counter| counter.steps() == 2), 0);
+        assert_eq!(counter.run_until(|counter| counter.steps() == 10), 3);
+    }
+
+    #[test]
+    fn observers() {
+        let mut recorder = Recorder::default();
+        counter(2).run_with(&mut recorder);
+        assert_eq!(recorder.events, ["start 0", "step 1", "step 2", "finish 2"]);
+
+        let mut sum = 0;
+        counter(4).run_with(&mut |counter: &Counter| sum += counter.value);
+        assert_eq!(sum, 10);
+    }
+
+    #[test]
+    fn observer_breaks() {
+        let mut counter = counter(5);
+        let mut steps = vec![];
+        let mut observers: Vec<Box<dyn Observer<Counter>>> = vec![
+            Box::new(Recorder {
+                stop_at: Some(1),
+                ..Default::default()
+            }),
+            Box::new(|counter: &Counter| steps.push(counter.value)),
+        ];
+
+        assert_eq!(counter.run_with(&mut observers), 1);
+        drop(observers);
+        assert_eq!(steps, [1]);
+    }
+}
@@ -0,0 +1,238 @@
+use std::{
+    fmt::Display,
+    time::Instant,
+};
+
+use tracing::{
+    info,
+    info_span,
+};
+
+#[cfg(feature = "day1")]
+use crate::day1::Day1;
+#[cfg(feature = "day10")]
+use crate::day10::Day10;
+#[cfg(feature = "day11")]
+use crate::day11::Day11;
+#[cfg(feature = "day12")]
+use crate::day12::Day12;
+#[cfg(feature = "day13")]
+use crate::day13::Day13;
+#[cfg(feature = "day14")]
+use crate::day14::Day14;
+#[cfg(feature = "day15")]
+use crate::day15::Day15;
+#[cfg(feature = "day2")]
+use crate::day2::Day2;
+#[cfg(feature = "day3")]
+use crate::day3::Day3;
+#[cfg(feature = "day4")]
+use crate::day4::Day4;
+#[cfg(feature = "day5")]
+use crate::day5::Day5;
+#[cfg(feature = "day6")]
+use crate::day6::Day6;
+#[cfg(feature = "day7")]
+use crate::day7::Day7;
+#[cfg(feature = "day8")]
+use crate::day8::Day8;
+#[cfg(feature = "day9")]
+use crate::day9::Day9;
+use crate::{
+    error::Error,
+    memory,
+    runner::{
+        DayRun,
+        Part,
+        PartRun,
+        RunError,
+    },
+};
+
+/// a solution for a single day.
+///
+/// the aoc-runner functions (`dayN_input`, `dayN_part1`, `dayN_part2`) are
+/// still the actual implementation, this trait just gives them typed names
+/// that can be used from outside the crate.
+pub trait Solution {
+    /// the day of the puzzle
+    const DAY: u8;
+
+    /// the parsed puzzle input
+    type Input;
+
+    /// answer of part 1
+    type Part1: Display;
+
+    /// answer of part 2
+    type Part2: Display;
+
+    fn parse(input: &str) -> Result<Self::Input, Error>;
+
+    fn part1(input: &Self::Input) -> Result<Self::Part1, Error>;
+
+    fn part2(input: &Self::Input) -> Result<Self::Part2, Error>;
+}
+
+/// object-safe version of [`Solution`] that works on the raw input and
+/// returns the answers as strings.
+///
+/// this is implemented for every [`Solution`]. solvers can be shared between
+/// threads, so that days can run in parallel.
+pub trait Solver: Send + Sync {
+    fn day(&self) -> u8;
+
+    /// parses the input and runs the requested parts on it.
+    ///
+    /// the input is expected to have trailing newlines stripped, just like
+    /// cargo-aoc does it.
+    fn run(&self, input: &str, parts: &[Part]) -> Result<DayRun, RunError>;
+}
+
+impl<S: Solution + Send + Sync> Solver for S {
+    fn day(&self) -> u8 {
+        S::DAY
+    }
+
+    fn run(&self, input: &str, parts: &[Part]) -> Result<DayRun, RunError> {
+        let _day = info_span!("day", day = S::DAY).entered();
+
+        let start_time = Instant::now();
+        let (parsed, generator_allocs) = memory::measure(|| S::parse(input));
+        let parsed = parsed.map_err(|source| {
+            RunError::Generator {
+                day: S::DAY,
+                source,
+            }
+        })?;
+        let generator_time = start_time.elapsed();
+        info!(elapsed = ?generator_time, allocs = ?generator_allocs, "parsed input");
+
+        let mut runs = Vec::with_capacity(parts.len());
+
+        for part in parts {
+            let _part = info_span!("part", part = part.number()).entered();
+
+            let start_time = Instant::now();
+            let (answer, runner_allocs) = memory::measure(|| {
+                match part {
+                    Part::Part1 => S::part1(&parsed).map(|answer| answer.to_string()),
+                    Part::Part2 => S::part2(&parsed).map(|answer| answer.to_string()),
+                }
+            });
+            let answer = answer.map_err(|source| {
+                RunError::Runner {
+                    day: S::DAY,
+                    part: *part,
+                    source,
+                }
+            })?;
+            let runner_time = start_time.elapsed();
+            info!(elapsed = ?runner_time, allocs = ?runner_allocs, "solved");
+
+            runs.push(PartRun {
+                part: *part,
+                answer,
+                runner_time,
+                runner_allocs,
+            });
+        }
+
+        Ok(DayRun {
+            day: S::DAY,
+            generator_time,
+            generator_allocs,
+            parts: runs,
+        })
+    }
+}
+
+/// returns solvers for all enabled days, ordered by day. every day has a cargo
+/// feature `day<N>`, and all of them are enabled by default.
+pub fn solvers() -> Vec<Box<dyn Solver>> {
+    vec![
+        #[cfg(feature = "day1")]
+        Box::new(Day1),
+        #[cfg(feature = "day2")]
+        Box::new(Day2),
+        #[cfg(feature = "day3")]
+        Box::new(Day3),
+        #[cfg(feature = "day4")]
+        Box::new(Day4),
+        #[cfg(feature = "day5")]
+        Box::new(Day5),
+        #[cfg(feature = "day6")]
+        Box::new(Day6),
+        #[cfg(feature = "day7")]
+        Box::new(Day7),
+        #[cfg(feature = "day8")]
+        Box::new(Day8),
+        #[cfg(feature = "day9")]
+        Box::new(Day9),
+        #[cfg(feature = "day10")]
+        Box::new(Day10),
+        #[cfg(feature = "day11")]
+        Box::new(Day11),
+        #[cfg(feature = "day12")]
+        Box::new(Day12),
+        #[cfg(feature = "day13")]
+        Box::new(Day13),
+        #[cfg(feature = "day14")]
+        Box::new(Day14),
+        #[cfg(feature = "day15")]
+        Box::new(Day15),
+    ]
+}
+
+/// returns the solver for a day.
+pub fn solver(day: u8) -> Result<Box<dyn Solver>, RunError> {
+    solvers()
+        .into_iter()
+        .find(|solver| solver.day() == day)
+        .ok_or(RunError::UnknownDay(day))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solvers_are_ordered_by_day() {
+        let days = solvers()
+            .iter()
+            .map(|solver| solver.day())
+            .collect::<Vec<_>>();
+        assert!(days.windows(2).all(|pair| pair[0] < pair[1]), "{:?}", days);
+        #[cfg(feature = "default")]
+        assert!(
+            days.starts_with(&(1..=15).collect::<Vec<_>>()),
+            "{:?}",
+            days
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "day2")]
+    fn solver_runs_requested_parts() {
+        let day_run = solver(2)
+            .unwrap()
+            .run("A Y\nB X\nC Z", &[Part::Part2])
+            .unwrap();
+        assert_eq!(day_run.day, 2);
+        assert_eq!(day_run.parts.len(), 1);
+        assert_eq!(day_run.parts[0].part, Part::Part2);
+        assert_eq!(day_run.parts[0].answer, "12");
+    }
+
+    #[test]
+    fn unknown_day() {
+        assert!(matches!(solver(25), Err(RunError::UnknownDay(25))));
+    }
+
+    #[test]
+    #[cfg(feature = "day2")]
+    fn typed_solution() {
+        let input = Day2::parse("A Y\nB X\nC Z").unwrap();
+        assert_eq!(Day2::part1(&input).unwrap(), 15);
+    }
+}
@@ -0,0 +1,450 @@
+//! solving days while their input arrives.
+//!
+//! the generators read the whole input before the solvers start. days whose
+//! puzzles can be solved line by line also implement [`Incremental`], which
+//! takes one line at a time and knows the answers for the input so far. a
+//! [`Stream`] feeds it from an [`io::BufRead`] and only keeps the current line
+//! in memory, so inputs of any size can be piped through. days whose input is
+//! a single line, like the signal of day 6, take that line in pieces.
+//!
+//! most days keep a constant amount of state. day 9 has to remember every
+//! position the tails visited, so its memory grows with the area they cover
+//! rather than with the input.
+
+use std::{
+    fmt::Display,
+    io::{
+        self,
+        BufRead,
+    },
+    str,
+};
+
+use thiserror::Error;
+
+#[cfg(feature = "day1")]
+use crate::day1::CalorieCounter;
+#[cfg(feature = "day10")]
+use crate::day10::Cpu;
+#[cfg(feature = "day2")]
+use crate::day2::ScoreCounter;
+#[cfg(feature = "day3")]
+use crate::day3::RucksackChecker;
+#[cfg(feature = "day4")]
+use crate::day4::AssignmentCounter;
+#[cfg(feature = "day6")]
+use crate::day6::SignalScanner;
+#[cfg(feature = "day9")]
+use crate::day9::TailTracker;
+use crate::{
+    error,
+    input::Line,
+    runner::Part,
+};
+
+#[derive(Debug, Error)]
+pub enum StreamError {
+    #[error("io error")]
+    Io(#[from] io::Error),
+    #[error("line {line} is not valid UTF-8")]
+    Utf8 { line: usize },
+    #[error("day {0} can't be streamed")]
+    UnknownDay(u8),
+    #[error("invalid input for day {day}")]
+    Input {
+        day: u8,
+        #[source]
+        source: error::Error,
+    },
+}
+
+/// a day that takes its input one line at a time.
+pub trait Incremental: Default {
+    /// the day of the puzzle
+    const DAY: u8;
+
+    /// whether a line can be pushed in several pieces, which all have the
+    /// number of that line. a [`Stream`] then doesn't have to keep long lines
+    /// in memory.
+    const SPLIT_LINES: bool = false;
+
+    type Part1: Display;
+    type Part2: Display;
+
+    /// takes the next line of the input, without its line break.
+    fn push_line(&mut self, line: Line<'_>) -> Result<(), error::Error>;
+
+    /// returns the answer of part 1 if the input ended after the lines pushed
+    /// so far.
+    fn part1(&self) -> Result<Self::Part1, error::Error>;
+
+    /// returns the answer of part 2 if the input ended after the lines pushed
+    /// so far.
+    fn part2(&self) -> Result<Self::Part2, error::Error>;
+}
+
+/// feeds the input of an [`Incremental`] day line by line, counting lines.
+#[derive(Debug, Default)]
+pub struct Stream<I> {
+    state: I,
+    /// the number of lines that ended so far.
+    lines: usize,
+    /// the start of the current line, if it arrived in pieces.
+    pending: Vec<u8>,
+}
+
+impl<I: Incremental> Stream<I> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn state(&self) -> &I {
+        &self.state
+    }
+
+    /// the number of lines that ended so far.
+    pub fn lines(&self) -> usize {
+        self.lines
+    }
+
+    /// takes the next line, without its line break.
+    pub fn push_line(&mut self, text: &str) -> Result<(), StreamError> {
+        self.push(text.as_bytes(), true)
+    }
+
+    /// reads lines from `reader` until it's exhausted, or until `max_lines`
+    /// lines ended. returns whether the reader is exhausted.
+    ///
+    /// a last line without a line break only counts once the reader is
+    /// exhausted, so `read` can be called again to continue after
+    /// `max_lines`.
+    pub fn read(
+        &mut self,
+        mut reader: impl BufRead,
+        max_lines: Option<usize>,
+    ) -> Result<bool, StreamError> {
+        let mut lines = 0;
+
+        while max_lines.is_none_or(|max_lines| lines < max_lines) {
+            let buffer = reader.fill_buf()?;
+            if buffer.is_empty() {
+                self.finish()?;
+                return Ok(true);
+            }
+
+            let consumed = match buffer.iter().position(|byte| *byte == b'\n') {
+                Some(end) => {
+                    self.push(&buffer[..end], true)?;
+                    lines += 1;
+                    end + 1
+                }
+                None => {
+                    self.push(buffer, false)?;
+                    buffer.len()
+                }
+            };
+            reader.consume(consumed);
+        }
+
+        Ok(false)
+    }
+
+    /// ends the last line if it didn't end with a line break.
+    pub fn finish(&mut self) -> Result<(), StreamError> {
+        if self.pending.is_empty() {
+            Ok(())
+        }
+        else {
+            self.push(&[], true)
+        }
+    }
+
+    /// takes a piece of the current line, and ends the line if `end` is set.
+    fn push(&mut self, bytes: &[u8], end: bool) -> Result<(), StreamError> {
+        let number = self.lines + 1;
+
+        if !end && !I::SPLIT_LINES {
+            self.pending.extend_from_slice(bytes);
+            return Ok(());
+        }
+
+        let bytes = if self.pending.is_empty() {
+            bytes
+        }
+        else {
+            self.pending.extend_from_slice(bytes);
+            &self.pending[..]
+        };
+
+        let (text, rest) = if end {
+            let text = str::from_utf8(bytes).map_err(|_| StreamError::Utf8 { line: number })?;
+            (text.strip_suffix('\r').unwrap_or(text), &[][..])
+        }
+        else {
+            // keep a character that's split between pieces, and a `\r` that
+            // might be part of the line break, for the next piece.
+            let valid = match str::from_utf8(bytes) {
+                Ok(_) => bytes.len(),
+                Err(e) if e.error_len().is_none() => e.valid_up_to(),
+                Err(_) => return Err(StreamError::Utf8 { line: number }),
+            };
+            let mut text = str::from_utf8(&bytes[..valid]).expect("checked above");
+            text = text.strip_suffix('\r').unwrap_or(text);
+            (text, &bytes[text.len()..])
+        };
+
+        if end || !text.is_empty() {
+            self.state
+                .push_line(Line { number, text })
+                .map_err(|source| {
+                    StreamError::Input {
+                        day: I::DAY,
+                        source,
+                    }
+                })?;
+        }
+
+        let rest = rest.to_vec();
+        self.pending.clear();
+        self.pending.extend_from_slice(&rest);
+        if end {
+            self.lines += 1;
+        }
+
+        Ok(())
+    }
+}
+
+/// a [`Stream`] of any day, so that days can be picked at runtime.
+pub trait StreamSolver {
+    fn day(&self) -> u8;
+
+    /// the number of lines that ended so far.
+    fn lines(&self) -> usize;
+
+    /// see [`Stream::push_line`].
+    fn push_line(&mut self, text: &str) -> Result<(), StreamError>;
+
+    /// see [`Stream::read`].
+    fn read(
+        &mut self,
+        reader: &mut dyn BufRead,
+        max_lines: Option<usize>,
+    ) -> Result<bool, StreamError>;
+
+    /// returns the answer of `part` for the input so far.
+    fn answer(&self, part: Part) -> Result<String, error::Error>;
+}
+
+impl<I: Incremental> StreamSolver for Stream<I> {
+    fn day(&self) -> u8 {
+        I::DAY
+    }
+
+    fn lines(&self) -> usize {
+        Stream::lines(self)
+    }
+
+    fn push_line(&mut self, text: &str) -> Result<(), StreamError> {
+        Stream::push_line(self, text)
+    }
+
+    fn read(
+        &mut self,
+        reader: &mut dyn BufRead,
+        max_lines: Option<usize>,
+    ) -> Result<bool, StreamError> {
+        Stream::read(self, reader, max_lines)
+    }
+
+    fn answer(&self, part: Part) -> Result<String, error::Error> {
+        match part {
+            Part::Part1 => self.state.part1().map(|answer| answer.to_string()),
+            Part::Part2 => self.state.part2().map(|answer| answer.to_string()),
+        }
+    }
+}
+
+/// returns empty streams for all enabled days that can be streamed, ordered
+/// by day.
+pub fn solvers() -> Vec<Box<dyn StreamSolver>> {
+    vec![
+        #[cfg(feature = "day1")]
+        Box::new(Stream::<CalorieCounter>::new()),
+        #[cfg(feature = "day2")]
+        Box::new(Stream::<ScoreCounter>::new()),
+        #[cfg(feature = "day3")]
+        Box::new(Stream::<RucksackChecker>::new()),
+        #[cfg(feature = "day4")]
+        Box::new(Stream::<AssignmentCounter>::new()),
+        #[cfg(feature = "day6")]
+        Box::new(Stream::<SignalScanner>::new()),
+        #[cfg(feature = "day9")]
+        Box::new(Stream::<TailTracker>::new()),
+        #[cfg(feature = "day10")]
+        Box::new(Stream::<Cpu>::new()),
+    ]
+}
+
+/// returns an empty stream for a day.
+pub fn solver(day: u8) -> Result<Box<dyn StreamSolver>, StreamError> {
+    solvers()
+        .into_iter()
+        .find(|solver| solver.day() == day)
+        .ok_or(StreamError::UnknownDay(day))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        io::BufReader,
+        path::Path,
+    };
+
+    use super::*;
+    use crate::solution;
+
+    /// records the lines it gets.
+    #[derive(Debug, Default)]
+    struct Recorder {
+        lines: Vec<(usize, String)>,
+    }
+
+    impl Incremental for Recorder {
+        const DAY: u8 = 0;
+
+        type Part1 = usize;
+        type Part2 = String;
+
+        fn push_line(&mut self, line: Line<'_>) -> Result<(), error::Error> {
+            if line.text == "x" {
+                return Err(line.error(line.text, "unexpected x"));
+            }
+            self.lines.push((line.number, line.text.to_owned()));
+            Ok(())
+        }
+
+        fn part1(&self) -> Result<Self::Part1, error::Error> {
+            Ok(self.lines.len())
+        }
+
+        fn part2(&self) -> Result<Self::Part2, error::Error> {
+            Ok(self
+                .lines
+                .last()
+                .map(|(_, text)| text.clone())
+                .unwrap_or_default())
+        }
+    }
+
+    /// like [`Recorder`], but takes pieces of lines.
+    #[derive(Debug, Default)]
+    struct PieceRecorder(Recorder);
+
+    impl Incremental for PieceRecorder {
+        const DAY: u8 = 0;
+        const SPLIT_LINES: bool = true;
+
+        type Part1 = usize;
+        type Part2 = String;
+
+        fn push_line(&mut self, line: Line<'_>) -> Result<(), error::Error> {
+            self.0.push_line(line)
+        }
+
+        fn part1(&self) -> Result<Self::Part1, error::Error> {
+            self.0.part1()
+        }
+
+        fn part2(&self) -> Result<Self::Part2, error::Error> {
+            self.0.part2()
+        }
+    }
+
+    fn lines(recorder: &Recorder) -> Vec<(usize, &str)> {
+        recorder
+            .lines
+            .iter()
+            .map(|(number, text)| (*number, text.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn read_lines() {
+        let input = "a\r\n\nbc\nd";
+        // a tiny buffer, so that lines arrive in pieces
+        let mut reader = BufReader::with_capacity(1, input.as_bytes());
+        let mut stream = Stream::<Recorder>::new();
+
+        assert!(!stream.read(&mut reader, Some(2)).unwrap());
+        assert_eq!(lines(stream.state()), [(1, "a"), (2, "")]);
+        assert!(stream.read(&mut reader, None).unwrap());
+        assert_eq!(
+            lines(stream.state()),
+            [(1, "a"), (2, ""), (3, "bc"), (4, "d")]
+        );
+        assert_eq!(stream.lines(), 4);
+    }
+
+    #[test]
+    fn read_pieces() {
+        let input = "aé\r\nbc";
+        let mut reader = BufReader::with_capacity(2, input.as_bytes());
+        let mut stream = Stream::<PieceRecorder>::new();
+        stream.read(&mut reader, None).unwrap();
+
+        // the `é` is split between two reads, and the `\r` is held back
+        // until it turns out to be part of the line break.
+        assert_eq!(
+            lines(&stream.state().0),
+            [(1, "a"), (1, "é"), (1, ""), (2, "b"), (2, "c")]
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let mut stream = Stream::<Recorder>::new();
+        stream.push_line("a").unwrap();
+        assert!(matches!(
+            stream.push_line("x"),
+            Err(StreamError::Input {
+                source: error::Error::Parse { line: 2, .. },
+                ..
+            })
+        ));
+
+        let mut stream = Stream::<Recorder>::new();
+        assert!(matches!(
+            stream.read(&b"a\n\xff\n"[..], None),
+            Err(StreamError::Utf8 { line: 2 })
+        ));
+    }
+
+    /// every day gives the same answers as its solver for a realistic input.
+    #[test]
+    fn same_answers() {
+        let inputs = Path::new(env!("CARGO_MANIFEST_DIR")).join("benches/inputs");
+
+        for mut stream in solvers() {
+            let day = stream.day();
+            let input =
+                fs::read_to_string(inputs.join(format!("day{}/realistic.txt", day))).unwrap();
+            stream.read(&mut input.as_bytes(), None).unwrap();
+
+            let day_run = solution::solver(day)
+                .unwrap()
+                .run(input.trim_end_matches('\n'), &Part::ALL)
+                .unwrap();
+            for part_run in day_run.parts {
+                assert_eq!(
+                    stream.answer(part_run.part).unwrap(),
+                    part_run.answer,
+                    "day {} {}",
+                    day,
+                    part_run.part
+                );
+            }
+        }
+    }
+}
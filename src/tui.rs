@@ -0,0 +1,738 @@
+//! an interactive viewer for the simulations that can be rendered.
+//!
+//! the viewer plays a [`Simulation`] in the terminal: it can be paused,
+//! stepped in both directions and rewound, and its [`Render`]ing can be
+//! scrolled and zoomed. a [`Player`] keeps a copy of the simulation every
+//! [`SNAPSHOT_INTERVAL`] steps, so going back only replays the steps since the
+//! last snapshot instead of the whole simulation.
+//!
+//! the terminal is driven by crossterm, which only needs a plain terminal and
+//! works fine over ssh. colors are sent as 24-bit colors if `COLORTERM` says
+//! that the terminal supports them, and as the closest of the 256 standard
+//! colors otherwise.
+
+use std::{
+    env,
+    io,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use ratatui::{
+    backend::Backend,
+    buffer::Buffer,
+    crossterm::event::{
+        self,
+        Event,
+        KeyCode,
+        KeyEvent,
+        KeyEventKind,
+    },
+    layout::{
+        Constraint,
+        Layout,
+        Rect,
+    },
+    style::{
+        Color,
+        Modifier,
+        Style,
+    },
+    text::{
+        Line,
+        Span,
+    },
+    widgets::{
+        Block,
+        Paragraph,
+        Widget,
+    },
+    Terminal,
+};
+
+#[cfg(feature = "day10")]
+use crate::day10::CpuSimulation;
+#[cfg(feature = "day14")]
+use crate::day14::Sandbox;
+#[cfg(feature = "day9")]
+use crate::day9::RopeSimulation;
+use crate::{
+    geometry::{
+        BoundingBox,
+        Point2,
+    },
+    render::{
+        Cell,
+        Frame,
+        Render,
+        Rgb,
+    },
+    simulation::Simulation,
+};
+
+/// the number of steps between two snapshots of a [`Player`].
+pub const SNAPSHOT_INTERVAL: usize = 256;
+
+/// the most cells that are shown by a single character.
+pub const MAX_ZOOM: i32 = 64;
+
+/// the time between two ticks while playing.
+const TICK: Duration = Duration::from_millis(50);
+
+/// the most steps taken in a single tick.
+const MAX_SPEED: usize = 1 << 16;
+
+/// the keys that are shown below the counters.
+const KEYS: &[(&str, &str)] = &[
+    ("space", "play/pause"),
+    ("n/b", "step"),
+    ("r", "reverse"),
+    ("[/]", "speed"),
+    ("home/end", "start/end"),
+    ("arrows", "scroll"),
+    ("+/-", "zoom"),
+    ("f", "fit"),
+    ("q", "quit"),
+];
+
+/// the values that are shown below a simulation, e.g. the number of resting
+/// grains of sand.
+pub trait Counters {
+    fn counters(&self) -> Vec<(&'static str, String)>;
+}
+
+#[cfg(feature = "day9")]
+impl Counters for RopeSimulation {
+    fn counters(&self) -> Vec<(&'static str, String)> {
+        vec![(
+            "tail positions",
+            self.rope().num_tail_positions().to_string(),
+        )]
+    }
+}
+
+#[cfg(feature = "day10")]
+impl Counters for CpuSimulation {
+    fn counters(&self) -> Vec<(&'static str, String)> {
+        let cpu = self.cpu();
+        vec![
+            ("cycle", cpu.cycle().to_string()),
+            ("x", cpu.x_register().to_string()),
+            ("signal strength", cpu.signal_strength().to_string()),
+        ]
+    }
+}
+
+#[cfg(feature = "day14")]
+impl Counters for Sandbox {
+    fn counters(&self) -> Vec<(&'static str, String)> {
+        vec![("resting sand", self.steps().to_string())]
+    }
+}
+
+/// plays a simulation forwards and backwards.
+pub struct Player<S> {
+    simulation: S,
+    /// the state after every [`SNAPSHOT_INTERVAL`] steps since the start,
+    /// starting with the initial one.
+    snapshots: Vec<S>,
+    /// the number of steps since the start.
+    position: usize,
+    playing: bool,
+    reverse: bool,
+    /// the number of steps per tick.
+    speed: usize,
+}
+
+impl<S: Simulation + Clone> Player<S> {
+    pub fn new(simulation: S) -> Self {
+        Self {
+            snapshots: vec![simulation.clone()],
+            simulation,
+            position: 0,
+            playing: false,
+            reverse: false,
+            speed: 1,
+        }
+    }
+
+    pub fn simulation(&self) -> &S {
+        &self.simulation
+    }
+
+    /// the number of steps since the start.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    pub fn is_reverse(&self) -> bool {
+        self.reverse
+    }
+
+    pub fn speed(&self) -> usize {
+        self.speed
+    }
+
+    /// takes the next step. returns `false` if the simulation is finished.
+    pub fn step(&mut self) -> bool {
+        if !self.simulation.step() {
+            return false;
+        }
+
+        self.position += 1;
+        if self.position == self.snapshots.len() * SNAPSHOT_INTERVAL {
+            self.snapshots.push(self.simulation.clone());
+        }
+        true
+    }
+
+    /// goes back by one step. returns `false` at the start.
+    pub fn step_back(&mut self) -> bool {
+        match self.position.checked_sub(1) {
+            Some(position) => {
+                self.seek(position);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// goes to the state after `position` steps, or to the end if the
+    /// simulation is finished before.
+    pub fn seek(&mut self, position: usize) {
+        if position < self.position {
+            let snapshot = position / SNAPSHOT_INTERVAL;
+            self.simulation = self.snapshots[snapshot].clone();
+            self.position = snapshot * SNAPSHOT_INTERVAL;
+        }
+        while self.position < position && self.step() {}
+    }
+
+    pub fn toggle_playing(&mut self) {
+        self.playing = !self.playing;
+    }
+
+    pub fn toggle_reverse(&mut self) {
+        self.reverse = !self.reverse;
+    }
+
+    pub fn faster(&mut self) {
+        self.speed = (self.speed * 2).min(MAX_SPEED);
+    }
+
+    pub fn slower(&mut self) {
+        self.speed = (self.speed / 2).max(1);
+    }
+
+    /// takes `speed` steps in the current direction if playing, and pauses at
+    /// either end.
+    pub fn tick(&mut self) {
+        if !self.playing {
+            return;
+        }
+
+        if self.reverse {
+            self.seek(self.position.saturating_sub(self.speed));
+            self.playing = self.position > 0;
+        }
+        else {
+            self.seek(self.position + self.speed);
+            self.playing = !self.simulation.is_finished();
+        }
+    }
+}
+
+/// the part of a rendering that is shown.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Viewport {
+    pub center: Point2<i32>,
+    /// the number of cells in each direction that are shown by a single
+    /// character.
+    pub zoom: i32,
+}
+
+impl Viewport {
+    /// the smallest zoom that shows all of `area` in `width` by `height`
+    /// characters, centered on it.
+    pub fn fit(area: BoundingBox<i32>, width: u16, height: u16) -> Self {
+        let zoom = |size: i32, characters: u16| {
+            let characters = i32::from(characters.max(1));
+            (size + characters - 1) / characters
+        };
+        Self {
+            center: Point2::new(
+                area.min.x + area.width() / 2,
+                area.min.y + area.height() / 2,
+            ),
+            zoom: zoom(area.width(), width)
+                .max(zoom(area.height(), height))
+                .clamp(1, MAX_ZOOM),
+        }
+    }
+
+    /// the cells that are shown in `width` by `height` characters.
+    pub fn area(&self, width: u16, height: u16) -> BoundingBox<i32> {
+        let size = Point2::new(i32::from(width), i32::from(height)) * self.zoom;
+        let min = self.center - Point2::new(size.x / 2, size.y / 2);
+        BoundingBox {
+            min,
+            max: min + size - Point2::new(1, 1),
+        }
+    }
+
+    /// moves the view by `dx` and `dy` characters.
+    pub fn scroll(&mut self, dx: i32, dy: i32) {
+        self.center += Point2::new(dx, dy) * self.zoom;
+    }
+
+    pub fn zoom_in(&mut self) {
+        self.zoom = (self.zoom / 2).max(1);
+    }
+
+    pub fn zoom_out(&mut self) {
+        self.zoom = (self.zoom * 2).min(MAX_ZOOM);
+    }
+}
+
+/// shows a frame with `zoom` by `zoom` cells per character. every character
+/// shows the brightest cell of its block, so that the interesting parts
+/// stay visible over the dark background when zoomed out.
+struct View<'a> {
+    frame: &'a Frame,
+    zoom: usize,
+    truecolor: bool,
+}
+
+impl View<'_> {
+    fn cell(&self, x: usize, y: usize) -> Option<Cell> {
+        (y * self.zoom..(y + 1) * self.zoom)
+            .flat_map(|y| (x * self.zoom..(x + 1) * self.zoom).map(move |x| (x, y)))
+            .filter_map(|(x, y)| self.frame.get(x, y))
+            .max_by_key(|cell| {
+                let Rgb(r, g, b) = cell.color;
+                u16::from(r) + u16::from(g) + u16::from(b)
+            })
+    }
+}
+
+impl Widget for View<'_> {
+    fn render(self, area: Rect, buffer: &mut Buffer) {
+        for y in 0..area.height {
+            for x in 0..area.width {
+                let (Some(cell), Some(target)) = (
+                    self.cell(x.into(), y.into()),
+                    buffer.cell_mut((area.x + x, area.y + y)),
+                )
+                else {
+                    continue;
+                };
+                target
+                    .set_char(cell.glyph)
+                    .set_fg(color(cell.color, self.truecolor));
+            }
+        }
+    }
+}
+
+/// converts `rgb` to a terminal color, using the 6x6x6 color cube of the 256
+/// standard colors if the terminal doesn't support 24-bit colors.
+fn color(rgb: Rgb, truecolor: bool) -> Color {
+    if truecolor {
+        return Color::Rgb(rgb.0, rgb.1, rgb.2);
+    }
+
+    let level = |c: u8| (u16::from(c) * 5 + 127) / 255;
+    Color::Indexed((16 + 36 * level(rgb.0) + 6 * level(rgb.1) + level(rgb.2)) as u8)
+}
+
+/// whether the terminal claims to support 24-bit colors.
+fn supports_truecolor() -> bool {
+    env::var("COLORTERM").is_ok_and(|value| value == "truecolor" || value == "24bit")
+}
+
+/// something the user wants to do.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Action {
+    TogglePlaying,
+    ToggleReverse,
+    StepForward,
+    StepBack,
+    Start,
+    End,
+    Faster,
+    Slower,
+    Scroll(i32, i32),
+    ZoomIn,
+    ZoomOut,
+    Fit,
+    Quit,
+}
+
+impl Action {
+    /// the action bound to `key`, if any.
+    pub fn from_key(key: KeyEvent) -> Option<Self> {
+        let action = match key.code {
+            KeyCode::Char(' ') | KeyCode::Char('p') => Self::TogglePlaying,
+            KeyCode::Char('r') => Self::ToggleReverse,
+            KeyCode::Char('n') | KeyCode::Char('.') => Self::StepForward,
+            KeyCode::Char('b') | KeyCode::Char(',') => Self::StepBack,
+            KeyCode::Home | KeyCode::Char('0') => Self::Start,
+            KeyCode::End | KeyCode::Char('$') => Self::End,
+            KeyCode::Char(']') => Self::Faster,
+            KeyCode::Char('[') => Self::Slower,
+            KeyCode::Left | KeyCode::Char('h') => Self::Scroll(-1, 0),
+            KeyCode::Right | KeyCode::Char('l') => Self::Scroll(1, 0),
+            KeyCode::Up | KeyCode::Char('k') => Self::Scroll(0, -1),
+            KeyCode::Down | KeyCode::Char('j') => Self::Scroll(0, 1),
+            KeyCode::Char('+') | KeyCode::Char('=') => Self::ZoomIn,
+            KeyCode::Char('-') => Self::ZoomOut,
+            KeyCode::Char('f') => Self::Fit,
+            KeyCode::Char('q') | KeyCode::Esc => Self::Quit,
+            _ => return None,
+        };
+        Some(action)
+    }
+}
+
+/// the state of the viewer.
+pub struct App<S> {
+    title: String,
+    player: Player<S>,
+    /// the number of steps until the simulation is finished, and the area it
+    /// covers by then.
+    total_steps: usize,
+    final_bounds: BoundingBox<i32>,
+    /// fitted to `final_bounds` on the next draw if `None`.
+    viewport: Option<Viewport>,
+    truecolor: bool,
+    quit: bool,
+}
+
+impl<S: Simulation + Render + Counters + Clone> App<S> {
+    pub fn new(title: impl Into<String>, simulation: S, truecolor: bool) -> Self {
+        let mut finished = simulation.clone();
+        let total_steps = finished.run();
+
+        Self {
+            title: title.into(),
+            player: Player::new(simulation),
+            total_steps,
+            final_bounds: finished.bounds(),
+            viewport: None,
+            truecolor,
+            quit: false,
+        }
+    }
+
+    pub fn player(&self) -> &Player<S> {
+        &self.player
+    }
+
+    pub fn viewport(&self) -> Option<Viewport> {
+        self.viewport
+    }
+
+    pub fn apply(&mut self, action: Action) {
+        match action {
+            Action::TogglePlaying => self.player.toggle_playing(),
+            Action::ToggleReverse => self.player.toggle_reverse(),
+            Action::StepForward => {
+                self.player.step();
+            }
+            Action::StepBack => {
+                self.player.step_back();
+            }
+            Action::Start => self.player.seek(0),
+            Action::End => self.player.seek(self.total_steps),
+            Action::Faster => self.player.faster(),
+            Action::Slower => self.player.slower(),
+            Action::Scroll(dx, dy) => {
+                if let Some(viewport) = &mut self.viewport {
+                    viewport.scroll(dx, dy);
+                }
+            }
+            Action::ZoomIn => {
+                if let Some(viewport) = &mut self.viewport {
+                    viewport.zoom_in();
+                }
+            }
+            Action::ZoomOut => {
+                if let Some(viewport) = &mut self.viewport {
+                    viewport.zoom_out();
+                }
+            }
+            Action::Fit => self.viewport = None,
+            Action::Quit => self.quit = true,
+        }
+    }
+
+    pub fn tick(&mut self) {
+        self.player.tick();
+    }
+
+    /// the line with the position, the state of the player and the counters
+    /// of the simulation.
+    fn status(&self) -> String {
+        let player = &self.player;
+        let state = if player.is_playing() {
+            "playing"
+        }
+        else if player.simulation().is_finished() {
+            "finished"
+        }
+        else {
+            "paused"
+        };
+        let direction = if player.is_reverse() { " reverse" } else { "" };
+
+        let mut status = format!(
+            "step {}/{} | {}{} x{}",
+            player.position(),
+            self.total_steps,
+            state,
+            direction,
+            player.speed()
+        );
+        for (name, value) in player.simulation().counters() {
+            status.push_str(&format!(" | {}: {}", name, value));
+        }
+        status
+    }
+
+    pub fn draw(&mut self, frame: &mut ratatui::Frame) {
+        let [view_area, status_area, help_area] = Layout::vertical([
+            Constraint::Min(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        let block = Block::bordered().title(self.title.as_str());
+        let inner = block.inner(view_area);
+        frame.render_widget(block, view_area);
+
+        let viewport = *self
+            .viewport
+            .get_or_insert_with(|| Viewport::fit(self.final_bounds, inner.width, inner.height));
+        let rendered = self
+            .player
+            .simulation()
+            .render_area(viewport.area(inner.width, inner.height));
+        frame.render_widget(
+            View {
+                frame: &rendered,
+                zoom: viewport.zoom as usize,
+                truecolor: self.truecolor,
+            },
+            inner,
+        );
+
+        frame.render_widget(Paragraph::new(self.status()), status_area);
+        let keys = KEYS.iter().flat_map(|(key, description)| {
+            [
+                Span::styled(*key, Style::new().add_modifier(Modifier::BOLD)),
+                Span::raw(format!(" {}  ", description)),
+            ]
+        });
+        frame.render_widget(
+            Paragraph::new(Line::from_iter(keys)).style(Style::new().fg(Color::DarkGray)),
+            help_area,
+        );
+    }
+
+    /// draws and handles keys until the user quits.
+    pub fn run<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> io::Result<()> {
+        let mut last_tick = Instant::now();
+
+        while !self.quit {
+            terminal.draw(|frame| self.draw(frame))?;
+
+            let timeout = TICK.saturating_sub(last_tick.elapsed());
+            if event::poll(timeout)? {
+                if let Event::Key(key) = event::read()? {
+                    if key.kind == KeyEventKind::Press {
+                        if let Some(action) = Action::from_key(key) {
+                            self.apply(action);
+                        }
+                    }
+                }
+            }
+
+            if last_tick.elapsed() >= TICK {
+                self.tick();
+                last_tick = Instant::now();
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// shows `simulation` in the terminal until the user quits.
+pub fn run<S: Simulation + Render + Counters + Clone>(
+    title: impl Into<String>,
+    simulation: S,
+) -> io::Result<()> {
+    let mut app = App::new(title, simulation, supports_truecolor());
+
+    let mut terminal = ratatui::try_init()?;
+    let result = app.run(&mut terminal);
+    ratatui::restore();
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::backend::TestBackend;
+
+    use super::*;
+
+    const EMPTY: Cell = Cell::new('.', Rgb(16, 16, 16));
+    const WALKER: Cell = Cell::new('@', Rgb(255, 255, 255));
+
+    /// walks to the right until it reaches `limit`.
+    #[derive(Clone, Debug, PartialEq)]
+    struct Walker {
+        x: i32,
+        limit: i32,
+    }
+
+    impl Simulation for Walker {
+        fn step(&mut self) -> bool {
+            if self.is_finished() {
+                return false;
+            }
+            self.x += 1;
+            true
+        }
+
+        fn steps(&self) -> usize {
+            self.x as usize
+        }
+
+        fn is_finished(&self) -> bool {
+            self.x >= self.limit
+        }
+    }
+
+    impl Render for Walker {
+        fn bounds(&self) -> BoundingBox<i32> {
+            BoundingBox {
+                min: Point2::zero(),
+                max: Point2::new(self.x, 0),
+            }
+        }
+
+        fn render_area(&self, area: BoundingBox<i32>) -> Frame {
+            let mut frame = Frame::for_area(area, EMPTY);
+            frame.set_point(area, Point2::new(self.x, 0), WALKER);
+            frame
+        }
+    }
+
+    impl Counters for Walker {
+        fn counters(&self) -> Vec<(&'static str, String)> {
+            vec![("x", self.x.to_string())]
+        }
+    }
+
+    fn walker(limit: i32) -> Walker {
+        Walker { x: 0, limit }
+    }
+
+    #[test]
+    fn rewind() {
+        let mut player = Player::new(walker(1000));
+        player.seek(600);
+        assert_eq!(player.simulation().x, 600);
+        assert_eq!(player.snapshots.len(), 3);
+
+        assert!(player.step_back());
+        assert_eq!(player.simulation().x, 599);
+        player.seek(10);
+        assert_eq!((player.position(), player.simulation().x), (10, 10));
+
+        player.seek(2000);
+        assert_eq!(player.position(), 1000);
+        assert_eq!(player.snapshots.len(), 4);
+
+        player.seek(0);
+        assert!(!player.step_back());
+        assert_eq!(player.simulation(), &walker(1000));
+    }
+
+    #[test]
+    fn play() {
+        let mut player = Player::new(walker(5));
+        player.faster();
+        player.tick();
+        assert_eq!(player.position(), 0);
+
+        player.toggle_playing();
+        player.tick();
+        player.tick();
+        assert_eq!(player.position(), 4);
+        player.tick();
+        assert_eq!(player.position(), 5);
+        assert!(!player.is_playing());
+
+        player.toggle_reverse();
+        player.toggle_playing();
+        player.slower();
+        player.tick();
+        assert_eq!(player.position(), 4);
+    }
+
+    #[test]
+    fn viewport() {
+        let area = BoundingBox {
+            min: Point2::new(-10, 0),
+            max: Point2::new(89, 9),
+        };
+        let mut viewport = Viewport::fit(area, 40, 10);
+        assert_eq!(viewport.zoom, 3);
+        let shown = viewport.area(40, 10);
+        assert!(shown.contains(area.min) && shown.contains(area.max));
+
+        viewport.zoom_in();
+        assert_eq!(viewport.area(40, 10).width(), 40);
+        viewport.scroll(2, -1);
+        assert_eq!(viewport.center, Point2::new(42, 4));
+    }
+
+    #[test]
+    fn colors() {
+        assert_eq!(color(Rgb(1, 2, 3), true), Color::Rgb(1, 2, 3));
+        assert_eq!(color(Rgb(0, 0, 0), false), Color::Indexed(16));
+        assert_eq!(color(Rgb(255, 0, 0), false), Color::Indexed(196));
+        assert_eq!(color(Rgb(255, 255, 255), false), Color::Indexed(231));
+    }
+
+    /// the walker stays visible when zoomed out, and the counters are shown.
+    #[test]
+    fn draw() {
+        let mut app = App::new("walker", walker(100), false);
+        app.apply(Action::End);
+        let mut terminal = Terminal::new(TestBackend::new(40, 5)).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+
+        assert_eq!(app.viewport().unwrap().zoom, 3);
+        let text = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect::<String>();
+        assert!(text.contains("walker"));
+        assert!(text.contains('@'));
+        assert!(text.contains("step 100/100 | finished x1 | x: 100"));
+    }
+}